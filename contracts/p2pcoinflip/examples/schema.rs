@@ -5,17 +5,28 @@ use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use p2pcoinflip::{
     msg::{
-        AddrPendingBetsResponse, AssetFilter, ConfigResponse, ExecuteMsg, HistoricalBetResponse,
-        InstantiateCoinLimitMsg, InstantiateMsg, LiquidationFilter, OngoingBetResponse,
-        PendingBetResponse, PendingBetsFilter, PendingBetsSort, QueryMsg, TotalPendingBetsResponse,
+        AddrPendingBetsResponse, AssetFilter, BetState, CompletedAtFilter, ConfigResponse,
+        CreatorReliabilityResponse, DenomFeeTier, DenomPlayerStats, ExecuteMsg, FeeTierResponse,
+        HistoricalBetResponse, HistoricalBetResult, HistoricalBetRole, HistoricalBetsFilter,
+        HistoricalBetsTotals, InstantiateMsg, JackpotResponse, JackpotWinnersResponse,
+        LeaderboardPeriod, LeaderboardResponse, LiquidationFilter, OffersResponse,
+        OngoingBetResponse, PendingBetResponse, PendingBetsFilter, PendingBetsSort,
+        PlayerLimitsResponse, PlayerStatsResponse, QueryMsg, ReferralResponse,
+        TotalPendingBetsResponse, WagerLimitUsage,
     },
     state::{
-        AddrPendingBets, CoinLimit, Config, FlipSide, GameOutcome, HistoricalBet, OngoingBet,
-        PendingBet,
+        AddrPendingBets, BetLocation, Config, CreatorBondPercent, CreatorReliability, FeeTier,
+        FlipSide, GameMode, GameOutcome, HistoricalBet, JackpotWinner, Leaderboard,
+        LeaderboardEntry, LeaderboardMetric, LimitUsage, Odds, OngoingBet, PendingBet,
+        PendingWagerLimit, PlayerLimits, PlayerStats, PoolFill, Rematch, StandingOffer, WagerLimit,
     },
 };
 
-use tefiluck::asset::Asset;
+use tefiluck::{
+    asset::Asset,
+    config::{CoinLimit, InstantiateCoinLimitMsg},
+    payout::{Payout, PayoutRole},
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -28,13 +39,28 @@ fn main() {
 
     export_schema(&schema_for!(Config), &out_dir);
     export_schema(&schema_for!(CoinLimit), &out_dir);
+    export_schema(&schema_for!(FeeTier), &out_dir);
+    export_schema(&schema_for!(CreatorBondPercent), &out_dir);
     export_schema(&schema_for!(Asset), &out_dir);
     export_schema(&schema_for!(FlipSide), &out_dir);
     export_schema(&schema_for!(AddrPendingBets), &out_dir);
     export_schema(&schema_for!(PendingBet), &out_dir);
     export_schema(&schema_for!(OngoingBet), &out_dir);
+    export_schema(&schema_for!(PoolFill), &out_dir);
+    export_schema(&schema_for!(StandingOffer), &out_dir);
+    export_schema(&schema_for!(Rematch), &out_dir);
+    export_schema(&schema_for!(GameMode), &out_dir);
     export_schema(&schema_for!(GameOutcome), &out_dir);
+    export_schema(&schema_for!(Odds), &out_dir);
     export_schema(&schema_for!(HistoricalBet), &out_dir);
+    export_schema(&schema_for!(Payout), &out_dir);
+    export_schema(&schema_for!(PayoutRole), &out_dir);
+    export_schema(&schema_for!(PlayerStats), &out_dir);
+    export_schema(&schema_for!(BetLocation), &out_dir);
+    export_schema(&schema_for!(CreatorReliability), &out_dir);
+    export_schema(&schema_for!(Leaderboard), &out_dir);
+    export_schema(&schema_for!(LeaderboardEntry), &out_dir);
+    export_schema(&schema_for!(LeaderboardMetric), &out_dir);
 
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
@@ -43,9 +69,33 @@ fn main() {
     export_schema(&schema_for!(OngoingBetResponse), &out_dir);
     export_schema(&schema_for!(AddrPendingBetsResponse), &out_dir);
     export_schema(&schema_for!(PendingBetResponse), &out_dir);
+    export_schema(&schema_for!(CreatorReliabilityResponse), &out_dir);
     export_schema(&schema_for!(TotalPendingBetsResponse), &out_dir);
+    export_schema(&schema_for!(BetState), &out_dir);
+    export_schema(&schema_for!(PlayerStatsResponse), &out_dir);
+    export_schema(&schema_for!(DenomPlayerStats), &out_dir);
+    export_schema(&schema_for!(LeaderboardPeriod), &out_dir);
+    export_schema(&schema_for!(LeaderboardResponse), &out_dir);
+    export_schema(&schema_for!(OffersResponse), &out_dir);
+    export_schema(&schema_for!(ReferralResponse), &out_dir);
+    export_schema(&schema_for!(FeeTierResponse), &out_dir);
+    export_schema(&schema_for!(DenomFeeTier), &out_dir);
+    export_schema(&schema_for!(JackpotResponse), &out_dir);
+    export_schema(&schema_for!(JackpotWinnersResponse), &out_dir);
+    export_schema(&schema_for!(JackpotWinner), &out_dir);
+    export_schema(&schema_for!(PlayerLimitsResponse), &out_dir);
+    export_schema(&schema_for!(WagerLimitUsage), &out_dir);
+    export_schema(&schema_for!(PlayerLimits), &out_dir);
+    export_schema(&schema_for!(WagerLimit), &out_dir);
+    export_schema(&schema_for!(PendingWagerLimit), &out_dir);
+    export_schema(&schema_for!(LimitUsage), &out_dir);
     export_schema(&schema_for!(AssetFilter), &out_dir);
     export_schema(&schema_for!(LiquidationFilter), &out_dir);
     export_schema(&schema_for!(PendingBetsSort), &out_dir);
     export_schema(&schema_for!(PendingBetsFilter), &out_dir);
+    export_schema(&schema_for!(HistoricalBetsFilter), &out_dir);
+    export_schema(&schema_for!(HistoricalBetRole), &out_dir);
+    export_schema(&schema_for!(HistoricalBetResult), &out_dir);
+    export_schema(&schema_for!(CompletedAtFilter), &out_dir);
+    export_schema(&schema_for!(HistoricalBetsTotals), &out_dir);
 }
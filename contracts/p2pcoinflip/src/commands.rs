@@ -1,23 +1,45 @@
-use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Response, StdResult, Storage, Uint64};
+use cosmwasm_std::{
+    Addr, Api, Coin, CosmosMsg, DepsMut, Env, Event, MessageInfo, QuerierWrapper, Response,
+    StdError, StdResult, Storage, Timestamp, Uint128, Uint64,
+};
+
+use cw0::Duration;
 
 use crate::{
     error::ContractError,
+    msg::{PendingBetsFilter, UpdateConfigMsg},
     state::{
-        load_config, load_historical_bets, load_ongoing_bet, load_pending_bets,
-        load_pending_bets_count, remove_ongoing_bet, store_config, store_historical_bets,
-        store_ongoing_bet, store_pending_bets, store_pending_bets_count, CoinLimit, Config,
-        FlipSide, GameOutcome, HistoricalBet, OngoingBet,
+        add_referral_earnings, append_historical_bet, append_jackpot_winner, find_best_match,
+        find_matching_offer, jackpot_roll, load_config, load_creator_reliability,
+        load_epoch_player_stats, load_jackpot, load_leaderboard, load_offer, load_ongoing_bet,
+        load_pending_bets, load_pending_bets_count, load_player_limits, load_player_stats,
+        load_referral_earnings, load_window_usage, load_window_volume, may_load_referrer,
+        next_offer_id, outcome_from_passphrase, outcome_from_randomness, read_offers_by_addr,
+        remove_offer, remove_oldest_historical_bets, remove_ongoing_bet, remove_referral_earnings,
+        store_bet_location, store_config, store_creator_reliability, store_epoch_player_stats,
+        store_jackpot, store_leaderboard, store_offer, store_ongoing_bet, store_pending_bets,
+        store_pending_bets_count, store_player_limits, store_player_stats, store_referrer,
+        update_daily_usage, usage_day, AddrPendingBets, BetLocation, Config, FlipSide, GameMode,
+        GameOutcome, HistoricalBet, JackpotWinner, LeaderboardMetric, Odds, OngoingBet, PendingBet,
+        PendingWagerLimit, PlaceBetParams, PlayerStats, PoolFill, Rematch, StandingOffer,
+        UpdatePendingBetParams, WagerLimit,
     },
 };
 
-use tefiluck::{asset::Asset, hash::calculate_sha256};
+use tefiluck::{
+    asset::Asset,
+    drand::{beacon_randomness, verify_beacon},
+    hash::calculate_sha256,
+    history::load_historical_bets_state,
+    payout::{send_payout, Payout, PayoutRole},
+};
 
 pub fn place_bet(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    signature: String,
-    blocks_until_liquidation: u64,
+    params: PlaceBetParams,
+    referrer: Option<Addr>,
 ) -> Result<Response, ContractError> {
     let config = load_config(deps.storage)?;
     let mut pending_bets = load_pending_bets(deps.storage, &info.sender)?;
@@ -26,40 +48,143 @@ pub fn place_bet(
         "{}{}{}",
         env.block.height,
         env.block.time.to_string(),
-        &signature
+        &params.signature
     ));
-    let asset = Asset::from_coins(info.funds)?;
+    // creator bond is sent along with the bet amount
+    let mut asset = Asset::from_coins(info.funds)?;
+    let creator_bond = Asset {
+        denom: asset.denom.clone(),
+        amount: params.creator_bond,
+    };
+    asset.checked_sub(&creator_bond)?;
+
+    config.validate_place_bet_inputs(
+        &params.until_liquidation,
+        pending_bets.bets.len(),
+        &asset,
+        &params.odds,
+        params.relayer_tip,
+        creator_bond.amount,
+    )?;
+    use_wager_limits(deps.storage, env.block.time, &info.sender, &asset)?;
 
-    config.validate_place_bet_inputs(blocks_until_liquidation, pending_bets.bets.len(), &asset)?;
+    // anyone could copy the passphrase from the mempool, so only a named relayer is tipped
+    if !params.relayer_tip.is_zero() && params.relayer.is_none() {
+        return Err(StdError::generic_err("relayer_tip requires a relayer").into());
+    }
+
+    // xor of two revealed sides only makes sense for a coin
+    if params.mode == GameMode::DualCommit && params.odds != Odds::coinflip() {
+        return Err(StdError::generic_err("dual commit mode supports coinflip odds only").into());
+    }
+
+    // closing a partly filled pool matches the creator stake one to one with the fills
+    if params.mode == GameMode::Pool && params.odds != Odds::coinflip() {
+        return Err(StdError::generic_err("pool mode supports coinflip odds only").into());
+    }
 
     pending_bets.store_bet(
         deps.api.addr_canonicalize(&info.sender.to_string())?,
         bet_id.clone(),
-        signature.clone(),
-        blocks_until_liquidation,
+        params.clone(),
         asset.clone(),
         env.block.time,
     )?;
 
     store_pending_bets(deps.storage, &info.sender, &pending_bets)?;
+    store_bet_location(
+        deps.storage,
+        &bet_id,
+        &BetLocation::Pending {
+            owner: info.sender.clone(),
+        },
+    )?;
+    let referrer = bind_referrer(deps.storage, &info.sender, referrer)?;
 
     let current_bets_count = load_pending_bets_count(deps.storage)?;
     let bets_count = current_bets_count.checked_add(Uint64::new(1u64))?;
     store_pending_bets_count(deps.storage, bets_count)?;
 
-    Ok(Response::new().add_attributes(vec![
+    let response = Response::new().add_attributes(vec![
         ("action", "place_bet"),
         ("sender", &info.sender.to_string()),
         ("bet_id", &bet_id),
-        ("signature", &signature),
-        (
-            "blocks_until_liquidation",
-            &blocks_until_liquidation.to_string(),
-        ),
+        ("signature", &params.signature),
+        ("mode", &params.mode.to_string()),
+        ("until_liquidation", &params.until_liquidation.to_string()),
         ("denom", &asset.denom),
         ("amount", &asset.amount.to_string()),
+        ("relayer_tip", &params.relayer_tip.to_string()),
+        (
+            "relayer",
+            &params
+                .relayer
+                .as_ref()
+                .map(|r| r.to_string())
+                .unwrap_or_default(),
+        ),
+        ("creator_bond", &creator_bond.amount.to_string()),
         ("created_at", &env.block.time.seconds().to_string()),
-    ]))
+        ("sides", &params.odds.sides.to_string()),
+        ("responder_picks", &params.odds.responder_picks.to_string()),
+        (
+            "referrer",
+            &referrer.map(|r| r.to_string()).unwrap_or_default(),
+        ),
+    ]);
+
+    // bet is accepted right away by the oldest standing offer it fits
+    // offers of owners who are out of their limits are passed over
+    let pending_bet = pending_bets.find_by_id(&bet_id)?;
+    let stake = pending_bet.responder_stake_asset();
+    let mut offer = match find_matching_offer(deps.storage, &pending_bet, &info.sender, |o| {
+        check_player_limits(deps.storage, env.block.time, &o.owner, &stake).is_ok()
+    })? {
+        Some(o) => o,
+        None => return Ok(response),
+    };
+    offer.asset.checked_sub(&stake)?;
+
+    // offer is closed once it cannot cover its min amount anymore
+    let mut messages = vec![];
+    if offer.asset.amount < offer.min_amount {
+        remove_offer(deps.storage, offer.id)?;
+        if !offer.asset.amount.is_zero() {
+            let (refund_msg, _) = send_payout(
+                &deps.querier,
+                &offer.owner,
+                PayoutRole::Responder,
+                &offer.asset,
+            )?;
+            messages.push(refund_msg);
+        }
+    } else {
+        store_offer(deps.storage, &offer)?;
+    }
+
+    let offer_info = MessageInfo {
+        sender: offer.owner.clone(),
+        funds: vec![Coin {
+            denom: stake.denom,
+            amount: stake.amount,
+        }],
+    };
+    let accept_response = accept_bet(
+        deps,
+        env,
+        offer_info,
+        info.sender,
+        bet_id,
+        Some(vec![offer.side.u8()]),
+        None,
+    )?;
+
+    Ok(response.add_messages(messages).add_event(
+        Event::new("match_offer")
+            .add_attribute("offer_id", offer.id.to_string())
+            .add_attribute("offer_remaining", offer.asset.amount.to_string())
+            .add_attributes(accept_response.attributes),
+    ))
 }
 
 pub fn respond_bet(
@@ -69,6 +194,66 @@ pub fn respond_bet(
     bet_owner: Addr,
     bet_id: String,
     side: u8,
+    referrer: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let referrer = bind_referrer(deps.storage, &info.sender, referrer)?;
+    let response = accept_bet(deps, env, info, bet_owner, bet_id, Some(vec![side]), None)?;
+    Ok(response.add_attribute(
+        "referrer",
+        referrer.map(|r| r.to_string()).unwrap_or_default(),
+    ))
+}
+
+// accepts the first pending bet in filter order that fits the attached funds and side
+pub fn respond_best_match(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    filter: PendingBetsFilter,
+    side: u8,
+) -> Result<Response, ContractError> {
+    let stake = Asset::from_coins(info.funds.clone())?;
+    let bet = match find_best_match(deps.storage, deps.api, &filter, &info.sender, &stake, side)? {
+        Some(b) => b,
+        None => return Err(ContractError::NoMatchingBet {}),
+    };
+
+    let bet_owner = deps.api.addr_humanize(&bet.owner)?;
+    accept_bet(deps, env, info, bet_owner, bet.id, Some(vec![side]), None)
+}
+
+// responder of an N-sided bet picks the set of outcomes they win on
+pub fn respond_dice_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_owner: Addr,
+    bet_id: String,
+    picks: Vec<u8>,
+) -> Result<Response, ContractError> {
+    accept_bet(deps, env, info, bet_owner, bet_id, Some(picks), None)
+}
+
+// responder commits a hashed side instead of picking it in the clear
+pub fn commit_respond_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_owner: Addr,
+    bet_id: String,
+    signature: String,
+) -> Result<Response, ContractError> {
+    accept_bet(deps, env, info, bet_owner, bet_id, None, Some(signature))
+}
+
+fn accept_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_owner: Addr,
+    bet_id: String,
+    responder_picks: Option<Vec<u8>>,
+    responder_signature: Option<String>,
 ) -> Result<Response, ContractError> {
     if info.sender == bet_owner {
         return Err(ContractError::ForbiddenToPlayVSYourself {});
@@ -81,29 +266,88 @@ pub fn respond_bet(
         Err(_) => return Err(ContractError::BetWasCancledOrAccepted {}),
     };
 
-    let mut asset = Asset::from_coins(info.funds)?;
-    if asset.ne(&pending_bet.asset) {
-        return Err(ContractError::ResponderAssetMismatch {});
+    // pool bets are filled in parts instead
+    if pending_bet.mode == GameMode::Pool {
+        return Err(ContractError::GameModeMismatch {});
     }
 
-    asset.checked_add(&pending_bet.asset)?;
+    if let Some(rematch) = &pending_bet.rematch {
+        if info.sender.ne(&rematch.opponent) {
+            return Err(ContractError::RematchIsPrivate {});
+        }
+        // past expiry the winner was promised a payout, only a decline closes it
+        if rematch.expires.is_expired(&env.block) {
+            return Err(ContractError::RematchExpired {});
+        }
+    }
 
-    let flip_side = FlipSide::from_u8(side)?;
-    let ongoing_bet = OngoingBet::new(
-        pending_bet.signature,
+    // only dual commit bets are responded with a hashed side
+    let is_dual_commit = pending_bet.mode == GameMode::DualCommit;
+    if is_dual_commit != responder_signature.is_some() {
+        return Err(ContractError::GameModeMismatch {});
+    }
+
+    // reusing creator commitment would let responder reveal the same passphrase
+    if responder_signature.as_ref() == Some(&pending_bet.signature) {
+        return Err(ContractError::SignatureMismatch {});
+    }
+
+    let responder_picks = responder_picks.unwrap_or_default();
+    let responder_side = match (
+        pending_bet.odds == Odds::coinflip(),
+        responder_picks.first(),
+    ) {
+        (true, Some(side)) => Some(FlipSide::from_u8(*side)?),
+        _ => None,
+    };
+    if responder_signature.is_none() {
+        pending_bet.odds.validate_picks(&responder_picks)?;
+    }
+
+    let asset = Asset::from_coins(info.funds)?;
+    let responder_stake = pending_bet.responder_stake_asset();
+    if asset.ne(&responder_stake) {
+        return Err(ContractError::ResponderAssetMismatch {});
+    }
+    use_wager_limits(deps.storage, env.block.time, &info.sender, &responder_stake)?;
+
+    let creator_stake = pending_bet.asset.amount;
+    let mut ongoing_bet = OngoingBet::new(
+        &pending_bet,
         bet_owner.clone(),
         info.sender.clone(),
-        flip_side,
-        asset,
-        pending_bet.blocks_until_liquidation,
-        config.blocks_for_responder_liquidation,
-        env.block.height,
-        env.block.time,
+        responder_side,
+        config.responder_liquidation_gap(&pending_bet.until_liquidation),
+        &env.block,
     )?;
+    if pending_bet.mode == GameMode::Beacon {
+        ongoing_bet.beacon_round = Some(config.beacon_round(env.block.time));
+    }
+    ongoing_bet.responder_picks = responder_picks;
+    ongoing_bet.responder_signature = responder_signature;
 
     pending_bets.remove_bet(&bet_id);
     store_pending_bets(deps.storage, &bet_owner, &pending_bets)?;
     store_ongoing_bet(deps.storage, bet_id.clone(), &ongoing_bet)?;
+    store_bet_location(deps.storage, &bet_id, &BetLocation::Ongoing {})?;
+
+    for (player, is_creator) in [(&bet_owner, true), (&info.sender, false)] {
+        record_player_stats(
+            deps.storage,
+            &config,
+            env.block.height,
+            player,
+            &ongoing_bet.asset.denom,
+            |stats| {
+                let stake = if is_creator {
+                    creator_stake
+                } else {
+                    responder_stake.amount
+                };
+                stats.add_game(is_creator, stake)
+            },
+        )?;
+    }
 
     let current_bets_count = load_pending_bets_count(deps.storage)?;
     let bets_count = current_bets_count.checked_sub(Uint64::new(1u64))?;
@@ -115,10 +359,13 @@ pub fn respond_bet(
         ("signature", &ongoing_bet.signature),
         ("bet_creator", &ongoing_bet.bet_creator.to_string()),
         ("bet_responder", &ongoing_bet.bet_responder.to_string()),
+        ("responder_side", &side_attr(&ongoing_bet.responder_side)),
         (
-            "responder_side",
-            &ongoing_bet.responder_side.u8().to_string(),
+            "responder_signature",
+            &ongoing_bet.responder_signature.clone().unwrap_or_default(),
         ),
+        ("mode", &ongoing_bet.mode.to_string()),
+        ("responder_picks", &picks_attr(&ongoing_bet.responder_picks)),
         ("denom", &ongoing_bet.asset.denom),
         ("amount", &ongoing_bet.asset.amount.to_string()),
         (
@@ -126,180 +373,205 @@ pub fn respond_bet(
             &ongoing_bet.started_at_block.to_string(),
         ),
         (
-            "blocks_until_liquidation",
-            &ongoing_bet.blocks_until_liquidation.to_string(),
+            "until_liquidation",
+            &ongoing_bet.until_liquidation.to_string(),
         ),
+        ("liquidation", &ongoing_bet.liquidation.to_string()),
         (
-            "liquidation_block",
-            &ongoing_bet.liquidation_block.to_string(),
-        ),
-        (
-            "responder_liquidation_blocks_gap",
-            &ongoing_bet.responder_liquidation_blocks_gap.to_string(),
+            "responder_liquidation",
+            &ongoing_bet.responder_liquidation.to_string(),
         ),
         ("created_at", &ongoing_bet.created_at.seconds().to_string()),
     ]))
 }
 
-pub fn resolve_bet(
+// responder takes a part of a pool bet, the game starts once the whole stake is filled
+pub fn fill_pool_bet(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    bet_owner: Addr,
     bet_id: String,
-    passphrase: String,
+    side: u8,
 ) -> Result<Response, ContractError> {
-    if !info.funds.is_empty() {
-        return Err(ContractError::ExecuteWithoutFunds {});
+    if info.sender == bet_owner {
+        return Err(ContractError::ForbiddenToPlayVSYourself {});
     }
 
     let config = load_config(deps.storage)?;
-    let ongoing_bet = match load_ongoing_bet(deps.storage, bet_id.clone()) {
+    let mut pending_bets = load_pending_bets(deps.storage, &bet_owner)?;
+    let mut pending_bet = match pending_bets.find_by_id(&bet_id) {
         Ok(b) => b,
-        Err(_) => return Err(ContractError::GameWasAlreadyLiquidated {}),
+        Err(_) => return Err(ContractError::BetWasCancledOrAccepted {}),
     };
 
-    if info.sender.ne(&ongoing_bet.bet_creator) {
-        return Err(ContractError::OnlyBetCreatorAllowedToResolve {});
+    if pending_bet.mode != GameMode::Pool {
+        return Err(ContractError::GameModeMismatch {});
     }
 
-    let signature = calculate_sha256(&passphrase);
-    if signature.ne(&ongoing_bet.signature) {
-        return Err(ContractError::SignatureMismatch {});
+    let side = FlipSide::from_u8(side)?;
+    if matches!(&pending_bet.pool_side, Some(pool_side) if pool_side.ne(&side)) {
+        return Err(ContractError::PoolSideMismatch {});
     }
 
-    let winner_addr = ongoing_bet.resolve_winner(&passphrase);
-    let mut bet_amount = ongoing_bet.asset;
-    let pot_size = bet_amount.clone();
-
-    let mut treasury_amount = bet_amount.take_percent(config.treasury_tax_percent)?;
-    let winner_amount = bet_amount.checked_sub(&treasury_amount)?;
-
-    remove_ongoing_bet(deps.storage, bet_id.clone());
-
-    let historical_bet = HistoricalBet::new(
-        bet_id.clone(),
-        ongoing_bet.bet_creator.to_string(),
-        ongoing_bet.bet_responder.to_string(),
-        winner_addr.to_string(),
-        None,
-        ongoing_bet.responder_side.clone(),
-        pot_size,
-        GameOutcome::Resolved,
-        ongoing_bet.created_at.seconds(),
-        env.block.time.seconds(),
-    );
-    save_historical_bet(deps.storage, &config, historical_bet.clone())?;
+    let asset = Asset::from_coins(info.funds)?;
+    if asset.denom.ne(&pending_bet.asset.denom) {
+        return Err(ContractError::ResponderAssetMismatch {});
+    }
 
-    let mut messages = vec![winner_amount.into_bank_msg(&deps.querier, &winner_addr)?];
+    let remaining = pending_bet
+        .responder_stake_asset()
+        .amount
+        .checked_sub(pending_bet.pool_filled()?)?;
+    if asset.amount > remaining {
+        return Err(ContractError::PoolFillExceedsRemainingStake {});
+    }
 
-    if !treasury_amount.amount.is_zero() {
-        messages.push(
-            treasury_amount
-                .into_bank_msg(&deps.querier, &deps.api.addr_humanize(&config.treasury)?)?,
-        );
+    // the last fill may be smaller than min limit, otherwise the pool could never be filled
+    let min_amount = config
+        .min_bet_amounts
+        .iter()
+        .find(|l| l.denom == asset.denom)
+        .map(|l| l.min_amount)
+        .unwrap_or_default();
+    if asset.amount < min_amount && asset.amount != remaining {
+        return Err(StdError::generic_err(
+            "provided amount less than min limit for provided asset",
+        )
+        .into());
+    }
+    use_wager_limits(deps.storage, env.block.time, &info.sender, &asset)?;
+
+    // repeated fills of the same responder are merged
+    let responder = &info.sender;
+    match pending_bet
+        .pool
+        .iter_mut()
+        .find(|f| f.responder.eq(responder))
+    {
+        Some(fill) => {
+            fill.amount = fill.amount.checked_add(asset.amount)?;
+            fill.filled_at_block = env.block.height;
+        }
+        None => {
+            if pending_bet.pool.len() as u64 >= config.max_pool_fills {
+                return Err(ContractError::PoolIsFull {});
+            }
+
+            pending_bet.pool.push(PoolFill {
+                responder: info.sender.clone(),
+                amount: asset.amount,
+                filled_at_block: env.block.height,
+            });
+        }
+    }
+    pending_bet.pool_side = Some(side.clone());
+
+    let remaining = remaining.checked_sub(asset.amount)?;
+    if remaining.is_zero() {
+        let ongoing_bet = start_pool_bet(
+            deps.storage,
+            &env,
+            &config,
+            &bet_owner,
+            &mut pending_bets,
+            pending_bet,
+        )?;
+
+        return Ok(Response::new().add_attributes(vec![
+            ("action", "fill_pool_bet"),
+            ("bet_id", &bet_id),
+            ("bet_creator", bet_owner.as_str()),
+            ("pool_responder", info.sender.as_str()),
+            ("responder_side", &side.u8().to_string()),
+            ("denom", &asset.denom),
+            ("amount", &asset.amount.to_string()),
+            ("remaining", &remaining.to_string()),
+            (
+                "started_at_block",
+                &ongoing_bet.started_at_block.to_string(),
+            ),
+            ("liquidation", &ongoing_bet.liquidation.to_string()),
+        ]));
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        ("action", "resolve_bet"),
+    pending_bets.replace_bet(pending_bet);
+    store_pending_bets(deps.storage, &bet_owner, &pending_bets)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "fill_pool_bet"),
         ("bet_id", &bet_id),
-        ("owner", &historical_bet.owner),
-        ("responder", &historical_bet.responder),
-        ("winner", &historical_bet.winner),
-        ("responder_side", &historical_bet.responder_side.to_string()),
-        ("denom", &historical_bet.asset.denom),
-        ("amount", &historical_bet.asset.amount.to_string()),
-        ("outcome", &historical_bet.outcome.to_string()),
-        ("created_at", &historical_bet.created_at.to_string()),
-        ("completed_at", &historical_bet.completed_at.to_string()),
+        ("bet_creator", bet_owner.as_str()),
+        ("pool_responder", info.sender.as_str()),
+        ("responder_side", &side.u8().to_string()),
+        ("denom", &asset.denom),
+        ("amount", &asset.amount.to_string()),
+        ("remaining", &remaining.to_string()),
     ]))
 }
 
-pub fn liquidate_bet(
+// pool responder takes their fill back while the bet is still pending,
+// the fill stays counted in their wager limits
+pub fn withdraw_pool_fill(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
+    bet_owner: Addr,
     bet_id: String,
 ) -> Result<Response, ContractError> {
     if !info.funds.is_empty() {
         return Err(ContractError::ExecuteWithoutFunds {});
     }
 
-    let config = load_config(deps.storage)?;
-    let ongoing_bet = match load_ongoing_bet(deps.storage, bet_id.clone()) {
+    let mut pending_bets = load_pending_bets(deps.storage, &bet_owner)?;
+    let mut pending_bet = match pending_bets.find_by_id(&bet_id) {
         Ok(b) => b,
-        Err(_) => return Err(ContractError::GameWasAlreadyResolved {}),
+        Err(_) => return Err(ContractError::BetWasCancledOrAccepted {}),
     };
 
-    if info.sender.eq(&ongoing_bet.bet_creator) {
-        return Err(ContractError::ForbiddenForBetCreatorToLiquidateHimself {});
-    }
-
-    if env.block.height <= ongoing_bet.liquidation_block {
-        return Err(ContractError::BetIsNotLiquidatableYet {});
+    if pending_bet.mode != GameMode::Pool {
+        return Err(ContractError::GameModeMismatch {});
     }
 
-    if info.sender.ne(&ongoing_bet.bet_responder)
-        && env.block.height <= ongoing_bet.responder_liquidation_blocks_gap
+    let fill = match pending_bet
+        .pool
+        .iter()
+        .position(|f| f.responder.eq(&info.sender))
     {
-        return Err(ContractError::ResponderLiquidationGapIsNotPassedYet {});
-    }
-
-    let responder_addr = ongoing_bet.bet_responder;
-    let mut bet_amount = ongoing_bet.asset;
-    let pot_size = bet_amount.clone();
-
-    let mut responder_amount = bet_amount.take_percent(config.bet_responder_liquidation_percent)?;
-    let mut liquidator_amount = bet_amount.take_percent(config.bet_liquidator_percent)?;
-    let treasury_amount = bet_amount
-        .checked_sub(&responder_amount)?
-        .checked_sub(&liquidator_amount)?;
+        Some(i) => pending_bet.pool.remove(i),
+        None => return Err(ContractError::PoolFillNotFound {}),
+    };
 
-    remove_ongoing_bet(deps.storage, bet_id.clone());
+    // an empty pool may be joined on any side again
+    if pending_bet.pool.is_empty() {
+        pending_bet.pool_side = None;
+    }
 
-    let historical_bet = HistoricalBet::new(
-        bet_id.clone(),
-        ongoing_bet.bet_creator.to_string(),
-        responder_addr.to_string(),
-        responder_addr.to_string(),
-        Some(info.sender.to_string()),
-        ongoing_bet.responder_side.clone(),
-        pot_size,
-        GameOutcome::Liquidated,
-        ongoing_bet.created_at.seconds(),
-        env.block.time.seconds(),
-    );
-    save_historical_bet(deps.storage, &config, historical_bet.clone())?;
+    let refund = Asset {
+        denom: pending_bet.asset.denom.clone(),
+        amount: fill.amount,
+    };
+    let pool_size = pending_bet.pool.len();
 
-    let mut messages = vec![
-        responder_amount.into_bank_msg(&deps.querier, &responder_addr)?,
-        liquidator_amount.into_bank_msg(&deps.querier, &info.sender)?,
-    ];
+    pending_bets.replace_bet(pending_bet);
+    store_pending_bets(deps.storage, &bet_owner, &pending_bets)?;
 
-    if !treasury_amount.amount.is_zero() {
-        messages.push(
-            treasury_amount
-                .into_bank_msg(&deps.querier, &deps.api.addr_humanize(&config.treasury)?)?,
-        );
-    }
+    let (refund_msg, _) = send_payout(&deps.querier, &info.sender, PayoutRole::Responder, &refund)?;
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        ("action", "liquidate_bet"),
+    Ok(Response::new().add_message(refund_msg).add_attributes(vec![
+        ("action", "withdraw_pool_fill"),
         ("bet_id", &bet_id),
-        ("owner", &historical_bet.owner),
-        ("responder", &historical_bet.responder),
-        ("winner", &historical_bet.winner),
-        ("liquidator", &info.sender.to_string()),
-        ("responder_side", &historical_bet.responder_side.to_string()),
-        ("denom", &historical_bet.asset.denom),
-        ("amount", &historical_bet.asset.amount.to_string()),
-        ("outcome", &historical_bet.outcome.to_string()),
-        ("created_at", &historical_bet.created_at.to_string()),
-        ("completed_at", &historical_bet.completed_at.to_string()),
+        ("bet_creator", bet_owner.as_str()),
+        ("pool_responder", info.sender.as_str()),
+        ("denom", &refund.denom),
+        ("amount", &refund.amount.to_string()),
+        ("pool_size", &pool_size.to_string()),
     ]))
 }
 
-pub fn withdraw_pending_bet(
+// creator starts the game with the filled part of a pool bet and takes back the rest of their stake
+pub fn close_pool_bet(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     bet_id: String,
 ) -> Result<Response, ContractError> {
@@ -307,106 +579,1560 @@ pub fn withdraw_pending_bet(
         return Err(ContractError::ExecuteWithoutFunds {});
     }
 
+    let config = load_config(deps.storage)?;
     let mut pending_bets = load_pending_bets(deps.storage, &info.sender)?;
-    let pending_bet = match pending_bets.find_by_id(&bet_id) {
+    let mut pending_bet = match pending_bets.find_by_id(&bet_id) {
         Ok(b) => b,
         Err(_) => return Err(ContractError::GameWasAlreadyAccepted {}),
     };
 
-    let mut withdraw_amount = pending_bet.asset;
-    let send_msg = withdraw_amount.into_bank_msg(&deps.querier, &info.sender)?;
+    if pending_bet.mode != GameMode::Pool {
+        return Err(ContractError::GameModeMismatch {});
+    }
 
-    pending_bets.remove_bet(&bet_id);
-    store_pending_bets(deps.storage, &info.sender, &pending_bets)?;
+    // nobody has joined the pool, so there is no game to start
+    if pending_bet.pool.is_empty() {
+        return withdraw_pending_bet(deps, env, info, bet_id);
+    }
 
-    let current_bets_count = load_pending_bets_count(deps.storage)?;
-    let bets_count = current_bets_count.checked_sub(Uint64::new(1u64))?;
-    store_pending_bets_count(deps.storage, bets_count)?;
+    // pool bets have even odds, so the filled part is matched one to one
+    let filled = pending_bet.pool_filled()?;
+    let mut refund = pending_bet.asset.clone();
+    refund.checked_sub(&Asset {
+        denom: refund.denom.clone(),
+        amount: filled,
+    })?;
+    pending_bet.asset.amount = filled;
+    config.validate_relayer_tip(
+        &pending_bet.asset,
+        &pending_bet.odds,
+        pending_bet.relayer_tip,
+    )?;
 
-    Ok(Response::new().add_message(send_msg).add_attributes(vec![
-        ("action", "withdraw_pending_bet"),
+    let ongoing_bet = start_pool_bet(
+        deps.storage,
+        &env,
+        &config,
+        &info.sender,
+        &mut pending_bets,
+        pending_bet,
+    )?;
+
+    let mut response = Response::new();
+    if !refund.amount.is_zero() {
+        let (refund_msg, _) = send_payout(&deps.querier, &info.sender, PayoutRole::Owner, &refund)?;
+        response = response.add_message(refund_msg);
+    }
+
+    Ok(response.add_attributes(vec![
+        ("action", "close_pool_bet"),
         ("bet_id", &bet_id),
+        ("bet_creator", info.sender.as_str()),
+        ("denom", &ongoing_bet.asset.denom),
+        ("amount", &ongoing_bet.asset.amount.to_string()),
+        ("refund", &refund.amount.to_string()),
+        ("pool_size", &ongoing_bet.pool.len().to_string()),
+        (
+            "started_at_block",
+            &ongoing_bet.started_at_block.to_string(),
+        ),
+        ("liquidation", &ongoing_bet.liquidation.to_string()),
     ]))
 }
 
-// only owner allowed to change config params
-pub fn update_config(
-    deps: DepsMut,
-    info: MessageInfo,
-    owner: Option<String>,
-    treasury: Option<String>,
-    treasury_tax_percent: Option<u8>,
-    max_bets_by_addr: Option<u64>,
-    min_bet_amounts: Option<Vec<CoinLimit>>,
-    min_blocks_until_liquidation: Option<u64>,
-    max_blocks_until_liquidation: Option<u64>,
-    blocks_for_responder_liquidation: Option<u64>,
-    bet_responder_liquidation_percent: Option<u8>,
-    bet_liquidator_percent: Option<u8>,
-    treasury_liquidation_percent: Option<u8>,
-    historical_bets_max_storage_size: Option<u64>,
-    historical_bets_clear_batch_size: Option<u64>,
-) -> Result<Response, ContractError> {
-    let mut config = load_config(deps.storage)?;
+// moves a filled or closed pool bet to ongoing bets, creator stake is matched by the pool
+fn start_pool_bet(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    bet_owner: &Addr,
+    pending_bets: &mut AddrPendingBets,
+    pending_bet: PendingBet,
+) -> StdResult<OngoingBet> {
+    let creator_stake = pending_bet.asset.amount;
+    let ongoing_bet = OngoingBet::new(
+        &pending_bet,
+        bet_owner.clone(),
+        pending_bet.pool[0].responder.clone(),
+        pending_bet.pool_side.clone(),
+        config.responder_liquidation_gap(&pending_bet.until_liquidation),
+        &env.block,
+    )?;
 
-    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
-        return Err(ContractError::Unauthorized {});
-    }
+    pending_bets.remove_bet(&pending_bet.id);
+    store_pending_bets(storage, bet_owner, pending_bets)?;
+    store_ongoing_bet(storage, pending_bet.id.clone(), &ongoing_bet)?;
+    store_bet_location(storage, &pending_bet.id, &BetLocation::Ongoing {})?;
 
-    if let Some(owner) = owner {
-        let _ = deps.api.addr_validate(&owner)?;
-        config.owner = deps.api.addr_canonicalize(&owner)?;
+    record_player_stats(
+        storage,
+        config,
+        env.block.height,
+        bet_owner,
+        &ongoing_bet.asset.denom,
+        |stats| stats.add_game(true, creator_stake),
+    )?;
+    for fill in &ongoing_bet.pool {
+        record_player_stats(
+            storage,
+            config,
+            env.block.height,
+            &fill.responder,
+            &ongoing_bet.asset.denom,
+            |stats| stats.add_game(false, fill.amount),
+        )?;
     }
 
-    if let Some(treasury) = treasury {
-        let _ = deps.api.addr_validate(&treasury)?;
-        config.treasury = deps.api.addr_canonicalize(&treasury)?;
-    }
+    let current_bets_count = load_pending_bets_count(storage)?;
+    let bets_count = current_bets_count.checked_sub(Uint64::new(1u64))?;
+    store_pending_bets_count(storage, bets_count)?;
 
-    if let Some(treasury_tax_percent) = treasury_tax_percent {
-        config.treasury_tax_percent = treasury_tax_percent;
-    }
+    Ok(ongoing_bet)
+}
 
-    if let Some(max_bets_by_addr) = max_bets_by_addr {
-        config.max_bets_by_addr = max_bets_by_addr;
+pub fn resolve_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_id: String,
+    passphrase: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
     }
 
-    if let Some(min_bet_amounts) = min_bet_amounts {
-        config.min_bet_amounts = min_bet_amounts;
-    }
+    let config = load_config(deps.storage)?;
+    let mut ongoing_bet = match load_ongoing_bet(deps.storage, bet_id.clone()) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::GameWasAlreadyLiquidated {}),
+    };
 
-    if let Some(min_blocks_until_liquidation) = min_blocks_until_liquidation {
-        config.min_blocks_until_liquidation = min_blocks_until_liquidation;
+    if ongoing_bet.mode == GameMode::Beacon {
+        return Err(ContractError::GameModeMismatch {});
     }
 
-    if let Some(max_blocks_until_liquidation) = max_blocks_until_liquidation {
-        config.max_blocks_until_liquidation = max_blocks_until_liquidation;
+    let signature = calculate_sha256(&passphrase);
+    if matches!(ongoing_bet.mode, GameMode::Classic | GameMode::Pool) {
+        if signature.ne(&ongoing_bet.signature) {
+            return Err(ContractError::SignatureMismatch {});
+        }
+
+        let winner_addr = ongoing_bet.resolve_winner(&passphrase);
+        let revealed_side = outcome_from_passphrase(&passphrase, ongoing_bet.odds.sides);
+        ongoing_bet.creator_passphrase = Some(passphrase.clone());
+        ongoing_bet.creator_revealed_by = Some(info.sender.clone());
+        ongoing_bet.creator_revealed_late = ongoing_bet.liquidation.is_expired(&env.block);
+        return settle_resolved_bet(
+            deps,
+            env,
+            info,
+            config,
+            bet_id,
+            ongoing_bet,
+            Settlement {
+                winner: winner_addr,
+                revealed_side,
+                jackpot_seed: passphrase,
+            },
+        );
+    }
+
+    // in dual commit mode every reveal is stored until both players have revealed
+    let revealed_by = if signature.eq(&ongoing_bet.signature) {
+        ongoing_bet.creator_passphrase = Some(passphrase);
+        ongoing_bet.creator_revealed_by = Some(info.sender.clone());
+        ongoing_bet.creator_revealed_late = ongoing_bet.liquidation.is_expired(&env.block);
+        ongoing_bet.bet_creator.clone()
+    } else if Some(&signature) == ongoing_bet.responder_signature.as_ref() {
+        ongoing_bet.responder_passphrase = Some(passphrase);
+        ongoing_bet.bet_responder.clone()
+    } else {
+        return Err(ContractError::SignatureMismatch {});
+    };
+
+    match ongoing_bet.resolve_dual_commit_winner() {
+        Some(winner_addr) => {
+            ongoing_bet.responder_side = ongoing_bet
+                .responder_passphrase
+                .as_deref()
+                .and_then(FlipSide::from_passphrase);
+            ongoing_bet.responder_picks =
+                ongoing_bet.responder_side.iter().map(|s| s.u8()).collect();
+            let revealed_side = ongoing_bet
+                .creator_passphrase
+                .as_deref()
+                .and_then(FlipSide::from_passphrase)
+                .map(|s| s.u8());
+            let jackpot_seed = format!(
+                "{}{}",
+                ongoing_bet.creator_passphrase.clone().unwrap_or_default(),
+                ongoing_bet.responder_passphrase.clone().unwrap_or_default()
+            );
+            settle_resolved_bet(
+                deps,
+                env,
+                info,
+                config,
+                bet_id,
+                ongoing_bet,
+                Settlement {
+                    winner: winner_addr,
+                    revealed_side,
+                    jackpot_seed,
+                },
+            )
+        }
+        None => {
+            store_ongoing_bet(deps.storage, bet_id.clone(), &ongoing_bet)?;
+
+            Ok(Response::new().add_attributes(vec![
+                ("action", "reveal_bet"),
+                ("bet_id", &bet_id),
+                ("revealed_by", revealed_by.as_str()),
+                ("resolved_by", info.sender.as_str()),
+            ]))
+        }
+    }
+}
+
+// anyone can submit the drand beacon of the bet round, its randomness flips the coin
+pub fn settle_with_beacon(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_id: String,
+    round: u64,
+    previous_signature: String,
+    signature: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let config = load_config(deps.storage)?;
+    let ongoing_bet = match load_ongoing_bet(deps.storage, bet_id.clone()) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::GameWasAlreadyLiquidated {}),
+    };
+
+    if ongoing_bet.mode != GameMode::Beacon {
+        return Err(ContractError::GameModeMismatch {});
+    }
+
+    if ongoing_bet.beacon_round != Some(round) {
+        return Err(ContractError::BeaconRoundMismatch {});
+    }
+
+    if !verify_beacon(
+        &config.drand_public_key,
+        round,
+        &previous_signature,
+        &signature,
+    )? {
+        return Err(ContractError::BeaconSignatureMismatch {});
+    }
+
+    let randomness = beacon_randomness(&signature)?;
+    let revealed_side = outcome_from_randomness(&randomness, ongoing_bet.odds.sides);
+    let winner_addr = ongoing_bet.outcome_winner(revealed_side);
+
+    settle_resolved_bet(
+        deps,
+        env,
+        info,
+        config,
+        bet_id,
+        ongoing_bet,
+        Settlement {
+            winner: winner_addr,
+            revealed_side: Some(revealed_side),
+            jackpot_seed: signature,
+        },
+    )
+}
+
+// outcome of a bet known from revealed passphrases or beacon,
+// jackpot_seed is the revealed randomness mixed into the jackpot roll
+struct Settlement {
+    winner: Addr,
+    revealed_side: Option<u8>,
+    jackpot_seed: String,
+}
+
+// pays out a bet whose winner is known from revealed passphrases or beacon
+fn settle_resolved_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Config,
+    bet_id: String,
+    ongoing_bet: OngoingBet,
+    settlement: Settlement,
+) -> Result<Response, ContractError> {
+    let Settlement {
+        winner: winner_addr,
+        revealed_side,
+        jackpot_seed,
+    } = settlement;
+    let mut creator_bond = ongoing_bet.creator_bond_asset();
+    let mut bet_amount = ongoing_bet.asset.clone();
+    let pot_size = bet_amount.clone();
+
+    // bond is returned in full only on a timely reveal, otherwise its slashed part
+    // goes to the responder side who had to wait past the deadline
+    let slashed_bond = if ongoing_bet.creator_revealed_late {
+        creator_bond.take_percent(config.creator_bond_slash_percent)?
+    } else {
+        Asset {
+            denom: creator_bond.denom.clone(),
+            amount: Uint128::zero(),
+        }
+    };
+    creator_bond.checked_sub(&slashed_bond)?;
+
+    // winner side pays the rate of its fee tier, a winning pool is shared by responders with
+    // different tiers so it pays the flat rate
+    let treasury_tax_percent =
+        if !ongoing_bet.pool.is_empty() && winner_addr.ne(&ongoing_bet.bet_creator) {
+            config.treasury_tax_percent
+        } else {
+            let winner_volume = load_window_volume(
+                deps.storage,
+                &config,
+                env.block.height,
+                &winner_addr,
+                &pot_size.denom,
+            )?;
+            config.treasury_tax_percent_for(&pot_size.denom, winner_volume)
+        };
+    let mut treasury_amount = bet_amount.take_percent(treasury_tax_percent)?;
+    let winner_amount = bet_amount.checked_sub(&treasury_amount)?;
+    let jackpot_amount = treasury_amount.take_percent(config.jackpot_percent)?;
+    let referral_payouts =
+        accrue_referral_shares(deps.storage, &config, &ongoing_bet, &mut treasury_amount)?;
+    treasury_amount.checked_sub(&jackpot_amount)?;
+
+    // named relayer is tipped only when it revealed the passphrase of the winning creator
+    let relayer_amount = Asset {
+        denom: winner_amount.denom.clone(),
+        amount: ongoing_bet.relayer_tip,
+    };
+    let relayer = match (&ongoing_bet.relayer, &ongoing_bet.creator_revealed_by) {
+        (Some(relayer), Some(revealed_by)) if relayer.eq(revealed_by) => Some(relayer.clone()),
+        _ => None,
+    }
+    .filter(|_| winner_addr.eq(&ongoing_bet.bet_creator) && !relayer_amount.amount.is_zero());
+    if relayer.is_some() {
+        winner_amount.checked_sub(&relayer_amount)?;
+    }
+
+    remove_ongoing_bet(deps.storage, bet_id.clone());
+
+    // winner who offered a rematch stakes their winnings against the loser instead of taking them,
+    // unless the rematch does not fit their limits or bets count anymore, then they are paid out
+    let rematch_stake = winner_amount.clone();
+    let mut rematch_signature = ongoing_bet.rematch_signature(&winner_addr);
+    if rematch_signature.is_some() {
+        let winner_bets = load_pending_bets(deps.storage, &winner_addr)?;
+        if (winner_bets.bets.len() as u64) >= config.max_bets_by_addr
+            || check_player_limits(deps.storage, env.block.time, &winner_addr, &rematch_stake)
+                .is_err()
+        {
+            rematch_signature = None;
+        } else {
+            use_wager_limits(deps.storage, env.block.time, &winner_addr, &rematch_stake)?;
+        }
+    }
+    let (mut messages, mut payouts) = if rematch_signature.is_some() {
+        let rematch_payout = Payout {
+            recipient: winner_addr.to_string(),
+            role: PayoutRole::Rematch,
+            gross_amount: rematch_stake.amount,
+            net_amount: rematch_stake.amount,
+        };
+        (vec![], vec![rematch_payout])
+    } else if winner_addr.eq(&ongoing_bet.bet_responder) {
+        send_responder_payouts(
+            &deps.querier,
+            &ongoing_bet,
+            PayoutRole::Winner,
+            winner_amount,
+        )?
+    } else {
+        let (winner_msg, winner_payout) = send_payout(
+            &deps.querier,
+            &winner_addr,
+            PayoutRole::Winner,
+            winner_amount,
+        )?;
+        (vec![winner_msg], vec![winner_payout])
+    };
+
+    if let Some(relayer) = &relayer {
+        let (relayer_msg, relayer_payout) =
+            send_payout(&deps.querier, relayer, PayoutRole::Relayer, &relayer_amount)?;
+        messages.push(relayer_msg);
+        payouts.push(relayer_payout);
+    }
+
+    if !treasury_amount.amount.is_zero() {
+        let (treasury_msg, treasury_payout) = send_payout(
+            &deps.querier,
+            &deps.api.addr_humanize(&config.treasury)?,
+            PayoutRole::Treasury,
+            &treasury_amount,
+        )?;
+        messages.push(treasury_msg);
+        payouts.push(treasury_payout);
+    }
+    payouts.extend(referral_payouts);
+
+    if !slashed_bond.amount.is_zero() {
+        let (slashed_msgs, slashed_payouts) = send_responder_payouts(
+            &deps.querier,
+            &ongoing_bet,
+            PayoutRole::Responder,
+            &slashed_bond,
+        )?;
+        messages.extend(slashed_msgs);
+        payouts.extend(slashed_payouts);
+    }
+
+    if !creator_bond.amount.is_zero() {
+        let (bond_msg, bond_payout) = send_payout(
+            &deps.querier,
+            &ongoing_bet.bet_creator,
+            PayoutRole::Owner,
+            &creator_bond,
+        )?;
+        messages.push(bond_msg);
+        payouts.push(bond_payout);
+    }
+
+    let jackpot_winner = feed_jackpot(
+        deps.storage,
+        &env,
+        &config,
+        &bet_id,
+        &ongoing_bet,
+        &jackpot_amount,
+        &jackpot_seed,
+    )?;
+    if let Some(jackpot_winner) = &jackpot_winner {
+        let (jackpot_msg, jackpot_payout) = send_payout(
+            &deps.querier,
+            &jackpot_winner.winner,
+            PayoutRole::Jackpot,
+            &jackpot_winner.asset,
+        )?;
+        messages.push(jackpot_msg);
+        payouts.push(jackpot_payout);
+    }
+
+    let mut historical_bet = HistoricalBet::new(
+        bet_id.clone(),
+        ongoing_bet.bet_creator.to_string(),
+        ongoing_bet.bet_responder.to_string(),
+        winner_addr.to_string(),
+        None,
+        ongoing_bet.responder_side.clone(),
+        pot_size,
+        GameOutcome::Resolved,
+        ongoing_bet.created_at.seconds(),
+        env.block.time.seconds(),
+    );
+    historical_bet.set_game(&ongoing_bet);
+    historical_bet.creator_bond = ongoing_bet.creator_bond;
+    historical_bet.payouts = payouts;
+    historical_bet.revealed_side = revealed_side;
+    historical_bet.passphrase = ongoing_bet.creator_passphrase.clone();
+    historical_bet.responder_passphrase = ongoing_bet.responder_passphrase.clone();
+    historical_bet.beacon_round = ongoing_bet.beacon_round;
+    save_historical_bet(deps.storage, &config, historical_bet.clone())?;
+
+    // creator takes no part in beacon settlement, so it says nothing about their reliability
+    if ongoing_bet.mode != GameMode::Beacon {
+        let mut reliability = load_creator_reliability(deps.storage, &ongoing_bet.bet_creator)?;
+        reliability.add_resolved(env.block.height - ongoing_bet.started_at_block);
+        store_creator_reliability(deps.storage, &ongoing_bet.bet_creator, &reliability)?;
+    }
+    update_player_stats(deps.storage, &config, env.block.height, &historical_bet)?;
+
+    let mut response = Response::new();
+    if let Some(signature) = rematch_signature {
+        let rematch = Rematch {
+            previous_bet_id: bet_id.clone(),
+            opponent: ongoing_bet.opponent(&winner_addr),
+            expires: ongoing_bet.until_liquidation.after(&env.block),
+        };
+        let rematch_bet_id = place_rematch_bet(
+            deps.storage,
+            deps.api,
+            &env,
+            &winner_addr,
+            PlaceBetParams::new(signature, ongoing_bet.until_liquidation),
+            rematch_stake,
+            rematch,
+        )?;
+        response = response.add_attribute("rematch_bet_id", rematch_bet_id);
+    }
+
+    if let Some(jackpot_winner) = jackpot_winner {
+        response = response.add_event(
+            Event::new("jackpot")
+                .add_attribute("bet_id", &bet_id)
+                .add_attribute("winner", jackpot_winner.winner)
+                .add_attribute("denom", jackpot_winner.asset.denom)
+                .add_attribute("amount", jackpot_winner.asset.amount.to_string()),
+        );
+    }
+
+    Ok(response.add_messages(messages).add_attributes(vec![
+        ("action", "resolve_bet"),
+        ("bet_id", &bet_id),
+        ("owner", &historical_bet.owner),
+        ("responder", ongoing_bet.bet_responder.as_str()),
+        ("winner", winner_addr.as_str()),
+        ("responder_side", &side_attr(&ongoing_bet.responder_side)),
+        ("denom", &historical_bet.asset.denom),
+        ("amount", &historical_bet.asset.amount.to_string()),
+        ("outcome", &historical_bet.outcome.to_string()),
+        ("created_at", &historical_bet.created_at.to_string()),
+        ("completed_at", &historical_bet.completed_at.to_string()),
+        ("resolved_by", info.sender.as_str()),
+        ("treasury_tax_percent", &treasury_tax_percent.to_string()),
+    ]))
+}
+
+pub fn liquidate_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_id: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let config = load_config(deps.storage)?;
+    let ongoing_bet = match load_ongoing_bet(deps.storage, bet_id.clone()) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::GameWasAlreadyResolved {}),
+    };
+
+    let parties = ongoing_bet.liquidation_parties();
+    if let Some((_, at_fault)) = &parties {
+        if info.sender.eq(at_fault) && at_fault.eq(&ongoing_bet.bet_creator) {
+            return Err(ContractError::ForbiddenForBetCreatorToLiquidateHimself {});
+        }
+
+        if info.sender.eq(at_fault) {
+            return Err(ContractError::ForbiddenForBetResponderToLiquidateHimself {});
+        }
+    }
+
+    if !ongoing_bet.liquidation.is_expired(&env.block) {
+        return Err(ContractError::BetIsNotLiquidatableYet {});
+    }
+
+    // the player who did reveal has an exclusive window to liquidate, shared by pool responders
+    let is_wronged = match &parties {
+        Some((wronged, _)) if wronged.eq(&ongoing_bet.bet_responder) => {
+            ongoing_bet.is_responder(&info.sender)
+        }
+        Some((wronged, _)) => info.sender.eq(wronged),
+        None => false,
+    };
+    if !is_wronged && !ongoing_bet.responder_liquidation.is_expired(&env.block) {
+        return Err(ContractError::ResponderLiquidationGapIsNotPassedYet {});
+    }
+
+    let (wronged_addr, at_fault_addr) = match parties {
+        Some(p) => p,
+        None => return refund_unrevealed_bet(deps, env, info, config, bet_id, ongoing_bet),
+    };
+    let creator_at_fault = at_fault_addr.eq(&ongoing_bet.bet_creator);
+
+    let mut creator_bond = ongoing_bet.creator_bond_asset();
+    let mut bet_amount = ongoing_bet.asset.clone();
+    let pot_size = bet_amount.clone();
+
+    let mut wronged_amount = bet_amount.take_percent(config.bet_responder_liquidation_percent)?;
+    let mut liquidator_amount = bet_amount.take_percent(config.bet_liquidator_percent)?;
+    let treasury_amount = bet_amount
+        .checked_sub(&wronged_amount)?
+        .checked_sub(&liquidator_amount)?;
+
+    // slashed part of creator bond is shared by responder and liquidator, the rest is returned
+    if creator_at_fault {
+        let slashed_bond = creator_bond.take_percent(config.creator_bond_slash_percent)?;
+        let liquidator_bond = slashed_bond.take_percent(config.bet_liquidator_percent)?;
+        wronged_amount
+            .checked_add(&slashed_bond)?
+            .checked_sub(&liquidator_bond)?;
+        liquidator_amount.checked_add(&liquidator_bond)?;
+        creator_bond.checked_sub(&slashed_bond)?;
+    }
+
+    let referral_payouts =
+        accrue_referral_shares(deps.storage, &config, &ongoing_bet, treasury_amount)?;
+
+    remove_ongoing_bet(deps.storage, bet_id.clone());
+
+    // responder keeps its liquidation role, a creator wronged in dual commit mode wins the game
+    let wronged_role = if creator_at_fault {
+        PayoutRole::Responder
+    } else {
+        PayoutRole::Winner
+    };
+    let (mut messages, mut payouts) = if wronged_addr.eq(&ongoing_bet.bet_responder) {
+        send_responder_payouts(&deps.querier, &ongoing_bet, wronged_role, &wronged_amount)?
+    } else {
+        let (wronged_msg, wronged_payout) =
+            send_payout(&deps.querier, &wronged_addr, wronged_role, &wronged_amount)?;
+        (vec![wronged_msg], vec![wronged_payout])
+    };
+    let (liquidator_msg, liquidator_payout) = send_payout(
+        &deps.querier,
+        &info.sender,
+        PayoutRole::Liquidator,
+        &liquidator_amount,
+    )?;
+    messages.push(liquidator_msg);
+    payouts.push(liquidator_payout);
+
+    if !treasury_amount.amount.is_zero() {
+        let (treasury_msg, treasury_payout) = send_payout(
+            &deps.querier,
+            &deps.api.addr_humanize(&config.treasury)?,
+            PayoutRole::Treasury,
+            treasury_amount,
+        )?;
+        messages.push(treasury_msg);
+        payouts.push(treasury_payout);
+    }
+    payouts.extend(referral_payouts);
+
+    if !creator_bond.amount.is_zero() {
+        let (bond_msg, bond_payout) = send_payout(
+            &deps.querier,
+            &ongoing_bet.bet_creator,
+            PayoutRole::Owner,
+            &creator_bond,
+        )?;
+        messages.push(bond_msg);
+        payouts.push(bond_payout);
+    }
+
+    let mut historical_bet =
+        liquidated_historical_bet(&env, &info, &bet_id, &ongoing_bet, pot_size);
+    historical_bet.winner = Some(wronged_addr.to_string());
+    historical_bet.payouts = payouts;
+    save_historical_bet(deps.storage, &config, historical_bet.clone())?;
+
+    if creator_at_fault {
+        let mut reliability = load_creator_reliability(deps.storage, &ongoing_bet.bet_creator)?;
+        reliability.add_liquidated();
+        store_creator_reliability(deps.storage, &ongoing_bet.bet_creator, &reliability)?;
+    }
+    update_player_stats(deps.storage, &config, env.block.height, &historical_bet)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "liquidate_bet"),
+        ("bet_id", &bet_id),
+        ("owner", &historical_bet.owner),
+        ("responder", ongoing_bet.bet_responder.as_str()),
+        ("winner", wronged_addr.as_str()),
+        ("liquidator", &info.sender.to_string()),
+        ("responder_side", &side_attr(&ongoing_bet.responder_side)),
+        ("denom", &historical_bet.asset.denom),
+        ("amount", &historical_bet.asset.amount.to_string()),
+        ("outcome", &historical_bet.outcome.to_string()),
+        ("created_at", &historical_bet.created_at.to_string()),
+        ("completed_at", &historical_bet.completed_at.to_string()),
+    ]))
+}
+
+// nobody is at fault when neither player revealed in dual commit mode or beacon was not submitted,
+// so both stakes and the bond are returned and nobody wins
+fn refund_unrevealed_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Config,
+    bet_id: String,
+    ongoing_bet: OngoingBet,
+) -> Result<Response, ContractError> {
+    let pot_size = ongoing_bet.asset.clone();
+    let responder_refund = ongoing_bet.responder_stake_asset();
+    let mut creator_refund = ongoing_bet.creator_stake_asset()?;
+    creator_refund.checked_add(&ongoing_bet.creator_bond_asset())?;
+
+    remove_ongoing_bet(deps.storage, bet_id.clone());
+
+    let (creator_msg, creator_payout) = send_payout(
+        &deps.querier,
+        &ongoing_bet.bet_creator,
+        PayoutRole::Owner,
+        &creator_refund,
+    )?;
+    let (responder_msgs, responder_payouts) = send_responder_payouts(
+        &deps.querier,
+        &ongoing_bet,
+        PayoutRole::Responder,
+        &responder_refund,
+    )?;
+    let mut messages = vec![creator_msg];
+    messages.extend(responder_msgs);
+    let mut payouts = vec![creator_payout];
+    payouts.extend(responder_payouts);
+
+    let mut historical_bet =
+        liquidated_historical_bet(&env, &info, &bet_id, &ongoing_bet, pot_size);
+    historical_bet.winner = None;
+    historical_bet.payouts = payouts;
+    save_historical_bet(deps.storage, &config, historical_bet.clone())?;
+    update_player_stats(deps.storage, &config, env.block.height, &historical_bet)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "liquidate_bet"),
+        ("bet_id", &bet_id),
+        ("owner", &historical_bet.owner),
+        ("responder", ongoing_bet.bet_responder.as_str()),
+        ("liquidator", info.sender.as_str()),
+        ("denom", &historical_bet.asset.denom),
+        ("amount", &historical_bet.asset.amount.to_string()),
+        ("outcome", &historical_bet.outcome.to_string()),
+        ("created_at", &historical_bet.created_at.to_string()),
+        ("completed_at", &historical_bet.completed_at.to_string()),
+    ]))
+}
+
+fn liquidated_historical_bet(
+    env: &Env,
+    info: &MessageInfo,
+    bet_id: &str,
+    ongoing_bet: &OngoingBet,
+    pot_size: Asset,
+) -> HistoricalBet {
+    let mut historical_bet = HistoricalBet::new(
+        bet_id.to_string(),
+        ongoing_bet.bet_creator.to_string(),
+        ongoing_bet.bet_responder.to_string(),
+        ongoing_bet.bet_responder.to_string(),
+        Some(info.sender.to_string()),
+        ongoing_bet.responder_side.clone(),
+        pot_size,
+        GameOutcome::Liquidated,
+        ongoing_bet.created_at.seconds(),
+        env.block.time.seconds(),
+    );
+    historical_bet.set_game(ongoing_bet);
+    historical_bet.creator_bond = ongoing_bet.creator_bond;
+    historical_bet.passphrase = ongoing_bet.creator_passphrase.clone();
+    historical_bet.responder_passphrase = ongoing_bet.responder_passphrase.clone();
+    historical_bet.beacon_round = ongoing_bet.beacon_round;
+    historical_bet
+}
+
+// creator concedes the game, responder gets the pot minus treasury tax
+pub fn forfeit_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_id: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let config = load_config(deps.storage)?;
+    let ongoing_bet = match load_ongoing_bet(deps.storage, bet_id.clone()) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::GameWasAlreadyLiquidated {}),
+    };
+
+    if info.sender.ne(&ongoing_bet.bet_creator) {
+        return Err(ContractError::OnlyBetCreatorAllowedToForfeit {});
+    }
+
+    let creator_bond = ongoing_bet.creator_bond_asset();
+    let responder_addr = ongoing_bet.bet_responder.clone();
+    let mut bet_amount = ongoing_bet.asset.clone();
+    let pot_size = bet_amount.clone();
+
+    let treasury_amount = bet_amount.take_percent(config.treasury_tax_percent)?;
+    let winner_amount = bet_amount.checked_sub(&treasury_amount)?;
+
+    remove_ongoing_bet(deps.storage, bet_id.clone());
+
+    let (mut messages, mut payouts) = send_responder_payouts(
+        &deps.querier,
+        &ongoing_bet,
+        PayoutRole::Winner,
+        winner_amount,
+    )?;
+
+    if !treasury_amount.amount.is_zero() {
+        let (treasury_msg, treasury_payout) = send_payout(
+            &deps.querier,
+            &deps.api.addr_humanize(&config.treasury)?,
+            PayoutRole::Treasury,
+            &treasury_amount,
+        )?;
+        messages.push(treasury_msg);
+        payouts.push(treasury_payout);
+    }
+
+    if !creator_bond.amount.is_zero() {
+        let (bond_msg, bond_payout) = send_payout(
+            &deps.querier,
+            &ongoing_bet.bet_creator,
+            PayoutRole::Owner,
+            &creator_bond,
+        )?;
+        messages.push(bond_msg);
+        payouts.push(bond_payout);
+    }
+
+    let mut historical_bet = HistoricalBet::new(
+        bet_id.clone(),
+        ongoing_bet.bet_creator.to_string(),
+        responder_addr.to_string(),
+        responder_addr.to_string(),
+        None,
+        ongoing_bet.responder_side.clone(),
+        pot_size,
+        GameOutcome::Forfeited,
+        ongoing_bet.created_at.seconds(),
+        env.block.time.seconds(),
+    );
+    historical_bet.set_game(&ongoing_bet);
+    historical_bet.creator_bond = creator_bond.amount;
+    historical_bet.payouts = payouts;
+    save_historical_bet(deps.storage, &config, historical_bet.clone())?;
+    update_player_stats(deps.storage, &config, env.block.height, &historical_bet)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "forfeit_bet"),
+        ("bet_id", &bet_id),
+        ("owner", &historical_bet.owner),
+        ("responder", responder_addr.as_str()),
+        ("winner", responder_addr.as_str()),
+        ("responder_side", &side_attr(&ongoing_bet.responder_side)),
+        ("denom", &historical_bet.asset.denom),
+        ("amount", &historical_bet.asset.amount.to_string()),
+        ("outcome", &historical_bet.outcome.to_string()),
+        ("created_at", &historical_bet.created_at.to_string()),
+        ("completed_at", &historical_bet.completed_at.to_string()),
+    ]))
+}
+
+// withdrawn stake stays counted in the wager limits
+pub fn withdraw_pending_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_id: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let mut pending_bets = load_pending_bets(deps.storage, &info.sender)?;
+    let pending_bet = match pending_bets.find_by_id(&bet_id) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::GameWasAlreadyAccepted {}),
+    };
+
+    if !pending_bet.pool.is_empty() {
+        return Err(ContractError::PoolBetHasFills {});
+    }
+
+    close_pending_bet(
+        deps,
+        env,
+        GameOutcome::Withdrawn,
+        info.sender,
+        pending_bets,
+        pending_bet,
+        "withdraw_pending_bet",
+    )
+}
+
+// tops up, partly withdraws, bonds or moves the deadline of a bet without losing its place
+// in creation order, withdrawn stake stays counted in the wager limits
+pub fn update_pending_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_id: String,
+    params: UpdatePendingBetParams,
+) -> Result<Response, ContractError> {
+    if !params.add_funds.is_zero() && !params.withdraw_funds.is_zero() {
+        return Err(
+            StdError::generic_err("add_funds and withdraw_funds are mutually exclusive").into(),
+        );
+    }
+
+    let config = load_config(deps.storage)?;
+    let mut pending_bets = load_pending_bets(deps.storage, &info.sender)?;
+    let mut pending_bet = match pending_bets.find_by_id(&bet_id) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::GameWasAlreadyAccepted {}),
+    };
+
+    if !pending_bet.pool.is_empty() {
+        return Err(ContractError::PoolBetHasFills {});
+    }
+
+    // rematch stake is the winnings of a previous game and expires with it
+    if pending_bet.rematch.is_some() {
+        return Err(StdError::generic_err("rematch bet can not be updated").into());
+    }
+
+    let previous_amount = pending_bet.asset.amount;
+    let asset = Asset {
+        denom: pending_bet.asset.denom.clone(),
+        amount: previous_amount
+            .checked_add(params.add_funds)?
+            .checked_sub(params.withdraw_funds)?,
+    };
+    let creator_bond = pending_bet.creator_bond.checked_add(params.add_bond)?;
+    let until_liquidation = params
+        .until_liquidation
+        .unwrap_or(pending_bet.until_liquidation);
+
+    // updated bet is already counted in address bets
+    config.validate_place_bet_inputs(
+        &until_liquidation,
+        pending_bets.bets.len() - 1,
+        &asset,
+        &pending_bet.odds,
+        pending_bet.relayer_tip,
+        creator_bond,
+    )?;
+
+    let attached = params.add_funds.checked_add(params.add_bond)?;
+    if attached.is_zero() {
+        if !info.funds.is_empty() {
+            return Err(ContractError::ExecuteWithoutFunds {});
+        }
+    } else {
+        let funds = Asset::from_coins(info.funds)?;
+        if funds.denom != asset.denom || funds.amount != attached {
+            return Err(ContractError::TopUpMismatch {});
+        }
+    }
+
+    // bond is not wagered, only the stake increase counts in the limits
+    if !params.add_funds.is_zero() {
+        let top_up = Asset {
+            denom: asset.denom.clone(),
+            amount: params.add_funds,
+        };
+        use_wager_limits(deps.storage, env.block.time, &info.sender, &top_up)?;
+    }
+
+    let mut messages = vec![];
+    if !params.withdraw_funds.is_zero() {
+        let refund = Asset {
+            denom: asset.denom.clone(),
+            amount: params.withdraw_funds,
+        };
+        let (refund_msg, _) = send_payout(&deps.querier, &info.sender, PayoutRole::Owner, &refund)?;
+        messages.push(refund_msg);
+    }
+
+    pending_bet.asset = asset.clone();
+    pending_bet.creator_bond = creator_bond;
+    pending_bet.until_liquidation = until_liquidation;
+    pending_bets.replace_bet(pending_bet);
+    store_pending_bets(deps.storage, &info.sender, &pending_bets)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "update_pending_bet"),
+        ("sender", info.sender.as_str()),
+        ("bet_id", &bet_id),
+        ("until_liquidation", &until_liquidation.to_string()),
+        ("denom", &asset.denom),
+        ("amount", &asset.amount.to_string()),
+        ("previous_amount", &previous_amount.to_string()),
+        ("creator_bond", &creator_bond.to_string()),
+        ("updated_at", &env.block.time.seconds().to_string()),
+    ]))
+}
+
+// returns the stake and bond of a bet nobody has played to its owner
+fn close_pending_bet(
+    deps: DepsMut,
+    env: Env,
+    outcome: GameOutcome,
+    bet_owner: Addr,
+    mut pending_bets: AddrPendingBets,
+    pending_bet: PendingBet,
+    action: &str,
+) -> Result<Response, ContractError> {
+    let config = load_config(deps.storage)?;
+    let mut refund = pending_bet.asset.clone();
+    refund.checked_add(&pending_bet.creator_bond_asset())?;
+    let (send_msg, refund_payout) =
+        send_payout(&deps.querier, &bet_owner, PayoutRole::Owner, &refund)?;
+
+    pending_bets.remove_bet(&pending_bet.id);
+    store_pending_bets(deps.storage, &bet_owner, &pending_bets)?;
+
+    let mut historical_bet = HistoricalBet::unplayed(
+        bet_owner.to_string(),
+        &pending_bet,
+        outcome,
+        env.block.time.seconds(),
+    );
+    historical_bet.payouts = vec![refund_payout];
+    save_historical_bet(deps.storage, &config, historical_bet.clone())?;
+
+    let current_bets_count = load_pending_bets_count(deps.storage)?;
+    let bets_count = current_bets_count.checked_sub(Uint64::new(1u64))?;
+    store_pending_bets_count(deps.storage, bets_count)?;
+
+    Ok(Response::new().add_message(send_msg).add_attributes(vec![
+        ("action", action),
+        ("bet_id", &pending_bet.id),
+        ("owner", &historical_bet.owner),
+        ("denom", &historical_bet.asset.denom),
+        ("amount", &historical_bet.asset.amount.to_string()),
+        ("outcome", &historical_bet.outcome.to_string()),
+        ("created_at", &historical_bet.created_at.to_string()),
+        ("completed_at", &historical_bet.completed_at.to_string()),
+    ]))
+}
+
+// player commits to roll their winnings into a rematch if they win the resolved game,
+// the commitment can be replaced until then
+pub fn offer_rematch(
+    deps: DepsMut,
+    info: MessageInfo,
+    bet_id: String,
+    signature: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let mut ongoing_bet = match load_ongoing_bet(deps.storage, bet_id.clone()) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::GameWasAlreadyResolved {}),
+    };
+
+    if ongoing_bet.mode == GameMode::Pool {
+        return Err(ContractError::GameModeMismatch {});
+    }
+
+    // commitments of this game are revealed on settlement
+    if signature.eq(&ongoing_bet.signature)
+        || Some(&signature) == ongoing_bet.responder_signature.as_ref()
+    {
+        return Err(ContractError::SignatureMismatch {});
+    }
+
+    if info.sender.eq(&ongoing_bet.bet_creator) {
+        ongoing_bet.creator_rematch_signature = Some(signature);
+    } else if info.sender.eq(&ongoing_bet.bet_responder) {
+        ongoing_bet.responder_rematch_signature = Some(signature);
+    } else {
+        return Err(ContractError::OnlyBetPlayersAllowedToOfferRematch {});
+    }
+
+    store_ongoing_bet(deps.storage, bet_id.clone(), &ongoing_bet)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "offer_rematch"),
+        ("bet_id", &bet_id),
+        ("sender", info.sender.as_str()),
+    ]))
+}
+
+pub fn accept_rematch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_owner: Addr,
+    bet_id: String,
+    side: u8,
+) -> Result<Response, ContractError> {
+    let mut pending_bets = load_pending_bets(deps.storage, &bet_owner)?;
+    let pending_bet = match pending_bets.find_by_id(&bet_id) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::BetWasCancledOrAccepted {}),
+    };
+
+    if pending_bet.rematch.is_none() {
+        return Err(ContractError::NotARematch {});
+    }
+
+    accept_bet(deps, env, info, bet_owner, bet_id, Some(vec![side]), None)
+}
+
+// declined or expired rematch pays the winnings out to its owner
+pub fn decline_rematch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_owner: Addr,
+    bet_id: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let mut pending_bets = load_pending_bets(deps.storage, &bet_owner)?;
+    let pending_bet = match pending_bets.find_by_id(&bet_id) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::BetWasCancledOrAccepted {}),
+    };
+
+    let rematch = match &pending_bet.rematch {
+        Some(r) => r,
+        None => return Err(ContractError::NotARematch {}),
+    };
+
+    let outcome = if rematch.expires.is_expired(&env.block) {
+        GameOutcome::Expired
+    } else if info.sender.eq(&rematch.opponent) {
+        GameOutcome::Withdrawn
+    } else {
+        return Err(ContractError::RematchIsNotExpiredYet {});
+    };
+
+    close_pending_bet(
+        deps,
+        env,
+        outcome,
+        bet_owner,
+        pending_bets,
+        pending_bet,
+        "decline_rematch",
+    )
+}
+
+// rolls winnings of a resolved bet into a private coinflip against the loser
+fn place_rematch_bet(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    winner: &Addr,
+    params: PlaceBetParams,
+    asset: Asset,
+    rematch: Rematch,
+) -> StdResult<String> {
+    let bet_id = calculate_sha256(&format!(
+        "{}{}{}",
+        env.block.height, env.block.time, &params.signature
+    ));
+
+    let mut pending_bets = load_pending_bets(storage, winner)?;
+    pending_bets.store_bet(
+        api.addr_canonicalize(winner.as_str())?,
+        bet_id.clone(),
+        params,
+        asset,
+        env.block.time,
+    )?;
+    if let Some(bet) = pending_bets.bets.last_mut() {
+        bet.rematch = Some(rematch);
+    }
+
+    store_pending_bets(storage, winner, &pending_bets)?;
+    store_bet_location(
+        storage,
+        &bet_id,
+        &BetLocation::Pending {
+            owner: winner.clone(),
+        },
+    )?;
+
+    let current_bets_count = load_pending_bets_count(storage)?;
+    let bets_count = current_bets_count.checked_add(Uint64::new(1u64))?;
+    store_pending_bets_count(storage, bets_count)?;
+
+    Ok(bet_id)
+}
+
+// responder escrows their stake to accept future bets automatically
+pub fn post_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    side: u8,
+    min_amount: Uint128,
+    max_amount: Uint128,
+    max_until_liquidation: Duration,
+) -> Result<Response, ContractError> {
+    let config = load_config(deps.storage)?;
+    let side = FlipSide::from_u8(side)?;
+    let asset = Asset::from_coins(info.funds)?;
+    let addr_offers = read_offers_by_addr(deps.storage, &info.sender)?;
+
+    config.validate_offer_inputs(
+        addr_offers.len(),
+        &asset,
+        min_amount,
+        max_amount,
+        &max_until_liquidation,
+    )?;
+
+    // limits are checked once the offer matches a bet
+    if load_player_limits(deps.storage, &info.sender)?.is_excluded(env.block.time.seconds()) {
+        return Err(ContractError::PlayerIsSelfExcluded {});
+    }
+
+    let offer = StandingOffer {
+        id: next_offer_id(deps.storage)?,
+        owner: info.sender.clone(),
+        side,
+        asset,
+        min_amount,
+        max_amount,
+        max_until_liquidation,
+        created_at: env.block.time,
+    };
+    store_offer(deps.storage, &offer)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "post_offer"),
+        ("sender", info.sender.as_str()),
+        ("offer_id", &offer.id.to_string()),
+        ("side", &offer.side.u8().to_string()),
+        ("denom", &offer.asset.denom),
+        ("amount", &offer.asset.amount.to_string()),
+        ("min_amount", &min_amount.to_string()),
+        ("max_amount", &max_amount.to_string()),
+        ("max_until_liquidation", &max_until_liquidation.to_string()),
+    ]))
+}
+
+// closes the offer and returns all of its escrow
+pub fn cancel_offer(
+    deps: DepsMut,
+    info: MessageInfo,
+    offer_id: u64,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let offer = load_offer(deps.storage, offer_id)?;
+    if offer.owner.ne(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    remove_offer(deps.storage, offer_id)?;
+    let (refund_msg, _) = send_payout(
+        &deps.querier,
+        &info.sender,
+        PayoutRole::Responder,
+        &offer.asset,
+    )?;
+
+    Ok(Response::new().add_message(refund_msg).add_attributes(vec![
+        ("action", "cancel_offer"),
+        ("offer_id", &offer_id.to_string()),
+        ("denom", &offer.asset.denom),
+        ("amount", &offer.asset.amount.to_string()),
+    ]))
+}
+
+// takes part of the escrow back, what is left must still cover min amount of the offer
+pub fn withdraw_from_offer(
+    deps: DepsMut,
+    info: MessageInfo,
+    offer_id: u64,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let mut offer = load_offer(deps.storage, offer_id)?;
+    if offer.owner.ne(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let withdrawal = Asset {
+        denom: offer.asset.denom.clone(),
+        amount,
+    };
+    offer.asset.checked_sub(&withdrawal)?;
+    if offer.asset.amount < offer.min_amount {
+        return Err(StdError::generic_err(
+            "escrowed amount must not be less than min_amount, cancel the offer instead",
+        )
+        .into());
+    }
+
+    store_offer(deps.storage, &offer)?;
+    let (withdraw_msg, _) = send_payout(
+        &deps.querier,
+        &info.sender,
+        PayoutRole::Responder,
+        &withdrawal,
+    )?;
+
+    Ok(Response::new()
+        .add_message(withdraw_msg)
+        .add_attributes(vec![
+            ("action", "withdraw_from_offer"),
+            ("offer_id", &offer_id.to_string()),
+            ("denom", &withdrawal.denom),
+            ("amount", &withdrawal.amount.to_string()),
+            ("remaining", &offer.asset.amount.to_string()),
+        ]))
+}
+
+// unclaimed earnings of a denom are paid out at once
+pub fn claim_referral_earnings(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let amount = load_referral_earnings(deps.storage, &info.sender, &denom)?;
+    if amount.is_zero() {
+        return Err(ContractError::NoReferralEarnings {});
+    }
+
+    remove_referral_earnings(deps.storage, &info.sender, &denom);
+    let earnings = Asset { denom, amount };
+    let (send_msg, payout) =
+        send_payout(&deps.querier, &info.sender, PayoutRole::Referrer, &earnings)?;
+
+    Ok(Response::new().add_message(send_msg).add_attributes(vec![
+        ("action", "claim_referral_earnings"),
+        ("referrer", info.sender.as_str()),
+        ("denom", &earnings.denom),
+        ("amount", &payout.gross_amount.to_string()),
+        ("net_amount", &payout.net_amount.to_string()),
+    ]))
+}
+
+// exclusion takes effect right away and can never be shortened
+pub fn self_exclude(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    until: u64,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    if until <= env.block.time.seconds() {
+        return Err(StdError::generic_err("self-exclusion must end in the future").into());
+    }
+
+    let mut limits = load_player_limits(deps.storage, &info.sender)?;
+    if matches!(limits.excluded_until, Some(current) if current > until) {
+        return Err(ContractError::SelfExclusionCanNotBeShortened {});
+    }
+
+    limits.excluded_until = Some(until);
+    store_player_limits(deps.storage, &info.sender, &limits)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "self_exclude"),
+        ("sender", info.sender.as_str()),
+        ("excluded_until", &until.to_string()),
+    ]))
+}
+
+// tighter limit replaces the current one right away, looser one waits for the cooldown
+pub fn set_wager_limit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: WagerLimit,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    limit.validate()?;
+
+    let config = load_config(deps.storage)?;
+    let now = env.block.time.seconds();
+    let mut limits = load_player_limits(deps.storage, &info.sender)?;
+    limits.apply_pending(now);
+
+    let is_tighter = match limits.find(&limit) {
+        Some(current) => limit.is_tighter_than(current),
+        None => true,
+    };
+    let effective_at = if is_tighter {
+        limits.replace(limit.clone());
+        now
+    } else {
+        let effective_at = now + config.limit_loosening_cooldown;
+        limits.pending.retain(|p| !p.limit.is_same(&limit));
+        limits.pending.push(PendingWagerLimit {
+            limit: limit.clone(),
+            effective_at,
+        });
+        effective_at
+    };
+    store_player_limits(deps.storage, &info.sender, &limits)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_wager_limit"),
+        ("sender", info.sender.as_str()),
+        ("denom", &limit.denom),
+        ("window_days", &limit.window_days.to_string()),
+        ("max_wager", &limit_attr(&limit.max_wager)),
+        ("max_loss", &limit_attr(&limit.max_loss)),
+        ("effective_at", &effective_at.to_string()),
+    ]))
+}
+
+// only owner allowed to change config params
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: UpdateConfigMsg,
+) -> Result<Response, ContractError> {
+    let mut config = load_config(deps.storage)?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = msg.owner {
+        let _ = deps.api.addr_validate(&owner)?;
+        config.owner = deps.api.addr_canonicalize(&owner)?;
     }
 
-    if let Some(blocks_for_responder_liquidation) = blocks_for_responder_liquidation {
+    if let Some(treasury) = msg.treasury {
+        let _ = deps.api.addr_validate(&treasury)?;
+        config.treasury = deps.api.addr_canonicalize(&treasury)?;
+    }
+
+    if let Some(treasury_tax_percent) = msg.treasury_tax_percent {
+        config.treasury_tax_percent = treasury_tax_percent;
+    }
+
+    if let Some(max_bets_by_addr) = msg.max_bets_by_addr {
+        config.max_bets_by_addr = max_bets_by_addr;
+    }
+
+    if let Some(min_bet_amounts) = msg.min_bet_amounts {
+        config.min_bet_amounts = min_bet_amounts;
+    }
+
+    if let Some(min_blocks_until_liquidation) = msg.min_blocks_until_liquidation {
+        config.min_blocks_until_liquidation = min_blocks_until_liquidation;
+    }
+
+    if let Some(max_blocks_until_liquidation) = msg.max_blocks_until_liquidation {
+        config.max_blocks_until_liquidation = max_blocks_until_liquidation;
+    }
+
+    if let Some(min_seconds_until_liquidation) = msg.min_seconds_until_liquidation {
+        config.min_seconds_until_liquidation = min_seconds_until_liquidation;
+    }
+
+    if let Some(max_seconds_until_liquidation) = msg.max_seconds_until_liquidation {
+        config.max_seconds_until_liquidation = max_seconds_until_liquidation;
+    }
+
+    if let Some(blocks_for_responder_liquidation) = msg.blocks_for_responder_liquidation {
         config.blocks_for_responder_liquidation = blocks_for_responder_liquidation;
     }
 
-    if let Some(bet_responder_liquidation_percent) = bet_responder_liquidation_percent {
+    if let Some(seconds_for_responder_liquidation) = msg.seconds_for_responder_liquidation {
+        config.seconds_for_responder_liquidation = seconds_for_responder_liquidation;
+    }
+
+    if let Some(bet_responder_liquidation_percent) = msg.bet_responder_liquidation_percent {
         config.bet_responder_liquidation_percent = bet_responder_liquidation_percent;
     }
 
-    if let Some(bet_liquidator_percent) = bet_liquidator_percent {
+    if let Some(bet_liquidator_percent) = msg.bet_liquidator_percent {
         config.bet_liquidator_percent = bet_liquidator_percent;
     }
 
-    if let Some(treasury_liquidation_percent) = treasury_liquidation_percent {
+    if let Some(treasury_liquidation_percent) = msg.treasury_liquidation_percent {
         config.treasury_liquidation_percent = treasury_liquidation_percent;
     }
 
-    if let Some(historical_bets_max_storage_size) = historical_bets_max_storage_size {
+    if let Some(historical_bets_max_storage_size) = msg.historical_bets_max_storage_size {
         config.historical_bets_max_storage_size = historical_bets_max_storage_size;
     }
 
-    if let Some(historical_bets_clear_batch_size) = historical_bets_clear_batch_size {
+    if let Some(historical_bets_clear_batch_size) = msg.historical_bets_clear_batch_size {
         config.historical_bets_clear_batch_size = historical_bets_clear_batch_size;
     }
 
+    if let Some(leaderboard_size) = msg.leaderboard_size {
+        config.leaderboard_size = leaderboard_size;
+    }
+
+    if let Some(leaderboard_epoch_blocks) = msg.leaderboard_epoch_blocks {
+        config.leaderboard_epoch_blocks = leaderboard_epoch_blocks;
+    }
+
+    if let Some(creator_bond_percents) = msg.creator_bond_percents {
+        config.creator_bond_percents = creator_bond_percents;
+    }
+
+    if let Some(creator_bond_slash_percent) = msg.creator_bond_slash_percent {
+        config.creator_bond_slash_percent = creator_bond_slash_percent;
+    }
+
+    if let Some(drand_public_key) = msg.drand_public_key {
+        config.drand_public_key = drand_public_key;
+    }
+
+    if let Some(drand_genesis_time) = msg.drand_genesis_time {
+        config.drand_genesis_time = drand_genesis_time;
+    }
+
+    if let Some(drand_period_seconds) = msg.drand_period_seconds {
+        config.drand_period_seconds = drand_period_seconds;
+    }
+
+    if let Some(beacon_round_delay) = msg.beacon_round_delay {
+        config.beacon_round_delay = beacon_round_delay;
+    }
+
+    if let Some(max_pool_fills) = msg.max_pool_fills {
+        config.max_pool_fills = max_pool_fills;
+    }
+
+    if let Some(referral_percent) = msg.referral_percent {
+        config.referral_percent = referral_percent;
+    }
+
+    if let Some(fee_tiers) = msg.fee_tiers {
+        config.fee_tiers = fee_tiers;
+    }
+
+    if let Some(fee_tier_window_blocks) = msg.fee_tier_window_blocks {
+        config.fee_tier_window_blocks = fee_tier_window_blocks;
+    }
+
+    if let Some(jackpot_percent) = msg.jackpot_percent {
+        config.jackpot_percent = jackpot_percent;
+    }
+
+    if let Some(jackpot_odds) = msg.jackpot_odds {
+        config.jackpot_odds = jackpot_odds;
+    }
+
+    if let Some(limit_loosening_cooldown) = msg.limit_loosening_cooldown {
+        config.limit_loosening_cooldown = limit_loosening_cooldown;
+    }
+
     let _ = config.validate()?;
     store_config(deps.storage, &config)?;
 
@@ -418,14 +2144,324 @@ fn save_historical_bet(
     config: &Config,
     bet: HistoricalBet,
 ) -> StdResult<()> {
-    let mut historical_bets = load_historical_bets(storage)?;
-    if historical_bets.len() >= config.historical_bets_max_storage_size as usize {
-        let crear_batch_size = config.historical_bets_clear_batch_size as usize;
-        historical_bets.drain(0..crear_batch_size);
+    let historical_bets_state = load_historical_bets_state(storage)?;
+    if historical_bets_state.len() >= config.historical_bets_max_storage_size {
+        remove_oldest_historical_bets(storage, config.historical_bets_clear_batch_size)?;
+    }
+
+    let seq = append_historical_bet(storage, &bet)?;
+    store_bet_location(storage, &bet.id, &BetLocation::Historical { seq })?;
+
+    Ok(())
+}
+
+// settles stats of everyone who took part in the completed game
+fn update_player_stats(
+    storage: &mut dyn Storage,
+    config: &Config,
+    block: u64,
+    bet: &HistoricalBet,
+) -> StdResult<()> {
+    let mut participants: Vec<&String> = vec![&bet.owner];
+    let pool_responders: Vec<String> = bet.pool.iter().map(|f| f.responder.to_string()).collect();
+    for addr in bet
+        .responder
+        .iter()
+        .chain(pool_responders.iter())
+        .chain(bet.liquidator.iter())
+    {
+        if !participants.contains(&addr) {
+            participants.push(addr);
+        }
+    }
+
+    for addr in participants {
+        record_player_stats(
+            storage,
+            config,
+            block,
+            &Addr::unchecked(addr),
+            &bet.asset.denom,
+            |stats| stats.settle(bet, addr),
+        )?;
+
+        // losses count against loss limits on the day the game is completed
+        let staked = bet.staked_by(addr)?;
+        let received = bet.received_by(addr)?;
+        if staked > received {
+            update_daily_usage(
+                storage,
+                &Addr::unchecked(addr),
+                &bet.asset.denom,
+                usage_day(bet.completed_at),
+                |usage| {
+                    usage.lost = usage.lost.checked_add(staked - received)?;
+                    Ok(())
+                },
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+// applies the change to all-time and current epoch stats of the player and refreshes leaderboards
+fn record_player_stats<F>(
+    storage: &mut dyn Storage,
+    config: &Config,
+    block: u64,
+    player: &Addr,
+    denom: &str,
+    action: F,
+) -> StdResult<()>
+where
+    F: Fn(&mut PlayerStats) -> StdResult<()>,
+{
+    let epoch = config.leaderboard_epoch(block);
+
+    let mut stats = load_player_stats(storage, player, denom)?;
+    action(&mut stats)?;
+    store_player_stats(storage, player, denom, &stats)?;
+
+    let mut epoch_stats = load_epoch_player_stats(storage, epoch, player, denom)?;
+    action(&mut epoch_stats)?;
+    store_epoch_player_stats(storage, epoch, player, denom, &epoch_stats)?;
+
+    for metric in LeaderboardMetric::all() {
+        for (period, period_stats) in [(None, &stats), (Some(epoch), &epoch_stats)] {
+            let mut leaderboard = load_leaderboard(storage, denom, &metric, period)?;
+            leaderboard.update(player, metric.value(period_stats), config.leaderboard_size);
+            store_leaderboard(storage, denom, &metric, period, &leaderboard)?;
+        }
+    }
+
+    Ok(())
+}
+
+// builds bank message for the recipient and remembers how much of it is left after terra tax
+// pool responders share the amount by their fills with rounding leftovers going to the last one,
+// a bet without pool pays it to its only responder
+fn send_responder_payouts(
+    querier: &QuerierWrapper,
+    bet: &OngoingBet,
+    role: PayoutRole,
+    asset: &Asset,
+) -> StdResult<(Vec<CosmosMsg>, Vec<Payout>)> {
+    if bet.pool.is_empty() {
+        let (msg, payout) = send_payout(querier, &bet.bet_responder, role, asset)?;
+        return Ok((vec![msg], vec![payout]));
+    }
+
+    let mut messages = vec![];
+    let mut payouts = vec![];
+    let mut rest = asset.clone();
+    for (i, fill) in bet.pool.iter().enumerate() {
+        let share = if i + 1 == bet.pool.len() {
+            rest.clone()
+        } else {
+            Asset {
+                denom: asset.denom.clone(),
+                amount: asset
+                    .amount
+                    .multiply_ratio(fill.amount, bet.responder_stake),
+            }
+        };
+        rest.checked_sub(&share)?;
+
+        if share.amount.is_zero() {
+            continue;
+        }
+
+        let (msg, payout) = send_payout(querier, &fill.responder, role.clone(), &share)?;
+        messages.push(msg);
+        payouts.push(payout);
+    }
+
+    Ok((messages, payouts))
+}
+
+// adds the fee slice to the denom jackpot and draws it for the players of the game,
+// the roll hits once in jackpot_odds games and then picks one of the players
+fn feed_jackpot(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    bet_id: &str,
+    bet: &OngoingBet,
+    contribution: &Asset,
+    seed: &str,
+) -> StdResult<Option<JackpotWinner>> {
+    let jackpot = load_jackpot(storage, &contribution.denom)?.checked_add(contribution.amount)?;
+    if jackpot.is_zero() {
+        return Ok(None);
+    }
+
+    let roll = jackpot_roll(seed, bet_id, bet);
+    if roll.checked_rem(config.jackpot_odds) != Some(0) {
+        store_jackpot(storage, &contribution.denom, jackpot)?;
+        return Ok(None);
+    }
+
+    let mut players = vec![bet.bet_creator.clone()];
+    if bet.pool.is_empty() {
+        players.push(bet.bet_responder.clone());
+    } else {
+        players.extend(bet.pool.iter().map(|f| f.responder.clone()));
+    }
+    let winner = players[((roll / config.jackpot_odds) % players.len() as u64) as usize].clone();
+
+    let jackpot_winner = JackpotWinner {
+        bet_id: bet_id.to_string(),
+        winner,
+        asset: Asset {
+            denom: contribution.denom.clone(),
+            amount: jackpot,
+        },
+        won_at: env.block.time.seconds(),
+    };
+    store_jackpot(storage, &contribution.denom, Uint128::zero())?;
+    append_jackpot_winner(storage, &jackpot_winner)?;
+
+    Ok(Some(jackpot_winner))
+}
+
+// stake must fit every limit of its denom, loss limit assumes the stake is lost
+fn check_player_limits(
+    storage: &dyn Storage,
+    time: Timestamp,
+    player: &Addr,
+    stake: &Asset,
+) -> Result<(), ContractError> {
+    let mut limits = load_player_limits(storage, player)?;
+    limits.apply_pending(time.seconds());
+    if limits.is_excluded(time.seconds()) {
+        return Err(ContractError::PlayerIsSelfExcluded {});
     }
 
-    historical_bets.push(bet);
-    store_historical_bets(storage, &historical_bets)?;
+    let day = usage_day(time.seconds());
+    for limit in limits.limits.iter().filter(|l| l.denom == stake.denom) {
+        let usage = load_window_usage(storage, player, limit, day)?;
+        if matches!(limit.max_wager, Some(max) if usage.wagered.checked_add(stake.amount)? > max) {
+            return Err(ContractError::WagerLimitExceeded {});
+        }
 
+        if matches!(limit.max_loss, Some(max) if usage.lost.checked_add(stake.amount)? > max) {
+            return Err(ContractError::LossLimitExceeded {});
+        }
+    }
+
+    Ok(())
+}
+
+// checks the stake against player limits and counts it as wagered today
+fn use_wager_limits(
+    storage: &mut dyn Storage,
+    time: Timestamp,
+    player: &Addr,
+    stake: &Asset,
+) -> Result<(), ContractError> {
+    check_player_limits(storage, time, player, stake)?;
+    update_daily_usage(
+        storage,
+        player,
+        &stake.denom,
+        usage_day(time.seconds()),
+        |usage| {
+            usage.wagered = usage.wagered.checked_add(stake.amount)?;
+            Ok(())
+        },
+    )?;
     Ok(())
 }
+
+// referrer given on the first use is kept, later ones are ignored
+fn bind_referrer(
+    storage: &mut dyn Storage,
+    player: &Addr,
+    referrer: Option<Addr>,
+) -> Result<Option<Addr>, ContractError> {
+    let referrer = match referrer {
+        Some(r) => r,
+        None => return Ok(may_load_referrer(storage, player)?),
+    };
+
+    if referrer.eq(player) {
+        return Err(ContractError::ForbiddenToReferYourself {});
+    }
+
+    if let Some(bound) = may_load_referrer(storage, player)? {
+        return Ok(Some(bound));
+    }
+
+    store_referrer(storage, player, &referrer)?;
+    Ok(Some(referrer))
+}
+
+// every player's referrer gets referral_percent of the treasury fee part matching their stake,
+// shares stay in the contract until claimed
+fn accrue_referral_shares(
+    storage: &mut dyn Storage,
+    config: &Config,
+    bet: &OngoingBet,
+    treasury_amount: &mut Asset,
+) -> StdResult<Vec<Payout>> {
+    if config.referral_percent == 0 || treasury_amount.amount.is_zero() {
+        return Ok(vec![]);
+    }
+
+    let fee = treasury_amount.amount;
+    let creator_stake = bet.asset.amount.checked_sub(bet.responder_stake)?;
+    let mut players = vec![(bet.bet_creator.clone(), creator_stake)];
+    if bet.pool.is_empty() {
+        players.push((bet.bet_responder.clone(), bet.responder_stake));
+    } else {
+        players.extend(bet.pool.iter().map(|f| (f.responder.clone(), f.amount)));
+    }
+
+    let mut payouts = vec![];
+    for (player, stake) in players {
+        let referrer = match may_load_referrer(storage, &player)? {
+            Some(r) => r,
+            None => continue,
+        };
+
+        let share = Asset {
+            denom: treasury_amount.denom.clone(),
+            amount: fee
+                .multiply_ratio(stake, bet.asset.amount)
+                .multiply_ratio(config.referral_percent, 100u8),
+        };
+        if share.amount.is_zero() {
+            continue;
+        }
+
+        treasury_amount.checked_sub(&share)?;
+        add_referral_earnings(storage, &referrer, &share)?;
+        payouts.push(Payout {
+            recipient: referrer.to_string(),
+            role: PayoutRole::Referrer,
+            gross_amount: share.amount,
+            net_amount: share.amount,
+        });
+    }
+
+    Ok(payouts)
+}
+
+fn limit_attr(limit: &Option<Uint128>) -> String {
+    limit.map(|l| l.to_string()).unwrap_or_default()
+}
+
+fn side_attr(side: &Option<FlipSide>) -> String {
+    side.as_ref()
+        .map(|s| s.u8().to_string())
+        .unwrap_or_default()
+}
+
+fn picks_attr(picks: &[u8]) -> String {
+    picks
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
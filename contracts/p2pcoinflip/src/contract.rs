@@ -1,16 +1,21 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint64,
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint64,
 };
+use cw0::Duration;
 use cw2::set_contract_version;
+use tefiluck::config::CoinLimit;
 
 use crate::{
     commands,
     error::ContractError,
     msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
     queries,
-    state::{store_config, store_pending_bets_count, CoinLimit, Config},
+    state::{
+        store_config, store_pending_bets_count, Config, GameMode, Odds, PlaceBetParams,
+        UpdatePendingBetParams, WagerLimit,
+    },
 };
 
 // version info for migration info
@@ -40,12 +45,30 @@ pub fn instantiate(
         min_bet_amounts: min_bet_amounts,
         min_blocks_until_liquidation: msg.min_blocks_until_liquidation,
         max_blocks_until_liquidation: msg.max_blocks_until_liquidation,
+        min_seconds_until_liquidation: msg.min_seconds_until_liquidation,
+        max_seconds_until_liquidation: msg.max_seconds_until_liquidation,
         blocks_for_responder_liquidation: msg.blocks_for_responder_liquidation,
+        seconds_for_responder_liquidation: msg.seconds_for_responder_liquidation,
         bet_responder_liquidation_percent: msg.bet_responder_liquidation_percent,
         bet_liquidator_percent: msg.bet_liquidator_percent,
         treasury_liquidation_percent: msg.treasury_liquidation_percent,
         historical_bets_max_storage_size: msg.historical_bets_max_storage_size,
         historical_bets_clear_batch_size: msg.historical_bets_clear_batch_size,
+        leaderboard_size: msg.leaderboard_size,
+        leaderboard_epoch_blocks: msg.leaderboard_epoch_blocks,
+        creator_bond_percents: msg.creator_bond_percents,
+        creator_bond_slash_percent: msg.creator_bond_slash_percent,
+        drand_public_key: msg.drand_public_key,
+        drand_genesis_time: msg.drand_genesis_time,
+        drand_period_seconds: msg.drand_period_seconds,
+        beacon_round_delay: msg.beacon_round_delay,
+        max_pool_fills: msg.max_pool_fills,
+        referral_percent: msg.referral_percent,
+        fee_tiers: msg.fee_tiers,
+        fee_tier_window_blocks: msg.fee_tier_window_blocks,
+        jackpot_percent: msg.jackpot_percent,
+        jackpot_odds: msg.jackpot_odds,
+        limit_loosening_cooldown: msg.limit_loosening_cooldown,
     };
 
     let _ = config.validate()?;
@@ -67,21 +90,111 @@ pub fn execute(
         ExecuteMsg::PlaceBet {
             signature,
             blocks_until_liquidation,
-        } => commands::place_bet(
-            deps,
-            env,
-            info,
-            signature.to_lowercase(),
-            blocks_until_liquidation,
-        ),
+            until_liquidation,
+            relayer_tip,
+            relayer,
+            creator_bond,
+            mode,
+            odds,
+            referrer,
+        } => {
+            let referrer = match referrer {
+                Some(referrer) => Some(deps.api.addr_validate(&referrer)?),
+                None => None,
+            };
+            let relayer = match relayer {
+                Some(relayer) => Some(deps.api.addr_validate(&relayer)?),
+                None => None,
+            };
+            let until_liquidation =
+                liquidation_deadline(blocks_until_liquidation, until_liquidation)?
+                    .ok_or_else(|| StdError::generic_err("until_liquidation is required"))?;
+            let params = PlaceBetParams {
+                relayer_tip: relayer_tip.unwrap_or_default(),
+                relayer,
+                creator_bond: creator_bond.unwrap_or_default(),
+                mode: mode.unwrap_or(GameMode::Classic),
+                odds: odds.unwrap_or_else(Odds::coinflip),
+                ..PlaceBetParams::new(signature.to_lowercase(), until_liquidation)
+            };
+            commands::place_bet(deps, env, info, params, referrer)
+        }
         ExecuteMsg::RespondBet {
             bet_owner,
             bet_id,
             side,
+            referrer,
+        } => {
+            let bet_owner = deps.api.addr_validate(&bet_owner)?;
+            let referrer = match referrer {
+                Some(referrer) => Some(deps.api.addr_validate(&referrer)?),
+                None => None,
+            };
+            commands::respond_bet(
+                deps,
+                env,
+                info,
+                bet_owner,
+                bet_id.to_lowercase(),
+                side,
+                referrer,
+            )
+        }
+        ExecuteMsg::RespondBestMatch { filter, side } => {
+            commands::respond_best_match(deps, env, info, filter, side)
+        }
+        ExecuteMsg::RespondDiceBet {
+            bet_owner,
+            bet_id,
+            picks,
+        } => {
+            let bet_owner = deps.api.addr_validate(&bet_owner)?;
+            commands::respond_dice_bet(deps, env, info, bet_owner, bet_id.to_lowercase(), picks)
+        }
+        ExecuteMsg::CommitRespondBet {
+            bet_owner,
+            bet_id,
+            signature,
+        } => {
+            let bet_owner = deps.api.addr_validate(&bet_owner)?;
+            commands::commit_respond_bet(
+                deps,
+                env,
+                info,
+                bet_owner,
+                bet_id.to_lowercase(),
+                signature.to_lowercase(),
+            )
+        }
+        ExecuteMsg::FillPoolBet {
+            bet_owner,
+            bet_id,
+            side,
         } => {
             let bet_owner = deps.api.addr_validate(&bet_owner)?;
-            commands::respond_bet(deps, env, info, bet_owner, bet_id.to_lowercase(), side)
+            commands::fill_pool_bet(deps, env, info, bet_owner, bet_id.to_lowercase(), side)
+        }
+        ExecuteMsg::WithdrawPoolFill { bet_owner, bet_id } => {
+            let bet_owner = deps.api.addr_validate(&bet_owner)?;
+            commands::withdraw_pool_fill(deps, info, bet_owner, bet_id.to_lowercase())
         }
+        ExecuteMsg::ClosePoolBet { bet_id } => {
+            commands::close_pool_bet(deps, env, info, bet_id.to_lowercase())
+        }
+        ExecuteMsg::SettleWithBeacon {
+            bet_id,
+            round,
+            previous_signature,
+            signature,
+        } => commands::settle_with_beacon(
+            deps,
+            env,
+            info,
+            bet_id.to_lowercase(),
+            round,
+            previous_signature.to_lowercase(),
+            signature.to_lowercase(),
+        ),
         ExecuteMsg::ResolveBet { bet_id, passphrase } => {
             commands::resolve_bet(deps, env, info, bet_id.to_lowercase(), passphrase)
         }
@@ -89,39 +202,84 @@ pub fn execute(
             commands::liquidate_bet(deps, env, info, bet_id.to_lowercase())
         }
         ExecuteMsg::WithdrawPendingBet { bet_id } => {
-            commands::withdraw_pending_bet(deps, info, bet_id.to_lowercase())
-        }
-        ExecuteMsg::UpdateConfig {
-            owner,
-            treasury,
-            treasury_tax_percent,
-            max_bets_by_addr,
-            min_bet_amounts,
-            min_blocks_until_liquidation,
-            max_blocks_until_liquidation,
-            blocks_for_responder_liquidation,
-            bet_responder_liquidation_percent,
-            bet_liquidator_percent,
-            treasury_liquidation_percent,
-            historical_bets_max_storage_size,
-            historical_bets_clear_batch_size,
-        } => commands::update_config(
+            commands::withdraw_pending_bet(deps, env, info, bet_id.to_lowercase())
+        }
+        ExecuteMsg::UpdatePendingBet {
+            bet_id,
+            add_funds,
+            withdraw_funds,
+            add_bond,
+            blocks_until_liquidation,
+            until_liquidation,
+        } => {
+            let params = UpdatePendingBetParams {
+                add_funds: add_funds.unwrap_or_default(),
+                withdraw_funds: withdraw_funds.unwrap_or_default(),
+                add_bond: add_bond.unwrap_or_default(),
+                until_liquidation: liquidation_deadline(
+                    blocks_until_liquidation,
+                    until_liquidation,
+                )?,
+            };
+            commands::update_pending_bet(deps, env, info, bet_id.to_lowercase(), params)
+        }
+        ExecuteMsg::ForfeitBet { bet_id } => {
+            commands::forfeit_bet(deps, env, info, bet_id.to_lowercase())
+        }
+        ExecuteMsg::ClaimReferralEarnings { denom } => {
+            commands::claim_referral_earnings(deps, info, denom)
+        }
+        ExecuteMsg::SelfExclude { until } => commands::self_exclude(deps, env, info, until),
+        ExecuteMsg::SetWagerLimit {
+            denom,
+            window_days,
+            max_wager,
+            max_loss,
+        } => commands::set_wager_limit(
             deps,
+            env,
             info,
-            owner,
-            treasury,
-            treasury_tax_percent,
-            max_bets_by_addr,
-            min_bet_amounts,
-            min_blocks_until_liquidation,
-            max_blocks_until_liquidation,
-            blocks_for_responder_liquidation,
-            bet_responder_liquidation_percent,
-            bet_liquidator_percent,
-            treasury_liquidation_percent,
-            historical_bets_max_storage_size,
-            historical_bets_clear_batch_size,
+            WagerLimit {
+                denom,
+                window_days,
+                max_wager,
+                max_loss,
+            },
         ),
+        ExecuteMsg::OfferRematch { bet_id, signature } => {
+            commands::offer_rematch(deps, info, bet_id.to_lowercase(), signature.to_lowercase())
+        }
+        ExecuteMsg::AcceptRematch {
+            bet_owner,
+            bet_id,
+            side,
+        } => {
+            let bet_owner = deps.api.addr_validate(&bet_owner)?;
+            commands::accept_rematch(deps, env, info, bet_owner, bet_id.to_lowercase(), side)
+        }
+        ExecuteMsg::DeclineRematch { bet_owner, bet_id } => {
+            let bet_owner = deps.api.addr_validate(&bet_owner)?;
+            commands::decline_rematch(deps, env, info, bet_owner, bet_id.to_lowercase())
+        }
+        ExecuteMsg::PostOffer {
+            side,
+            min_amount,
+            max_amount,
+            max_until_liquidation,
+        } => commands::post_offer(
+            deps,
+            env,
+            info,
+            side,
+            min_amount,
+            max_amount,
+            max_until_liquidation,
+        ),
+        ExecuteMsg::CancelOffer { offer_id } => commands::cancel_offer(deps, info, offer_id),
+        ExecuteMsg::WithdrawFromOffer { offer_id, amount } => {
+            commands::withdraw_from_offer(deps, info, offer_id, amount)
+        }
+        ExecuteMsg::UpdateConfig(msg) => commands::update_config(deps, info, *msg),
     }
 }
 
@@ -150,23 +308,68 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             skip,
             limit,
             exclude_address,
-        } => {
-            let current_block = env.block.height;
-            to_binary(&queries::query_public_liquidatable_bets(
-                deps,
-                current_block,
-                skip,
-                limit,
-                exclude_address,
-            )?)
-        }
-        QueryMsg::HistoricalBets {
+        } => to_binary(&queries::query_public_liquidatable_bets(
+            deps,
+            &env.block,
             skip,
             limit,
-            address,
-        } => {
+            exclude_address,
+        )?),
+        QueryMsg::HistoricalBets { filter } => {
+            to_binary(&queries::query_historical_bet(deps, filter)?)
+        }
+        QueryMsg::Bet { bet_id } => to_binary(&queries::query_bet(deps, bet_id.to_lowercase())?),
+        QueryMsg::PlayerStats { address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_binary(&queries::query_player_stats(deps, addr)?)
+        }
+        QueryMsg::Leaderboard {
+            denom,
+            metric,
+            period,
+        } => to_binary(&queries::query_leaderboard(
+            deps,
+            env.block.height,
+            denom,
+            metric,
+            period,
+        )?),
+        QueryMsg::Offers { denom, skip, limit } => {
+            to_binary(&queries::query_offers(deps, denom, skip, limit)?)
+        }
+        QueryMsg::OffersByAddr { address } => {
             let addr = deps.api.addr_validate(&address)?;
-            to_binary(&queries::query_historical_bet(deps, skip, limit, addr)?)
+            to_binary(&queries::query_offers_by_addr(deps, addr)?)
         }
+        QueryMsg::Referral { address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_binary(&queries::query_referral(deps, addr)?)
+        }
+        QueryMsg::FeeTier { address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_binary(&queries::query_fee_tier(deps, env.block.height, addr)?)
+        }
+        QueryMsg::Jackpot {} => to_binary(&queries::query_jackpot(deps)?),
+        QueryMsg::JackpotWinners { skip, limit } => {
+            to_binary(&queries::query_jackpot_winners(deps, skip, limit)?)
+        }
+        QueryMsg::PlayerLimits { address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_binary(&queries::query_player_limits(deps, env.block.time, addr)?)
+        }
+    }
+}
+
+// blocks_until_liquidation is the pre-Duration way to give a deadline in blocks
+fn liquidation_deadline(
+    blocks_until_liquidation: Option<u64>,
+    until_liquidation: Option<Duration>,
+) -> StdResult<Option<Duration>> {
+    match (blocks_until_liquidation, until_liquidation) {
+        (Some(_), Some(_)) => Err(StdError::generic_err(
+            "blocks_until_liquidation and until_liquidation are mutually exclusive",
+        )),
+        (Some(blocks), None) => Ok(Some(Duration::Height(blocks))),
+        (None, until_liquidation) => Ok(until_liquidation),
     }
 }
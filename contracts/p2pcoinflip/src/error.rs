@@ -21,9 +21,6 @@ pub enum ContractError {
     #[error("[1003]: You are not allowed to play vs yourself")]
     ForbiddenToPlayVSYourself {},
 
-    #[error("[1004]: Only bet creator allowed to resolve bet")]
-    OnlyBetCreatorAllowedToResolve {},
-
     #[error("[1005]: Signatures mismatch")]
     SignatureMismatch {},
 
@@ -50,4 +47,73 @@ pub enum ContractError {
 
     #[error("[1013]: This game has already been resolved")]
     GameWasAlreadyResolved {},
+
+    #[error("[1014]: Only bet creator allowed to forfeit bet")]
+    OnlyBetCreatorAllowedToForfeit {},
+
+    #[error("[1015]: Bet responder is not allowed to liquidate a bet they have not revealed")]
+    ForbiddenForBetResponderToLiquidateHimself {},
+
+    #[error("[1016]: Bet was placed in another game mode")]
+    GameModeMismatch {},
+
+    #[error("[1017]: Beacon round does not match the round of this bet")]
+    BeaconRoundMismatch {},
+
+    #[error("[1018]: Beacon signature does not match drand public key")]
+    BeaconSignatureMismatch {},
+
+    #[error("[1019]: Flip side does not match the side of this pool")]
+    PoolSideMismatch {},
+
+    #[error("[1020]: Pool fill exceeds the unfilled part of the bet")]
+    PoolFillExceedsRemainingStake {},
+
+    #[error("[1021]: Max number of pool responders was reached")]
+    PoolIsFull {},
+
+    #[error("[1022]: Pool bet already has responders, close it instead")]
+    PoolBetHasFills {},
+
+    #[error("[1023]: No pending bet matches the filter and provided funds")]
+    NoMatchingBet {},
+
+    #[error("[1024]: This rematch is offered to another player")]
+    RematchIsPrivate {},
+
+    #[error("[1025]: Bet is not a rematch")]
+    NotARematch {},
+
+    #[error("[1026]: Only the opponent can decline a rematch before it expires")]
+    RematchIsNotExpiredYet {},
+
+    #[error("[1027]: Only bet players allowed to offer a rematch")]
+    OnlyBetPlayersAllowedToOfferRematch {},
+
+    #[error("[1028]: Attached funds must match the stake increase of the bet")]
+    TopUpMismatch {},
+
+    #[error("[1029]: Player is not allowed to refer themselves")]
+    ForbiddenToReferYourself {},
+
+    #[error("[1030]: No referral earnings to claim")]
+    NoReferralEarnings {},
+
+    #[error("[1031]: Player is self-excluded from betting")]
+    PlayerIsSelfExcluded {},
+
+    #[error("[1032]: Bet exceeds the wager limit of the player")]
+    WagerLimitExceeded {},
+
+    #[error("[1033]: Bet may exceed the loss limit of the player")]
+    LossLimitExceeded {},
+
+    #[error("[1034]: Self-exclusion can only be extended")]
+    SelfExclusionCanNotBeShortened {},
+
+    #[error("[1035]: Player has no fill in this pool")]
+    PoolFillNotFound {},
+
+    #[error("[1036]: Rematch has expired")]
+    RematchExpired {},
 }
@@ -9,6 +9,3 @@ pub use crate::error::ContractError;
 
 #[cfg(test)]
 mod testing;
-
-#[cfg(test)]
-mod mock_querier;
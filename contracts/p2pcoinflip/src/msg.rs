@@ -1,11 +1,19 @@
 use std::collections::HashMap;
 
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, StdResult, Uint128};
+use cw0::{Duration, Expiration};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tefiluck::asset::Asset;
+use tefiluck::{
+    asset::Asset,
+    config::{CoinLimit, InstantiateCoinLimitMsg},
+};
 
-use crate::state::{CoinLimit, HistoricalBet, OngoingBet, PendingBet};
+use crate::state::{
+    CreatorBondPercent, CreatorReliability, FeeTier, GameMode, GameOutcome, HistoricalBet,
+    JackpotWinner, LeaderboardEntry, LeaderboardMetric, LimitUsage, Odds, OngoingBet, PendingBet,
+    PendingWagerLimit, PlayerStats, PoolFill, Rematch, StandingOffer, WagerLimit,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -15,18 +23,31 @@ pub struct InstantiateMsg {
     pub min_bet_amounts: Vec<InstantiateCoinLimitMsg>,
     pub min_blocks_until_liquidation: u64,
     pub max_blocks_until_liquidation: u64,
+    pub min_seconds_until_liquidation: u64,
+    pub max_seconds_until_liquidation: u64,
     pub blocks_for_responder_liquidation: u64,
+    pub seconds_for_responder_liquidation: u64,
     pub bet_responder_liquidation_percent: u8,
     pub bet_liquidator_percent: u8,
     pub treasury_liquidation_percent: u8,
     pub historical_bets_max_storage_size: u64,
     pub historical_bets_clear_batch_size: u64,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateCoinLimitMsg {
-    pub denom: String,
-    pub min_amount: u64,
+    pub leaderboard_size: u64,
+    pub leaderboard_epoch_blocks: u64,
+    pub creator_bond_percents: Vec<CreatorBondPercent>,
+    pub creator_bond_slash_percent: u8,
+    pub drand_public_key: String,
+    pub drand_genesis_time: u64,
+    pub drand_period_seconds: u64,
+    // in drand rounds, not blocks, counted from the round current when the bet is responded
+    pub beacon_round_delay: u64,
+    pub max_pool_fills: u64,
+    pub referral_percent: u8,
+    pub fee_tiers: Vec<FeeTier>,
+    pub fee_tier_window_blocks: u64,
+    pub jackpot_percent: u8,
+    pub jackpot_odds: u64,
+    pub limit_loosening_cooldown: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -34,12 +55,56 @@ pub struct InstantiateCoinLimitMsg {
 pub enum ExecuteMsg {
     PlaceBet {
         signature: String,
-        blocks_until_liquidation: u64,
+        // legacy deadline in blocks, give either it or until_liquidation
+        blocks_until_liquidation: Option<u64>,
+        until_liquidation: Option<Duration>,
+        relayer_tip: Option<Uint128>,
+        // the tip goes only to this address and only if it revealed the creator passphrase
+        relayer: Option<String>,
+        creator_bond: Option<Uint128>,
+        mode: Option<GameMode>,
+        odds: Option<Odds>,
+        referrer: Option<String>,
     },
     RespondBet {
         bet_owner: String,
         bet_id: String,
         side: u8,
+        referrer: Option<String>,
+    },
+    // only the 100 oldest pending bets, or the 100 newest when sorted by creation descending,
+    // are matched against the filter, so a fitting bet may be missed while many bets are pending
+    RespondBestMatch {
+        filter: PendingBetsFilter,
+        side: u8,
+    },
+    RespondDiceBet {
+        bet_owner: String,
+        bet_id: String,
+        picks: Vec<u8>,
+    },
+    CommitRespondBet {
+        bet_owner: String,
+        bet_id: String,
+        signature: String,
+    },
+    FillPoolBet {
+        bet_owner: String,
+        bet_id: String,
+        side: u8,
+    },
+    WithdrawPoolFill {
+        bet_owner: String,
+        bet_id: String,
+    },
+    ClosePoolBet {
+        bet_id: String,
+    },
+    SettleWithBeacon {
+        bet_id: String,
+        round: u64,
+        previous_signature: String,
+        signature: String,
     },
     ResolveBet {
         bet_id: String,
@@ -51,21 +116,94 @@ pub enum ExecuteMsg {
     WithdrawPendingBet {
         bet_id: String,
     },
-    UpdateConfig {
-        owner: Option<String>,
-        treasury: Option<String>,
-        treasury_tax_percent: Option<u8>,
-        max_bets_by_addr: Option<u64>,
-        min_bet_amounts: Option<Vec<CoinLimit>>,
-        min_blocks_until_liquidation: Option<u64>,
-        max_blocks_until_liquidation: Option<u64>,
-        blocks_for_responder_liquidation: Option<u64>,
-        bet_responder_liquidation_percent: Option<u8>,
-        bet_liquidator_percent: Option<u8>,
-        treasury_liquidation_percent: Option<u8>,
-        historical_bets_max_storage_size: Option<u64>,
-        historical_bets_clear_batch_size: Option<u64>,
+    // add_funds and add_bond are attached together as funds, withdraw_funds is refunded
+    UpdatePendingBet {
+        bet_id: String,
+        add_funds: Option<Uint128>,
+        withdraw_funds: Option<Uint128>,
+        add_bond: Option<Uint128>,
+        blocks_until_liquidation: Option<u64>,
+        until_liquidation: Option<Duration>,
+    },
+    ForfeitBet {
+        bet_id: String,
+    },
+    ClaimReferralEarnings {
+        denom: String,
     },
+    SelfExclude {
+        until: u64,
+    },
+    SetWagerLimit {
+        denom: String,
+        window_days: u64,
+        max_wager: Option<Uint128>,
+        max_loss: Option<Uint128>,
+    },
+    OfferRematch {
+        bet_id: String,
+        signature: String,
+    },
+    AcceptRematch {
+        bet_owner: String,
+        bet_id: String,
+        side: u8,
+    },
+    DeclineRematch {
+        bet_owner: String,
+        bet_id: String,
+    },
+    PostOffer {
+        side: u8,
+        min_amount: Uint128,
+        max_amount: Uint128,
+        max_until_liquidation: Duration,
+    },
+    CancelOffer {
+        offer_id: u64,
+    },
+    WithdrawFromOffer {
+        offer_id: u64,
+        amount: Uint128,
+    },
+    UpdateConfig(Box<UpdateConfigMsg>),
+}
+
+// every field left empty keeps its current config value
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UpdateConfigMsg {
+    pub owner: Option<String>,
+    pub treasury: Option<String>,
+    pub treasury_tax_percent: Option<u8>,
+    pub max_bets_by_addr: Option<u64>,
+    pub min_bet_amounts: Option<Vec<CoinLimit>>,
+    pub min_blocks_until_liquidation: Option<u64>,
+    pub max_blocks_until_liquidation: Option<u64>,
+    pub min_seconds_until_liquidation: Option<u64>,
+    pub max_seconds_until_liquidation: Option<u64>,
+    pub blocks_for_responder_liquidation: Option<u64>,
+    pub seconds_for_responder_liquidation: Option<u64>,
+    pub bet_responder_liquidation_percent: Option<u8>,
+    pub bet_liquidator_percent: Option<u8>,
+    pub treasury_liquidation_percent: Option<u8>,
+    pub historical_bets_max_storage_size: Option<u64>,
+    pub historical_bets_clear_batch_size: Option<u64>,
+    pub leaderboard_size: Option<u64>,
+    pub leaderboard_epoch_blocks: Option<u64>,
+    pub creator_bond_percents: Option<Vec<CreatorBondPercent>>,
+    pub creator_bond_slash_percent: Option<u8>,
+    pub drand_public_key: Option<String>,
+    pub drand_genesis_time: Option<u64>,
+    pub drand_period_seconds: Option<u64>,
+    // in drand rounds, not blocks, at least 2
+    pub beacon_round_delay: Option<u64>,
+    pub max_pool_fills: Option<u64>,
+    pub referral_percent: Option<u8>,
+    pub fee_tiers: Option<Vec<FeeTier>>,
+    pub fee_tier_window_blocks: Option<u64>,
+    pub jackpot_percent: Option<u8>,
+    pub jackpot_odds: Option<u64>,
+    pub limit_loosening_cooldown: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -95,10 +233,49 @@ pub enum QueryMsg {
         exclude_address: Option<String>,
     },
     HistoricalBets {
+        filter: HistoricalBetsFilter,
+    },
+    Bet {
+        bet_id: String,
+    },
+    PlayerStats {
+        address: String,
+    },
+    Leaderboard {
+        denom: String,
+        metric: LeaderboardMetric,
+        period: LeaderboardPeriod,
+    },
+    Offers {
+        denom: Option<String>,
         skip: u32,
-        limit: u32,
+        limit: Option<u32>,
+    },
+    OffersByAddr {
         address: String,
     },
+    Referral {
+        address: String,
+    },
+    FeeTier {
+        address: String,
+    },
+    Jackpot {},
+    JackpotWinners {
+        skip: u32,
+        limit: Option<u32>,
+    },
+    PlayerLimits {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardPeriod {
+    AllTime {},
+    // current epoch is used when not specified
+    Epoch { epoch: Option<u64> },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -110,12 +287,30 @@ pub struct ConfigResponse {
     pub min_bet_amounts: Vec<CoinLimit>,
     pub min_blocks_until_liquidation: u64,
     pub max_blocks_until_liquidation: u64,
+    pub min_seconds_until_liquidation: u64,
+    pub max_seconds_until_liquidation: u64,
     pub blocks_for_responder_liquidation: u64,
+    pub seconds_for_responder_liquidation: u64,
     pub bet_responder_liquidation_percent: u8,
     pub bet_liquidator_percent: u8,
     pub treasury_liquidation_percent: u8,
     pub historical_bets_max_storage_size: u64,
     pub historical_bets_clear_batch_size: u64,
+    pub leaderboard_size: u64,
+    pub leaderboard_epoch_blocks: u64,
+    pub creator_bond_percents: Vec<CreatorBondPercent>,
+    pub creator_bond_slash_percent: u8,
+    pub drand_public_key: String,
+    pub drand_genesis_time: u64,
+    pub drand_period_seconds: u64,
+    pub beacon_round_delay: u64,
+    pub max_pool_fills: u64,
+    pub referral_percent: u8,
+    pub fee_tiers: Vec<FeeTier>,
+    pub fee_tier_window_blocks: u64,
+    pub jackpot_percent: u8,
+    pub jackpot_odds: u64,
+    pub limit_loosening_cooldown: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -125,6 +320,10 @@ pub struct PendingBetsFilter {
     pub exclude_address: Option<String>,
     pub assets: Option<Vec<AssetFilter>>,
     pub liquidation: Option<LiquidationFilter>,
+    // creators without resolved or liquidated games have no score and never pass it
+    pub min_creator_reliability: Option<Decimal>,
+    // resolved plus liquidated games, keeps out creators whose score rests on a few games
+    pub min_creator_games: Option<u64>,
     pub sort_by: PendingBetsSort,
 }
 
@@ -161,6 +360,8 @@ pub struct AssetFilter {
 pub struct LiquidationFilter {
     pub blocks_until_liquidation_from: Option<u64>,
     pub blocks_until_liquidation_to: Option<u64>,
+    pub seconds_until_liquidation_from: Option<u64>,
+    pub seconds_until_liquidation_to: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -170,6 +371,39 @@ pub enum PendingBetsSort {
     Price { asc: bool },
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoricalBetsFilter {
+    pub skip: u32,
+    pub limit: Option<u32>,
+    pub address: Option<String>,
+    pub role: Option<HistoricalBetRole>,
+    pub result: Option<HistoricalBetResult>,
+    pub outcomes: Option<Vec<GameOutcome>>,
+    pub denoms: Option<Vec<String>>,
+    pub completed_at: Option<CompletedAtFilter>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoricalBetRole {
+    Owner,
+    Responder,
+    Liquidator,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoricalBetResult {
+    Won,
+    Lost,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CompletedAtFilter {
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AddrPendingBetsResponse {
     pub bets: Vec<PendingBetResponse>,
@@ -180,20 +414,60 @@ pub struct PendingBetResponse {
     pub owner: String,
     pub id: String,
     pub signature: String,
-    pub blocks_until_liquidation: u64,
+    pub mode: GameMode,
+    pub odds: Odds,
+    pub until_liquidation: Duration,
     pub asset: Asset,
+    pub responder_stake: Uint128,
+    pub relayer_tip: Uint128,
+    pub relayer: Option<String>,
+    pub creator_bond: Uint128,
+    pub pool_side: Option<u8>,
+    pub pool: Vec<PoolFill>,
+    pub rematch: Option<Rematch>,
     pub created_at: u64,
+    pub creator_reliability: CreatorReliabilityResponse,
 }
 
 impl PendingBetResponse {
-    pub fn new(owner: String, bet: &PendingBet) -> Self {
+    pub fn new(owner: String, bet: &PendingBet, reliability: &CreatorReliability) -> Self {
         Self {
             owner: owner,
             id: bet.id.clone(),
             signature: bet.signature.clone(),
-            blocks_until_liquidation: bet.blocks_until_liquidation,
+            mode: bet.mode.clone(),
+            odds: bet.odds.clone(),
+            until_liquidation: bet.until_liquidation,
             asset: bet.asset.clone(),
+            responder_stake: bet.responder_stake_asset().amount,
+            relayer_tip: bet.relayer_tip,
+            relayer: bet.relayer.as_ref().map(|r| r.to_string()),
+            creator_bond: bet.creator_bond,
+            pool_side: bet.pool_side.as_ref().map(|s| s.u8()),
+            pool: bet.pool.clone(),
+            rematch: bet.rematch.clone(),
             created_at: bet.created_at.seconds(),
+            creator_reliability: reliability.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreatorReliabilityResponse {
+    pub resolved_games: u64,
+    pub liquidated_games: u64,
+    // none until the creator has resolved or liquidated games
+    pub reliability: Option<Decimal>,
+    pub median_reveal_latency: Option<u64>,
+}
+
+impl From<&CreatorReliability> for CreatorReliabilityResponse {
+    fn from(reliability: &CreatorReliability) -> Self {
+        Self {
+            resolved_games: reliability.resolved_games,
+            liquidated_games: reliability.liquidated_games,
+            reliability: reliability.score(),
+            median_reveal_latency: reliability.median_reveal_latency(),
         }
     }
 }
@@ -204,12 +478,28 @@ pub struct OngoingBetResponse {
     pub signature: String,
     pub owner: String,
     pub responder: String,
-    pub responder_side: u8,
+    pub mode: GameMode,
+    pub odds: Odds,
+    pub responder_side: Option<u8>,
+    pub responder_picks: Vec<u8>,
+    pub responder_signature: Option<String>,
+    pub creator_passphrase: Option<String>,
+    pub responder_passphrase: Option<String>,
+    pub beacon_round: Option<u64>,
     pub asset: Asset,
+    pub responder_stake: Uint128,
     pub started_at_block: u64,
-    pub blocks_until_liquidation: u64,
-    pub liquidation_block: u64,
-    pub responder_liquidation_blocks_gap: u64,
+    pub until_liquidation: Duration,
+    pub liquidation: Expiration,
+    pub responder_liquidation: Expiration,
+    pub relayer_tip: Uint128,
+    pub relayer: Option<String>,
+    pub creator_revealed_by: Option<String>,
+    pub creator_revealed_late: bool,
+    pub creator_bond: Uint128,
+    pub pool: Vec<PoolFill>,
+    pub creator_rematch_signature: Option<String>,
+    pub responder_rematch_signature: Option<String>,
     pub created_at: u64,
 }
 
@@ -220,12 +510,28 @@ impl OngoingBetResponse {
             signature: bet.signature.clone(),
             owner: bet.bet_creator.to_string(),
             responder: bet.bet_responder.to_string(),
-            responder_side: bet.responder_side.u8(),
+            mode: bet.mode.clone(),
+            odds: bet.odds.clone(),
+            responder_side: bet.responder_side.as_ref().map(|s| s.u8()),
+            responder_picks: bet.responder_picks.clone(),
+            responder_signature: bet.responder_signature.clone(),
+            creator_passphrase: bet.creator_passphrase.clone(),
+            responder_passphrase: bet.responder_passphrase.clone(),
+            beacon_round: bet.beacon_round,
             asset: bet.asset.clone(),
+            responder_stake: bet.responder_stake,
             started_at_block: bet.started_at_block,
-            blocks_until_liquidation: bet.blocks_until_liquidation.clone(),
-            liquidation_block: bet.liquidation_block.clone(),
-            responder_liquidation_blocks_gap: bet.responder_liquidation_blocks_gap.clone(),
+            until_liquidation: bet.until_liquidation,
+            liquidation: bet.liquidation,
+            responder_liquidation: bet.responder_liquidation,
+            relayer_tip: bet.relayer_tip,
+            relayer: bet.relayer.as_ref().map(|r| r.to_string()),
+            creator_revealed_by: bet.creator_revealed_by.as_ref().map(|r| r.to_string()),
+            creator_revealed_late: bet.creator_revealed_late,
+            creator_bond: bet.creator_bond,
+            pool: bet.pool.clone(),
+            creator_rematch_signature: bet.creator_rematch_signature.clone(),
+            responder_rematch_signature: bet.responder_rematch_signature.clone(),
             created_at: bet.created_at.seconds(),
         }
     }
@@ -234,9 +540,156 @@ impl OngoingBetResponse {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct HistoricalBetResponse {
     pub history: Vec<HistoricalBet>,
+    pub totals: Vec<HistoricalBetsTotals>,
+}
+
+// profit and loss are counted from the filtered address point of view as the difference
+// between received payouts and staked amount, so they are zero when no address is given,
+// liquidator rewards are not part of them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoricalBetsTotals {
+    pub denom: String,
+    pub count: u64,
+    pub volume: Uint128,
+    pub profit: Uint128,
+    pub loss: Uint128,
+}
+
+impl HistoricalBetsTotals {
+    pub fn new(denom: String) -> Self {
+        Self {
+            denom,
+            count: 0,
+            volume: Uint128::zero(),
+            profit: Uint128::zero(),
+            loss: Uint128::zero(),
+        }
+    }
+
+    pub fn add_bet(&mut self, bet: &HistoricalBet, addr: Option<&String>) -> StdResult<()> {
+        self.count += 1;
+
+        // withdrawn and expired bets were never played
+        if bet.outcome.is_played() {
+            self.volume = self.volume.checked_add(bet.asset.amount)?;
+        }
+
+        if let Some(addr) = addr {
+            let received = bet.received_by(addr)?;
+            let staked = bet.staked_by(addr)?;
+            if received > staked {
+                self.profit = self.profit.checked_add(received.checked_sub(staked)?)?;
+            } else {
+                self.loss = self.loss.checked_add(staked.checked_sub(received)?)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BetState {
+    Pending(PendingBetResponse),
+    Ongoing(OngoingBetResponse),
+    Resolved(HistoricalBet),
+    Liquidated(HistoricalBet),
+    Withdrawn(HistoricalBet),
+    Forfeited(HistoricalBet),
+    Expired(HistoricalBet),
+}
+
+impl From<HistoricalBet> for BetState {
+    fn from(bet: HistoricalBet) -> Self {
+        match bet.outcome {
+            GameOutcome::Resolved => BetState::Resolved(bet),
+            GameOutcome::Liquidated => BetState::Liquidated(bet),
+            GameOutcome::Withdrawn => BetState::Withdrawn(bet),
+            GameOutcome::Forfeited => BetState::Forfeited(bet),
+            GameOutcome::Expired => BetState::Expired(bet),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlayerStatsResponse {
+    pub address: String,
+    pub stats: Vec<DenomPlayerStats>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DenomPlayerStats {
+    pub denom: String,
+    pub stats: PlayerStats,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardResponse {
+    pub denom: String,
+    pub metric: LeaderboardMetric,
+    pub epoch: Option<u64>,
+    pub entries: Vec<LeaderboardEntry>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TotalPendingBetsResponse {
     pub count: u64,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OffersResponse {
+    pub offers: Vec<StandingOffer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReferralResponse {
+    pub address: String,
+    // referrer the address itself is bound to
+    pub referrer: Option<String>,
+    pub referred_players: u64,
+    // unclaimed earnings per denom
+    pub earnings: Vec<Asset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeTierResponse {
+    pub address: String,
+    pub tiers: Vec<DenomFeeTier>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DenomFeeTier {
+    pub denom: String,
+    // volume within the fee tier window
+    pub volume: Uint128,
+    pub treasury_tax_percent: u8,
+    pub tier: Option<FeeTier>,
+    pub next_tier: Option<FeeTier>,
+    pub volume_to_next_tier: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JackpotResponse {
+    pub jackpots: Vec<Asset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JackpotWinnersResponse {
+    pub winners: Vec<JackpotWinner>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlayerLimitsResponse {
+    pub address: String,
+    pub excluded_until: Option<u64>,
+    pub limits: Vec<WagerLimitUsage>,
+    pub pending: Vec<PendingWagerLimit>,
+}
+
+// usage is summed over the limit window ending today
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WagerLimitUsage {
+    pub limit: WagerLimit,
+    pub usage: LimitUsage,
+}
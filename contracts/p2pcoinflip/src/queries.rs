@@ -1,14 +1,21 @@
-use cosmwasm_std::{Addr, Deps, StdResult};
+use cosmwasm_std::{Addr, BlockInfo, Deps, StdError, StdResult, Timestamp};
 
 use crate::{
     msg::{
-        AddrPendingBetsResponse, ConfigResponse, HistoricalBetResponse, OngoingBetResponse,
-        PendingBetResponse, PendingBetsFilter, TotalPendingBetsResponse,
+        AddrPendingBetsResponse, BetState, ConfigResponse, DenomFeeTier, DenomPlayerStats,
+        FeeTierResponse, HistoricalBetResponse, HistoricalBetsFilter, JackpotResponse,
+        JackpotWinnersResponse, LeaderboardPeriod, LeaderboardResponse, OffersResponse,
+        OngoingBetResponse, PendingBetResponse, PendingBetsFilter, PlayerLimitsResponse,
+        PlayerStatsResponse, ReferralResponse, TotalPendingBetsResponse, WagerLimitUsage,
     },
     state::{
-        load_config, load_historical_bets, load_ongoing_bet, load_pending_bets,
-        load_pending_bets_count, read_ongoing_bets_by_addr, read_pending_bets,
-        read_public_liquidatable_bets, HistoricalBet,
+        load_config, load_creator_reliability, load_historical_bet, load_leaderboard,
+        load_ongoing_bet, load_pending_bets, load_pending_bets_count, load_player_limits,
+        load_referred_count, load_window_usage, load_window_volume, may_load_bet_location,
+        may_load_referrer, read_historical_bets, read_jackpot_winners, read_jackpots, read_offers,
+        read_offers_by_addr, read_ongoing_bets_by_addr, read_pending_bets, read_player_stats,
+        read_public_liquidatable_bets, read_referral_earnings, usage_day, BetLocation,
+        LeaderboardMetric,
     },
 };
 
@@ -22,12 +29,30 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         min_bet_amounts: config.min_bet_amounts,
         min_blocks_until_liquidation: config.min_blocks_until_liquidation,
         max_blocks_until_liquidation: config.max_blocks_until_liquidation,
+        min_seconds_until_liquidation: config.min_seconds_until_liquidation,
+        max_seconds_until_liquidation: config.max_seconds_until_liquidation,
         blocks_for_responder_liquidation: config.blocks_for_responder_liquidation,
+        seconds_for_responder_liquidation: config.seconds_for_responder_liquidation,
         bet_responder_liquidation_percent: config.bet_responder_liquidation_percent,
         bet_liquidator_percent: config.bet_liquidator_percent,
         treasury_liquidation_percent: config.treasury_liquidation_percent,
         historical_bets_max_storage_size: config.historical_bets_max_storage_size,
         historical_bets_clear_batch_size: config.historical_bets_clear_batch_size,
+        leaderboard_size: config.leaderboard_size,
+        leaderboard_epoch_blocks: config.leaderboard_epoch_blocks,
+        creator_bond_percents: config.creator_bond_percents,
+        creator_bond_slash_percent: config.creator_bond_slash_percent,
+        drand_public_key: config.drand_public_key,
+        drand_genesis_time: config.drand_genesis_time,
+        drand_period_seconds: config.drand_period_seconds,
+        beacon_round_delay: config.beacon_round_delay,
+        max_pool_fills: config.max_pool_fills,
+        referral_percent: config.referral_percent,
+        fee_tiers: config.fee_tiers,
+        fee_tier_window_blocks: config.fee_tier_window_blocks,
+        jackpot_percent: config.jackpot_percent,
+        jackpot_odds: config.jackpot_odds,
+        limit_loosening_cooldown: config.limit_loosening_cooldown,
     };
 
     Ok(response)
@@ -35,12 +60,11 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 
 pub fn query_pending_bets_by_addr(deps: Deps, addr: Addr) -> StdResult<AddrPendingBetsResponse> {
     let bets = load_pending_bets(deps.storage, &addr)?;
+    let reliability = load_creator_reliability(deps.storage, &addr)?;
     let resp = bets
         .bets
         .iter()
-        .map(|bet| {
-            PendingBetResponse::new(deps.api.addr_humanize(&bet.owner).unwrap().to_string(), bet)
-        })
+        .map(|bet| PendingBetResponse::new(addr.to_string(), bet, &reliability))
         .collect();
 
     Ok(AddrPendingBetsResponse { bets: resp })
@@ -53,7 +77,8 @@ pub fn query_pending_bet_by_id(
 ) -> StdResult<PendingBetResponse> {
     let mut bets = load_pending_bets(deps.storage, &addr)?;
     let bet = bets.find_by_id(&bet_id)?;
-    let resp = PendingBetResponse::new(deps.api.addr_humanize(&bet.owner)?.to_string(), &bet);
+    let reliability = load_creator_reliability(deps.storage, &addr)?;
+    let resp = PendingBetResponse::new(addr.to_string(), &bet, &reliability);
 
     Ok(resp)
 }
@@ -65,9 +90,12 @@ pub fn query_pending_bets(
     let bets = read_pending_bets(deps.storage, deps.api, &filter)?;
     bets.iter()
         .map(|bet| {
+            let owner = deps.api.addr_humanize(&bet.owner)?;
+            let reliability = load_creator_reliability(deps.storage, &owner)?;
             Ok(PendingBetResponse::new(
-                deps.api.addr_humanize(&bet.owner)?.to_string(),
+                owner.to_string(),
                 bet,
+                &reliability,
             ))
         })
         .collect()
@@ -100,7 +128,7 @@ pub fn query_ongoing_bets_by_addr(deps: Deps, addr: Addr) -> StdResult<Vec<Ongoi
 
 pub fn query_public_liquidatable_bets(
     deps: Deps,
-    block: u64,
+    block: &BlockInfo,
     skip: u32,
     limit: Option<u32>,
     exclude_addr: Option<String>,
@@ -122,21 +150,165 @@ pub fn query_public_liquidatable_bets(
 
 pub fn query_historical_bet(
     deps: Deps,
-    skip: u32,
-    limit: u32,
-    addr: Addr,
+    filter: HistoricalBetsFilter,
 ) -> StdResult<HistoricalBetResponse> {
-    let addr = addr.to_string();
-    let mut bets: Vec<HistoricalBet> = load_historical_bets(deps.storage)?
+    let (history, totals) = read_historical_bets(deps.storage, deps.api, &filter)?;
+    Ok(HistoricalBetResponse { history, totals })
+}
+
+pub fn query_bet(deps: Deps, bet_id: String) -> StdResult<BetState> {
+    let location = match may_load_bet_location(deps.storage, &bet_id)? {
+        Some(l) => l,
+        None => return Err(StdError::generic_err("bet by id not found")),
+    };
+
+    match location {
+        BetLocation::Pending { owner } => {
+            let bet = query_pending_bet_by_id(deps, owner, bet_id)?;
+            Ok(BetState::Pending(bet))
+        }
+        BetLocation::Ongoing {} => {
+            let bet = query_ongoing_bet(deps, bet_id)?;
+            Ok(BetState::Ongoing(bet))
+        }
+        BetLocation::Historical { seq } => {
+            let bet = load_historical_bet(deps.storage, seq)?;
+            Ok(bet.into())
+        }
+    }
+}
+
+pub fn query_player_stats(deps: Deps, addr: Addr) -> StdResult<PlayerStatsResponse> {
+    let stats = read_player_stats(deps.storage, &addr)?
         .into_iter()
-        .filter(|bet| {
-            bet.owner.eq(&addr) || bet.responder.eq(&addr) || bet.liquidator.eq(&Some(addr.clone()))
-        })
-        .skip(skip as usize)
-        .take(limit as usize)
+        .map(|(denom, stats)| DenomPlayerStats { denom, stats })
         .collect();
 
-    bets.sort_by(|a, b| b.completed_at.cmp(&a.completed_at));
+    Ok(PlayerStatsResponse {
+        address: addr.to_string(),
+        stats,
+    })
+}
+
+pub fn query_leaderboard(
+    deps: Deps,
+    current_block: u64,
+    denom: String,
+    metric: LeaderboardMetric,
+    period: LeaderboardPeriod,
+) -> StdResult<LeaderboardResponse> {
+    let epoch = match period {
+        LeaderboardPeriod::AllTime {} => None,
+        LeaderboardPeriod::Epoch { epoch } => {
+            let config = load_config(deps.storage)?;
+            Some(epoch.unwrap_or_else(|| config.leaderboard_epoch(current_block)))
+        }
+    };
+
+    let leaderboard = load_leaderboard(deps.storage, &denom, &metric, epoch)?;
+
+    Ok(LeaderboardResponse {
+        denom,
+        metric,
+        epoch,
+        entries: leaderboard.entries,
+    })
+}
+
+pub fn query_offers(
+    deps: Deps,
+    denom: Option<String>,
+    skip: u32,
+    limit: Option<u32>,
+) -> StdResult<OffersResponse> {
+    let offers = read_offers(deps.storage, denom, skip, limit)?;
+    Ok(OffersResponse { offers })
+}
+
+pub fn query_offers_by_addr(deps: Deps, addr: Addr) -> StdResult<OffersResponse> {
+    let offers = read_offers_by_addr(deps.storage, &addr)?;
+    Ok(OffersResponse { offers })
+}
 
-    Ok(HistoricalBetResponse { history: bets })
+pub fn query_referral(deps: Deps, addr: Addr) -> StdResult<ReferralResponse> {
+    Ok(ReferralResponse {
+        address: addr.to_string(),
+        referrer: may_load_referrer(deps.storage, &addr)?.map(|r| r.to_string()),
+        referred_players: load_referred_count(deps.storage, &addr)?,
+        earnings: read_referral_earnings(deps.storage, &addr)?,
+    })
+}
+
+// tiers of every denom accepted for bets
+pub fn query_fee_tier(deps: Deps, block: u64, addr: Addr) -> StdResult<FeeTierResponse> {
+    let config = load_config(deps.storage)?;
+    let tiers = config
+        .min_bet_amounts
+        .iter()
+        .map(|limit| {
+            let volume = load_window_volume(deps.storage, &config, block, &addr, &limit.denom)?;
+            let next_tier = config.next_fee_tier(&limit.denom, volume).cloned();
+            Ok(DenomFeeTier {
+                denom: limit.denom.clone(),
+                volume,
+                treasury_tax_percent: config.treasury_tax_percent_for(&limit.denom, volume),
+                tier: config.fee_tier(&limit.denom, volume).cloned(),
+                volume_to_next_tier: next_tier
+                    .as_ref()
+                    .map(|t| t.min_volume.checked_sub(volume))
+                    .transpose()?,
+                next_tier,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FeeTierResponse {
+        address: addr.to_string(),
+        tiers,
+    })
+}
+
+pub fn query_jackpot(deps: Deps) -> StdResult<JackpotResponse> {
+    Ok(JackpotResponse {
+        jackpots: read_jackpots(deps.storage)?,
+    })
+}
+
+pub fn query_jackpot_winners(
+    deps: Deps,
+    skip: u32,
+    limit: Option<u32>,
+) -> StdResult<JackpotWinnersResponse> {
+    Ok(JackpotWinnersResponse {
+        winners: read_jackpot_winners(deps.storage, skip, limit)?,
+    })
+}
+
+// loosened limits whose cooldown has passed are shown as current ones
+pub fn query_player_limits(
+    deps: Deps,
+    time: Timestamp,
+    addr: Addr,
+) -> StdResult<PlayerLimitsResponse> {
+    let mut limits = load_player_limits(deps.storage, &addr)?;
+    limits.apply_pending(time.seconds());
+
+    let day = usage_day(time.seconds());
+    let usages = limits
+        .limits
+        .into_iter()
+        .map(|limit| {
+            let usage = load_window_usage(deps.storage, &addr, &limit, day)?;
+            Ok(WagerLimitUsage { limit, usage })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PlayerLimitsResponse {
+        address: addr.to_string(),
+        excluded_until: limits
+            .excluded_until
+            .filter(|until| *until > time.seconds()),
+        limits: usages,
+        pending: limits.pending,
+    })
 }
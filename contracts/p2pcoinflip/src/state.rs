@@ -1,23 +1,90 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::RangeInclusive;
+
 use cosmwasm_std::{
-    Addr, Api, CanonicalAddr, Coin, Order, StdError, StdResult, Storage, Timestamp, Uint128, Uint64,
+    Addr, Api, BlockInfo, CanonicalAddr, Decimal, Order, StdError, StdResult, Storage, Timestamp,
+    Uint128, Uint64,
 };
-use cw_storage_plus::{Item, Map};
+use cw0::{Duration, Expiration};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, U64Key, U8Key};
 
 use crate::{
     error::ContractError,
-    msg::{InstantiateCoinLimitMsg, PendingBetsFilter, PendingBetsSort},
+    msg::{
+        HistoricalBetResult, HistoricalBetRole, HistoricalBetsFilter, HistoricalBetsTotals,
+        PendingBetsFilter, PendingBetsSort,
+    },
 };
 
-use tefiluck::asset::Asset;
+use tefiluck::{
+    asset::Asset,
+    config::{CoinLimit, CommonConfig},
+    drand::is_valid_public_key,
+    hash::calculate_sha256,
+    history::{self, historical_bets, HistoricalRecord},
+    payout::{Payout, PayoutRole},
+};
 
 static CONFIG: Item<Config> = Item::new("config");
 static PENDING_BETS: Map<&Addr, AddrPendingBets> = Map::new("pending_bets");
 static PENDING_BETS_COUNT: Item<Uint64> = Item::new("pending_bets_count");
+// pending bets by creation time, kept in step with PENDING_BETS by store_pending_bets
+static PENDING_BETS_BY_CREATION: Map<CreationKey, (Addr, String)> =
+    Map::new("pending_bets_by_creation");
 static ONGOING_BETS: Map<String, OngoingBet> = Map::new("ongoing_bets");
-static HISTORICAL_BETS: Item<Vec<HistoricalBet>> = Item::new("historical_bets");
+static BET_LOCATIONS: Map<&str, BetLocation> = Map::new("bet_locations");
+static PLAYER_STATS: Map<(&Addr, &str), PlayerStats> = Map::new("player_stats");
+static EPOCH_PLAYER_STATS: Map<(U64Key, (&Addr, &str)), PlayerStats> =
+    Map::new("epoch_player_stats");
+static LEADERBOARDS: Map<(&str, &str), Leaderboard> = Map::new("leaderboards");
+static CREATOR_RELIABILITY: Map<&Addr, CreatorReliability> = Map::new("creator_reliability");
+// every pool responder of a historical bet, the responder index only holds the first one
+static POOL_RESPONDERS: Map<(&Addr, U64Key), bool> = Map::new("pool_responders");
+static OFFERS_SEQ: Item<u64> = Item::new("offers_seq");
+// stake bands each offer covers, kept in step with offers by store_offer and remove_offer
+static OFFER_BANDS: Map<(&str, U8Key, U64Key), bool> = Map::new("offer_bands");
+static REFERRERS: Map<&Addr, Addr> = Map::new("referrers");
+static REFERRED_COUNTS: Map<&Addr, u64> = Map::new("referred_counts");
+static REFERRAL_EARNINGS: Map<(&Addr, &str), Uint128> = Map::new("referral_earnings");
+static PLAYER_LIMITS: Map<&Addr, PlayerLimits> = Map::new("player_limits");
+static DAILY_USAGE: Map<(&Addr, (&str, U64Key)), LimitUsage> = Map::new("daily_usage");
+static JACKPOTS: Map<&str, Uint128> = Map::new("jackpots");
+static JACKPOT_WINNERS: Map<U64Key, JackpotWinner> = Map::new("jackpot_winners");
+static JACKPOT_WINNERS_SEQ: Item<u64> = Item::new("jackpot_winners_seq");
+
+pub struct OfferIndexes<'a> {
+    pub owner: MultiIndex<'a, (Vec<u8>, Vec<u8>), StandingOffer>,
+    pub denom: MultiIndex<'a, (Vec<u8>, Vec<u8>), StandingOffer>,
+}
+
+impl<'a> IndexList<StandingOffer> for OfferIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<StandingOffer>> + '_> {
+        let v: Vec<&dyn Index<StandingOffer>> = vec![&self.owner, &self.denom];
+        Box::new(v.into_iter())
+    }
+}
+
+// offers are keyed by their id, so the oldest ones come first when matching
+fn offers<'a>() -> IndexedMap<'a, U64Key, StandingOffer, OfferIndexes<'a>> {
+    let indexes = OfferIndexes {
+        owner: MultiIndex::new(
+            |offer, pk| (offer.owner.as_bytes().to_vec(), pk),
+            "offers",
+            "offers__owner",
+        ),
+        denom: MultiIndex::new(
+            |offer, pk| (offer.asset.denom.as_bytes().to_vec(), pk),
+            "offers",
+            "offers__denom",
+        ),
+    };
+
+    IndexedMap::new("offers", indexes)
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -28,83 +95,354 @@ pub struct Config {
     pub min_bet_amounts: Vec<CoinLimit>,
     pub min_blocks_until_liquidation: u64,
     pub max_blocks_until_liquidation: u64,
+    pub min_seconds_until_liquidation: u64,
+    pub max_seconds_until_liquidation: u64,
     pub blocks_for_responder_liquidation: u64,
+    pub seconds_for_responder_liquidation: u64,
     pub bet_responder_liquidation_percent: u8,
     pub bet_liquidator_percent: u8,
     pub treasury_liquidation_percent: u8,
     pub historical_bets_max_storage_size: u64,
     pub historical_bets_clear_batch_size: u64,
+    pub leaderboard_size: u64,
+    pub leaderboard_epoch_blocks: u64,
+    pub creator_bond_percents: Vec<CreatorBondPercent>,
+    pub creator_bond_slash_percent: u8,
+    pub drand_public_key: String,
+    pub drand_genesis_time: u64,
+    pub drand_period_seconds: u64,
+    pub beacon_round_delay: u64,
+    pub max_pool_fills: u64,
+    pub referral_percent: u8,
+    pub fee_tiers: Vec<FeeTier>,
+    pub fee_tier_window_blocks: u64,
+    pub jackpot_percent: u8,
+    pub jackpot_odds: u64,
+    pub limit_loosening_cooldown: u64,
 }
 
 impl Config {
+    // settings shared with the other games
+    pub fn common(&self) -> CommonConfig<'_> {
+        CommonConfig {
+            min_bet_amounts: &self.min_bet_amounts,
+            treasury_tax_percent: self.treasury_tax_percent,
+            liquidation_percents: [
+                self.bet_responder_liquidation_percent,
+                self.bet_liquidator_percent,
+                self.treasury_liquidation_percent,
+            ],
+            min_blocks_until_liquidation: self.min_blocks_until_liquidation,
+            max_blocks_until_liquidation: self.max_blocks_until_liquidation,
+            min_seconds_until_liquidation: self.min_seconds_until_liquidation,
+            max_seconds_until_liquidation: self.max_seconds_until_liquidation,
+            historical_bets_max_storage_size: self.historical_bets_max_storage_size,
+            historical_bets_clear_batch_size: self.historical_bets_clear_batch_size,
+        }
+    }
+
     pub fn validate(&self) -> Result<(), ContractError> {
-        if self.min_bet_amounts.is_empty() {
+        self.common()
+            .validate()
+            .map_err(|message| ContractError::ValidationErr { message })?;
+
+        if self.leaderboard_size == 0 {
+            return Err(ContractError::ValidationErr {
+                message: "Config validation: leaderboard_size must be higher than 0".to_string(),
+            });
+        }
+
+        if self.leaderboard_size > 100 {
+            return Err(ContractError::ValidationErr {
+                message: "Config validation: leaderboard_size must not exceed 100".to_string(),
+            });
+        }
+
+        for (i, bond_percent) in self.creator_bond_percents.iter().enumerate() {
+            if bond_percent.percent > 100 {
+                return Err(ContractError::ValidationErr {
+                    message: "Config validation: creator_bond_percent must not exceed 100"
+                        .to_string(),
+                });
+            }
+
+            if self.creator_bond_percents[..i]
+                .iter()
+                .any(|p| p.denom == bond_percent.denom)
+            {
+                return Err(ContractError::ValidationErr {
+                    message:
+                        "Config validation: creator_bond_percents must have one percent per denom"
+                            .to_string(),
+                });
+            }
+        }
+
+        if self.creator_bond_slash_percent > 100 {
+            return Err(ContractError::ValidationErr {
+                message: "Config validation: creator_bond_slash_percent must not exceed 100"
+                    .to_string(),
+            });
+        }
+
+        if self.leaderboard_epoch_blocks == 0 {
             return Err(ContractError::ValidationErr {
-                message: "Config validation: min_bet_amounts must be a non-empty list".to_string(),
+                message: "Config validation: leaderboard_epoch_blocks must be higher than 0"
+                    .to_string(),
+            });
+        }
+
+        if self.drand_period_seconds == 0 {
+            return Err(ContractError::ValidationErr {
+                message: "Config validation: drand_period_seconds must be higher than 0"
+                    .to_string(),
+            });
+        }
+
+        // the round right after the current one may already be published when the response
+        // lands in a block, so a responder could know the beacon with a delay of 1
+        if self.beacon_round_delay < 2 {
+            return Err(ContractError::ValidationErr {
+                message: "Config validation: beacon_round_delay must be at least 2 drand rounds"
+                    .to_string(),
+            });
+        }
+
+        // jackpot and referrers share the treasury fee, so together they can not go beyond it
+        if self.referral_percent as u16 + self.jackpot_percent as u16 > 100 {
+            return Err(ContractError::ValidationErr {
+                message: "Config validation: referral_percent and jackpot_percent must not exceed 100 together".to_string(),
+            });
+        }
+
+        if self.jackpot_odds == 0 {
+            return Err(ContractError::ValidationErr {
+                message: "Config validation: jackpot_odds must be higher than 0".to_string(),
+            });
+        }
+
+        if self.fee_tier_window_blocks == 0 {
+            return Err(ContractError::ValidationErr {
+                message: "Config validation: fee_tier_window_blocks must be higher than 0"
+                    .to_string(),
             });
         }
 
-        if self.treasury_tax_percent > 10 {
+        // window volume is summed from epoch stats, one read per epoch
+        if self.fee_tier_window_blocks / self.leaderboard_epoch_blocks > MAX_FEE_TIER_EPOCHS {
+            return Err(ContractError::ValidationErr {
+                message: "Config validation: fee_tier_window_blocks must not span more than 100 leaderboard epochs".to_string(),
+            });
+        }
+
+        // tiers of a denom go up by volume and only discount the flat rate
+        for (i, tier) in self.fee_tiers.iter().enumerate() {
+            if tier.treasury_tax_percent > self.treasury_tax_percent {
+                return Err(ContractError::ValidationErr {
+                    message:
+                        "Config validation: fee tier percent must not exceed treasury_tax_percent"
+                            .to_string(),
+                });
+            }
+
+            let is_ascending = self.fee_tiers[..i]
+                .iter()
+                .filter(|t| t.denom == tier.denom)
+                .all(|t| t.min_volume < tier.min_volume);
+            if !is_ascending {
+                return Err(ContractError::ValidationErr {
+                    message: "Config validation: fee tiers of a denom must be sorted by ascending min_volume".to_string(),
+                });
+            }
+        }
+
+        // otherwise a loosened limit would apply right away
+        if self.limit_loosening_cooldown == 0 {
             return Err(ContractError::ValidationErr {
-                message: "Config validation: treasury percent must be less than 10".to_string(),
+                message: "Config validation: limit_loosening_cooldown must be higher than 0"
+                    .to_string(),
             });
         }
 
-        let liquidation_percent = self.bet_responder_liquidation_percent
-            + self.bet_liquidator_percent
-            + self.treasury_liquidation_percent;
-        if liquidation_percent != 100 {
+        // every fill is paid out in its own message when the bet is settled
+        if self.max_pool_fills == 0 || self.max_pool_fills > MAX_POOL_FILLS {
             return Err(ContractError::ValidationErr {
-                message: "Config validation: liquidation percent must be equal to 100".to_string(),
+                message: "Config validation: max_pool_fills must be between 1 and 50".to_string(),
             });
         }
 
-        if self.min_blocks_until_liquidation > self.max_blocks_until_liquidation {
+        if !is_valid_public_key(&self.drand_public_key) {
             return Err(ContractError::ValidationErr {
-                message: "Config validation: min_blocks_until_liquidation must be less than max_blocks_until_liquidation".to_string(),
+                message: "Config validation: drand_public_key must be a hex encoded G1 point"
+                    .to_string(),
             });
         }
 
         Ok(())
     }
 
+    pub fn leaderboard_epoch(&self, block: u64) -> u64 {
+        block / self.leaderboard_epoch_blocks
+    }
+
+    // first drand round is published at genesis time
+    pub fn drand_round(&self, time: Timestamp) -> u64 {
+        let elapsed = time.seconds().saturating_sub(self.drand_genesis_time);
+        elapsed / self.drand_period_seconds + 1
+    }
+
+    // beacon round deciding a bet responded at given time, delay is counted in drand rounds
+    pub fn beacon_round(&self, time: Timestamp) -> u64 {
+        self.drand_round(time) + self.beacon_round_delay
+    }
+
+    // bets in denoms without a percent accept a bond of any size
+    pub fn creator_bond_percent(&self, denom: &str) -> u8 {
+        self.creator_bond_percents
+            .iter()
+            .find(|p| p.denom == denom)
+            .map(|p| p.percent)
+            .unwrap_or_default()
+    }
+
+    // highest tier reached by the volume, None means the flat treasury rate
+    pub fn fee_tier(&self, denom: &str, volume: Uint128) -> Option<&FeeTier> {
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|t| t.denom == denom && t.min_volume <= volume)
+    }
+
+    pub fn next_fee_tier(&self, denom: &str, volume: Uint128) -> Option<&FeeTier> {
+        self.fee_tiers
+            .iter()
+            .find(|t| t.denom == denom && t.min_volume > volume)
+    }
+
+    pub fn treasury_tax_percent_for(&self, denom: &str, volume: Uint128) -> u8 {
+        self.fee_tier(denom, volume)
+            .map(|t| t.treasury_tax_percent)
+            .unwrap_or(self.treasury_tax_percent)
+    }
+
+    // responder gets exclusive liquidation right for a gap measured in the same units as the bet
+    pub fn responder_liquidation_gap(&self, until_liquidation: &Duration) -> Duration {
+        match until_liquidation {
+            Duration::Height(_) => Duration::Height(self.blocks_for_responder_liquidation),
+            Duration::Time(_) => Duration::Time(self.seconds_for_responder_liquidation),
+        }
+    }
+
     pub fn validate_place_bet_inputs(
         &self,
-        blocks_until_liquidation: u64,
+        until_liquidation: &Duration,
         addr_bets_count: usize,
         asset: &Asset,
+        odds: &Odds,
+        relayer_tip: Uint128,
+        creator_bond: Uint128,
     ) -> StdResult<()> {
-        if self.min_blocks_until_liquidation > blocks_until_liquidation {
+        odds.validate()?;
+
+        self.common()
+            .validate_until_liquidation(until_liquidation)?;
+
+        if (addr_bets_count as u64) == self.max_bets_by_addr {
             return Err(StdError::generic_err(
-                "blocks_before_liquidation must be higher than min allowed value",
+                "max bets by address limit was reached",
             ));
         }
 
-        if self.max_blocks_until_liquidation < blocks_until_liquidation {
+        let coin_limit = self.common().coin_limit(&asset.denom)?;
+
+        // both stakes must satisfy the limit, responder one is the smaller when odds favour creator
+        let responder_stake = odds.responder_stake(asset.amount);
+        if asset.amount < coin_limit.min_amount || responder_stake < coin_limit.min_amount {
             return Err(StdError::generic_err(
-                "blocks_before_liquidation must be less than max allowed value",
+                "provided amount less than min limit for provided asset",
             ));
         }
 
-        if (addr_bets_count as u64) == self.max_bets_by_addr {
+        self.validate_relayer_tip(asset, odds, relayer_tip)?;
+
+        let min_creator_bond = asset.take_percent(self.creator_bond_percent(&asset.denom))?;
+        if !creator_bond.is_zero() && creator_bond < min_creator_bond.amount {
             return Err(StdError::generic_err(
-                "max bets by address limit was reached",
+                "creator_bond must not be less than creator_bond_percent of bet amount",
             ));
         }
 
-        let coin_limit: &CoinLimit =
-            match self.min_bet_amounts.iter().find(|l| l.denom == asset.denom) {
-                Some(l) => l,
-                None => {
-                    return Err(StdError::generic_err(
-                        "coin limits for provided asset not found",
-                    ))
-                }
-            };
+        Ok(())
+    }
+
+    // tip is paid out of the winnings of the creator, which are the responder stake less
+    // the treasury tax of the pot, fee tiers only lower the tax so the flat rate bounds it
+    pub fn validate_relayer_tip(
+        &self,
+        asset: &Asset,
+        odds: &Odds,
+        relayer_tip: Uint128,
+    ) -> StdResult<()> {
+        let responder_stake = odds.responder_stake(asset.amount);
+        let pot = Asset {
+            denom: asset.denom.clone(),
+            amount: asset.amount.checked_add(responder_stake)?,
+        };
+        let max_treasury_tax = pot.take_percent(self.treasury_tax_percent)?;
+        let creator_winnings = responder_stake.saturating_sub(max_treasury_tax.amount);
 
-        if asset.amount < coin_limit.min_amount {
+        if !relayer_tip.is_zero() && relayer_tip >= creator_winnings {
             return Err(StdError::generic_err(
-                "provided amount less than min limit for provided asset",
+                "relayer_tip must be less than creator winnings",
+            ));
+        }
+
+        Ok(())
+    }
+
+    // offers share the per address limit with pending bets, but are counted separately
+    pub fn validate_offer_inputs(
+        &self,
+        addr_offers_count: usize,
+        asset: &Asset,
+        min_amount: Uint128,
+        max_amount: Uint128,
+        max_until_liquidation: &Duration,
+    ) -> StdResult<()> {
+        if (addr_offers_count as u64) >= self.max_bets_by_addr {
+            return Err(StdError::generic_err(
+                "max offers by address limit was reached",
+            ));
+        }
+
+        let coin_limit = self.common().coin_limit(&asset.denom)?;
+
+        if min_amount < coin_limit.min_amount {
+            return Err(StdError::generic_err(
+                "min_amount less than min limit for provided asset",
+            ));
+        }
+
+        if min_amount > max_amount {
+            return Err(StdError::generic_err(
+                "min_amount must not be higher than max_amount",
+            ));
+        }
+
+        if asset.amount < min_amount {
+            return Err(StdError::generic_err(
+                "escrowed amount must not be less than min_amount",
+            ));
+        }
+
+        // no bet deadline could fit an offer capped below the shortest allowed one
+        let too_short = match *max_until_liquidation {
+            Duration::Height(blocks) => blocks < self.min_blocks_until_liquidation,
+            Duration::Time(seconds) => seconds < self.min_seconds_until_liquidation,
+        };
+        if too_short {
+            return Err(StdError::generic_err(
+                "max_until_liquidation must not be less than min allowed value",
             ));
         }
 
@@ -112,32 +450,114 @@ impl Config {
     }
 }
 
+// caps of what a player can stake and lose in a denom within the last window_days
+// calendar days (UTC) including today, one day makes it a daily limit, None means no cap
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct CoinLimit {
+pub struct WagerLimit {
     pub denom: String,
-    pub min_amount: Uint128,
+    pub window_days: u64,
+    pub max_wager: Option<Uint128>,
+    pub max_loss: Option<Uint128>,
 }
 
-impl CoinLimit {
-    pub fn validate(&self, coin: &Coin) -> Result<(), ContractError> {
-        if self.min_amount > coin.amount {
-            return Err(ContractError::ValidationErr {
-                message: "CoinLimit validation: amount for bet must be higher than min limit"
-                    .to_string(),
-            });
+impl WagerLimit {
+    pub fn validate(&self) -> StdResult<()> {
+        if self.window_days == 0 || self.window_days > MAX_LIMIT_WINDOW_DAYS {
+            return Err(StdError::generic_err(
+                "window_days must be between 1 and 30",
+            ));
         }
 
         Ok(())
     }
+
+    pub fn is_same(&self, other: &WagerLimit) -> bool {
+        self.denom == other.denom && self.window_days == other.window_days
+    }
+
+    // no cap is loosened and no cap is added back
+    pub fn is_tighter_than(&self, current: &WagerLimit) -> bool {
+        let is_tighter = |new: Option<Uint128>, old: Option<Uint128>| match (new, old) {
+            (_, None) => true,
+            (Some(new), Some(old)) => new <= old,
+            (None, Some(_)) => false,
+        };
+        is_tighter(self.max_wager, current.max_wager) && is_tighter(self.max_loss, current.max_loss)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingWagerLimit {
+    pub limit: WagerLimit,
+    pub effective_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PlayerLimits {
+    pub excluded_until: Option<u64>,
+    pub limits: Vec<WagerLimit>,
+    // loosened limits waiting for the cooldown
+    pub pending: Vec<PendingWagerLimit>,
 }
 
-impl From<InstantiateCoinLimitMsg> for CoinLimit {
-    fn from(coin: InstantiateCoinLimitMsg) -> Self {
-        CoinLimit {
-            denom: coin.denom,
-            min_amount: Uint128::new(coin.min_amount.into()),
+impl PlayerLimits {
+    pub fn is_excluded(&self, time: u64) -> bool {
+        matches!(self.excluded_until, Some(until) if until > time)
+    }
+
+    // moves loosened limits whose cooldown has passed in place of the current ones,
+    // limits without any cap are dropped
+    pub fn apply_pending(&mut self, time: u64) {
+        let (due, pending): (Vec<_>, Vec<_>) =
+            self.pending.drain(..).partition(|p| p.effective_at <= time);
+        self.pending = pending;
+        for p in due {
+            self.replace(p.limit);
+        }
+    }
+
+    pub fn replace(&mut self, limit: WagerLimit) {
+        self.limits.retain(|l| !l.is_same(&limit));
+        self.pending.retain(|p| !p.limit.is_same(&limit));
+        if limit.max_wager.is_some() || limit.max_loss.is_some() {
+            self.limits.push(limit);
         }
     }
+
+    pub fn find(&self, limit: &WagerLimit) -> Option<&WagerLimit> {
+        self.limits.iter().find(|l| l.is_same(limit))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
+pub struct LimitUsage {
+    // stakes are counted when they are put in, withdrawing them later does not give it back
+    pub wagered: Uint128,
+    pub lost: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JackpotWinner {
+    pub bet_id: String,
+    pub winner: Addr,
+    pub asset: Asset,
+    pub won_at: u64,
+}
+
+// discounted treasury rate for players who staked at least min_volume of the denom
+// within the fee tier window
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeTier {
+    pub denom: String,
+    pub min_volume: Uint128,
+    pub treasury_tax_percent: u8,
+}
+
+// min share of the bet amount a non-zero creator bond must cover for bets in the denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreatorBondPercent {
+    pub denom: String,
+    pub percent: u8,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -150,8 +570,7 @@ impl AddrPendingBets {
         &mut self,
         owner: CanonicalAddr,
         bet_id: String,
-        sig: String,
-        blocks_until_liquidation: u64,
+        params: PlaceBetParams,
         asset: Asset,
         time: Timestamp,
     ) -> StdResult<()> {
@@ -159,14 +578,8 @@ impl AddrPendingBets {
             return Err(StdError::generic_err("bet with same id alreay exists"));
         }
 
-        self.bets.push(PendingBet::new(
-            owner,
-            bet_id,
-            sig,
-            blocks_until_liquidation,
-            asset,
-            time,
-        ));
+        self.bets
+            .push(PendingBet::new(owner, bet_id, params, asset, time));
         Ok(())
     }
 
@@ -180,6 +593,12 @@ impl AddrPendingBets {
     pub fn remove_bet(&mut self, bet_id: &String) {
         self.bets.retain(|bet| bet.id.ne(bet_id))
     }
+
+    pub fn replace_bet(&mut self, bet: PendingBet) {
+        if let Some(b) = self.bets.iter_mut().find(|b| b.id.eq(&bet.id)) {
+            *b = bet;
+        }
+    }
 }
 
 impl Default for AddrPendingBets {
@@ -189,13 +608,64 @@ impl Default for AddrPendingBets {
     }
 }
 
+// terms a bet is placed with, a classic coinflip without tip and bond unless changed
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlaceBetParams {
+    pub signature: String,
+    pub until_liquidation: Duration,
+    pub relayer_tip: Uint128,
+    pub relayer: Option<Addr>,
+    pub creator_bond: Uint128,
+    pub mode: GameMode,
+    pub odds: Odds,
+}
+
+impl PlaceBetParams {
+    pub fn new(signature: String, until_liquidation: Duration) -> Self {
+        PlaceBetParams {
+            signature,
+            until_liquidation,
+            relayer_tip: Uint128::zero(),
+            relayer: None,
+            creator_bond: Uint128::zero(),
+            mode: GameMode::Classic,
+            odds: Odds::coinflip(),
+        }
+    }
+}
+
+// changes to a pending bet, zero amounts and no deadline leave the bet as it is
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UpdatePendingBetParams {
+    pub add_funds: Uint128,
+    pub withdraw_funds: Uint128,
+    pub add_bond: Uint128,
+    pub until_liquidation: Option<Duration>,
+}
+
+// created_at nanos, owner and id of a pending bet
+pub type CreationKey<'a> = (U64Key, (&'a Addr, &'a str));
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PendingBet {
     pub owner: CanonicalAddr,
     pub id: String,
     pub signature: String,
-    pub blocks_until_liquidation: u64,
+    pub mode: GameMode,
+    pub odds: Odds,
+    pub until_liquidation: Duration,
+    // creator stake, responder stakes it scaled by the odds
     pub asset: Asset,
+    // paid from creator winnings to the relayer when it reveals the bet on creator's behalf
+    pub relayer_tip: Uint128,
+    pub relayer: Option<Addr>,
+    // returned on resolve and partly slashed on liquidation, zero means no bond
+    pub creator_bond: Uint128,
+    // set by the first fill of a pool bet, later fills must bet the same side
+    pub pool_side: Option<FlipSide>,
+    pub pool: Vec<PoolFill>,
+    // set when the bet holds winnings of a previous game offered back to its loser
+    pub rematch: Option<Rematch>,
     pub created_at: Timestamp,
 }
 
@@ -203,20 +673,159 @@ impl PendingBet {
     pub fn new(
         owner: CanonicalAddr,
         id: String,
-        sig: String,
-        blocks_until_liquidation: u64,
+        params: PlaceBetParams,
         asset: Asset,
         time: Timestamp,
     ) -> Self {
         PendingBet {
             owner: owner,
             id: id,
-            signature: sig,
-            blocks_until_liquidation: blocks_until_liquidation,
+            signature: params.signature,
+            mode: params.mode,
+            odds: params.odds,
+            until_liquidation: params.until_liquidation,
             asset: asset,
+            relayer_tip: params.relayer_tip,
+            relayer: params.relayer,
+            creator_bond: params.creator_bond,
+            pool_side: None,
+            pool: vec![],
+            rematch: None,
             created_at: time,
         }
     }
+
+    pub fn creator_bond_asset(&self) -> Asset {
+        Asset {
+            denom: self.asset.denom.clone(),
+            amount: self.creator_bond,
+        }
+    }
+
+    pub fn responder_stake_asset(&self) -> Asset {
+        Asset {
+            denom: self.asset.denom.clone(),
+            amount: self.odds.responder_stake(self.asset.amount),
+        }
+    }
+
+    pub fn creation_key<'a>(&'a self, owner: &'a Addr) -> CreationKey<'a> {
+        (U64Key::new(self.created_at.nanos()), (owner, &self.id))
+    }
+
+    pub fn pool_filled(&self) -> StdResult<Uint128> {
+        self.pool
+            .iter()
+            .try_fold(Uint128::zero(), |acc, f| Ok(acc.checked_add(f.amount)?))
+    }
+}
+
+// private bet only the opponent may accept, they or anyone after expiration may decline it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Rematch {
+    pub previous_bet_id: String,
+    pub opponent: Addr,
+    pub expires: Expiration,
+}
+
+// part of a pool bet stake put by one of its responders
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolFill {
+    pub responder: Addr,
+    pub amount: Uint128,
+    // block of the latest fill of the responder
+    pub filled_at_block: u64,
+}
+
+// creator commits one of `sides` outcomes and responder wins when it is among their picks,
+// stakes follow the odds so that the game stays fair, coinflip is 2 sides with a single pick
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Odds {
+    pub sides: u8,
+    pub responder_picks: u8,
+}
+
+impl Odds {
+    pub fn coinflip() -> Self {
+        Odds {
+            sides: 2,
+            responder_picks: 1,
+        }
+    }
+
+    pub fn validate(&self) -> StdResult<()> {
+        if self.sides < 2 || self.sides > 100 {
+            return Err(StdError::generic_err("sides must be between 2 and 100"));
+        }
+
+        if self.responder_picks == 0 || self.responder_picks >= self.sides {
+            return Err(StdError::generic_err(
+                "responder_picks must be higher than 0 and less than sides",
+            ));
+        }
+
+        Ok(())
+    }
+
+    // responder stake to creator stake is as responder chance to creator chance
+    pub fn responder_stake(&self, creator_stake: Uint128) -> Uint128 {
+        creator_stake.multiply_ratio(self.responder_picks, self.sides - self.responder_picks)
+    }
+
+    pub fn validate_picks(&self, picks: &[u8]) -> StdResult<()> {
+        if picks.len() != self.responder_picks as usize {
+            return Err(StdError::generic_err(
+                "number of picks must be equal to responder_picks",
+            ));
+        }
+
+        if picks.iter().any(|pick| *pick >= self.sides) {
+            return Err(StdError::generic_err("invalid outcome"));
+        }
+
+        let unique: BTreeSet<&u8> = picks.iter().collect();
+        if unique.len() != picks.len() {
+            return Err(StdError::generic_err("picks must be unique"));
+        }
+
+        Ok(())
+    }
+}
+
+// passphrase is expected to look like "<outcome>_<secret>"
+pub fn outcome_from_passphrase(passphrase: &str, sides: u8) -> Option<u8> {
+    let split: Vec<&str> = passphrase.split('_').collect();
+    if split.len() != 2 {
+        return None;
+    }
+
+    let outcome = split[0].parse::<u8>().ok()?;
+    if outcome >= sides {
+        return None;
+    }
+
+    Some(outcome)
+}
+
+// first 8 bytes of sha256 over the revealed randomness, both commitments and the acceptance
+// of the bet, anyone can recompute it once the game is settled but nobody knows it when
+// the bet is placed
+pub fn jackpot_roll(seed: &str, bet_id: &str, bet: &OngoingBet) -> u64 {
+    let hash = calculate_sha256(&format!(
+        "{}{}{}{}",
+        seed,
+        bet.signature,
+        bet.acceptance_entropy(),
+        bet_id
+    ));
+    u64::from_str_radix(&hash[..16], 16).unwrap_or_default()
+}
+
+// last 8 bytes of beacon randomness modulo sides, bias is negligible for up to 100 sides
+pub fn outcome_from_randomness(randomness: &[u8; 32], sides: u8) -> u8 {
+    let mut tail = [0u8; 8];
+    tail.copy_from_slice(&randomness[24..]);
+    (u64::from_be_bytes(tail) % sides as u64) as u8
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -241,6 +850,36 @@ impl FlipSide {
             FlipSide::Tails => 1,
         }
     }
+
+    pub fn from_passphrase(passphrase: &str) -> Option<FlipSide> {
+        let side = outcome_from_passphrase(passphrase, 2)?;
+        FlipSide::from_u8(side).ok()
+    }
+}
+
+// in classic mode responder picks a side in the clear and only the creator reveals,
+// in dual commit mode both players commit a side and the coin is the xor of revealed sides,
+// in beacon mode responder picks a side in the clear and the coin comes from a drand round,
+// in pool mode several responders fill the creator stake in parts and share the winnings
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    Classic,
+    DualCommit,
+    Beacon,
+    Pool,
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            GameMode::Classic => "classic",
+            GameMode::DualCommit => "dual_commit",
+            GameMode::Beacon => "beacon",
+            GameMode::Pool => "pool",
+        };
+        f.write_str(name)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -248,80 +887,211 @@ pub struct OngoingBet {
     pub signature: String,
     pub bet_creator: Addr,
     pub bet_responder: Addr,
-    pub responder_side: FlipSide,
+    pub mode: GameMode,
+    pub odds: Odds,
+    // known right away in classic mode, revealed later in dual commit mode
+    pub responder_side: Option<FlipSide>,
+    pub responder_picks: Vec<u8>,
+    pub responder_signature: Option<String>,
+    pub creator_passphrase: Option<String>,
+    pub responder_passphrase: Option<String>,
+    pub beacon_round: Option<u64>,
+    // both stakes, creator one is the rest of the pot
     pub asset: Asset,
+    pub responder_stake: Uint128,
     pub started_at_block: u64,
-    pub blocks_until_liquidation: u64,
-    pub liquidation_block: u64,
-    pub responder_liquidation_blocks_gap: u64,
+    pub until_liquidation: Duration,
+    // anyone except the creator can liquidate once it expires
+    pub liquidation: Expiration,
+    // before it expires only the responder can liquidate
+    pub responder_liquidation: Expiration,
+    pub relayer_tip: Uint128,
+    pub relayer: Option<Addr>,
+    // sender of the creator passphrase, the relayer is tipped only when it revealed it
+    pub creator_revealed_by: Option<Addr>,
+    // creator passphrase came after the deadline, part of the bond is slashed on settlement
+    pub creator_revealed_late: bool,
+    pub creator_bond: Uint128,
+    // bet responder is the first of pool responders, empty for other modes
+    pub pool: Vec<PoolFill>,
+    // commitments for a rematch the player wants to offer if they win
+    pub creator_rematch_signature: Option<String>,
+    pub responder_rematch_signature: Option<String>,
     pub created_at: Timestamp,
 }
 
 impl OngoingBet {
+    // both stakes of the pending bet go to the pot, pool fills are taken over as they are
     pub fn new(
-        sig: String,
+        pending_bet: &PendingBet,
         bet_creator: Addr,
         bet_responder: Addr,
-        side: FlipSide,
-        asset: Asset,
-        blocks_until_liquidation: u64,
-        blocks_for_responder_liquidation: u64,
-        current_block: u64,
-        created_at: Timestamp,
+        side: Option<FlipSide>,
+        responder_liquidation_gap: Duration,
+        block: &BlockInfo,
     ) -> StdResult<Self> {
-        let liquidation_block = match current_block.checked_add(blocks_until_liquidation) {
-            Some(l) => l,
-            None => return Err(StdError::generic_err("failed to compute liquidation_block")),
-        };
+        // bet becomes liquidatable strictly after the deadline has passed
+        let until_liquidation = pending_bet.until_liquidation;
+        let liquidation = until_liquidation.plus_one().after(block);
+        let responder_liquidation = (liquidation + responder_liquidation_gap)?;
 
-        let responder_liquidation_blocks_gap =
-            match liquidation_block.checked_add(blocks_for_responder_liquidation) {
-                Some(l) => l,
-                None => {
-                    return Err(StdError::generic_err(
-                        "failed to compute responder_liquidation_blocks_gap",
-                    ))
-                }
-            };
+        let responder_stake = pending_bet.responder_stake_asset();
+        let mut asset = pending_bet.asset.clone();
+        asset.checked_add(&responder_stake)?;
 
         Ok(OngoingBet {
-            signature: sig,
+            signature: pending_bet.signature.clone(),
             bet_creator: bet_creator,
             bet_responder: bet_responder,
+            mode: pending_bet.mode.clone(),
+            odds: pending_bet.odds.clone(),
+            responder_picks: side.iter().map(|s| s.u8()).collect(),
             responder_side: side,
+            responder_signature: None,
+            creator_passphrase: None,
+            responder_passphrase: None,
+            beacon_round: None,
+            responder_stake: responder_stake.amount,
             asset: asset,
-            started_at_block: current_block,
-            blocks_until_liquidation: blocks_until_liquidation,
-            liquidation_block: liquidation_block,
-            responder_liquidation_blocks_gap: responder_liquidation_blocks_gap,
-            created_at: created_at,
+            started_at_block: block.height,
+            until_liquidation,
+            liquidation,
+            responder_liquidation,
+            relayer_tip: pending_bet.relayer_tip,
+            relayer: pending_bet.relayer.clone(),
+            creator_revealed_by: None,
+            creator_revealed_late: false,
+            creator_bond: pending_bet.creator_bond,
+            pool: pending_bet.pool.clone(),
+            creator_rematch_signature: None,
+            responder_rematch_signature: None,
+            created_at: block.time,
         })
     }
 }
 
 impl OngoingBet {
-    pub fn resolve_winner(&self, passphrase: &String) -> Addr {
-        let split: Vec<&str> = passphrase.split("_").collect();
-        if split.len() != 2 {
-            return self.bet_responder.clone();
+    pub fn creator_bond_asset(&self) -> Asset {
+        Asset {
+            denom: self.asset.denom.clone(),
+            amount: self.creator_bond,
         }
+    }
 
-        let side = match split[0].parse::<u8>() {
-            Ok(s) => s,
-            Err(_) => return self.bet_responder.clone(),
-        };
+    pub fn rematch_signature(&self, winner: &Addr) -> Option<String> {
+        if winner.eq(&self.bet_creator) {
+            self.creator_rematch_signature.clone()
+        } else {
+            self.responder_rematch_signature.clone()
+        }
+    }
+
+    pub fn opponent(&self, player: &Addr) -> Addr {
+        if player.eq(&self.bet_creator) {
+            self.bet_responder.clone()
+        } else {
+            self.bet_creator.clone()
+        }
+    }
+
+    // fixed by the responder side when it joins, the creator can not pick it either by
+    // choosing when to close a pool as only the fills count for pool bets
+    pub fn acceptance_entropy(&self) -> String {
+        if self.pool.is_empty() {
+            return format!(
+                "{}{}{}{}",
+                self.bet_responder,
+                self.responder_signature.clone().unwrap_or_default(),
+                self.started_at_block,
+                self.created_at.nanos()
+            );
+        }
+
+        self.pool
+            .iter()
+            .map(|f| format!("{}{}{}", f.responder, f.amount, f.filled_at_block))
+            .collect()
+    }
 
-        let flipside = match FlipSide::from_u8(side) {
-            Ok(f) => f,
-            Err(_) => return self.bet_responder.clone(),
+    pub fn is_responder(&self, addr: &Addr) -> bool {
+        self.bet_responder.eq(addr) || self.pool.iter().any(|f| f.responder.eq(addr))
+    }
+
+    pub fn resolve_winner(&self, passphrase: &String) -> Addr {
+        // malformed passphrase means creator loses
+        let outcome = match outcome_from_passphrase(passphrase, self.odds.sides) {
+            Some(o) => o,
+            None => return self.bet_responder.clone(),
         };
 
-        if flipside == self.responder_side {
+        self.outcome_winner(outcome)
+    }
+
+    pub fn outcome_winner(&self, outcome: u8) -> Addr {
+        if self.responder_picks.contains(&outcome) {
             self.bet_responder.clone()
         } else {
             self.bet_creator.clone()
         }
     }
+
+    pub fn creator_stake_asset(&self) -> StdResult<Asset> {
+        Ok(Asset {
+            denom: self.asset.denom.clone(),
+            amount: self.asset.amount.checked_sub(self.responder_stake)?,
+        })
+    }
+
+    pub fn responder_stake_asset(&self) -> Asset {
+        Asset {
+            denom: self.asset.denom.clone(),
+            amount: self.responder_stake,
+        }
+    }
+
+    // known once both players revealed, malformed passphrase loses for whoever revealed it
+    pub fn resolve_dual_commit_winner(&self) -> Option<Addr> {
+        let (creator_passphrase, responder_passphrase) =
+            match (&self.creator_passphrase, &self.responder_passphrase) {
+                (Some(c), Some(r)) => (c, r),
+                _ => return None,
+            };
+
+        let creator_side = match FlipSide::from_passphrase(creator_passphrase) {
+            Some(f) => f,
+            None => return Some(self.bet_responder.clone()),
+        };
+        let responder_side = match FlipSide::from_passphrase(responder_passphrase) {
+            Some(f) => f,
+            None => return Some(self.bet_creator.clone()),
+        };
+
+        if creator_side.u8() ^ responder_side.u8() == 1 {
+            Some(self.bet_responder.clone())
+        } else {
+            Some(self.bet_creator.clone())
+        }
+    }
+
+    // player compensated on liquidation and the one who failed to reveal,
+    // none when nobody has revealed in dual commit mode
+    pub fn liquidation_parties(&self) -> Option<(Addr, Addr)> {
+        match self.mode {
+            GameMode::Classic | GameMode::Pool => {
+                Some((self.bet_responder.clone(), self.bet_creator.clone()))
+            }
+            GameMode::DualCommit => match (
+                self.creator_passphrase.is_some(),
+                self.responder_passphrase.is_some(),
+            ) {
+                (true, false) => Some((self.bet_creator.clone(), self.bet_responder.clone())),
+                (false, true) => Some((self.bet_responder.clone(), self.bet_creator.clone())),
+                _ => None,
+            },
+            // nobody can withhold the beacon, so an unsettled bet is simply refunded
+            GameMode::Beacon => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -329,14 +1099,29 @@ impl OngoingBet {
 pub enum GameOutcome {
     Resolved,
     Liquidated,
+    Withdrawn,
+    Forfeited,
+    // pending bet was closed unplayed once its deadline had passed
+    Expired,
 }
 
-impl ToString for GameOutcome {
-    fn to_string(&self) -> String {
-        match self {
-            GameOutcome::Resolved => "resolved".to_string(),
-            GameOutcome::Liquidated => "liquidated".to_string(),
-        }
+impl GameOutcome {
+    // withdrawn and expired bets were closed before anyone responded
+    pub fn is_played(&self) -> bool {
+        !matches!(self, GameOutcome::Withdrawn | GameOutcome::Expired)
+    }
+}
+
+impl fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            GameOutcome::Resolved => "resolved",
+            GameOutcome::Liquidated => "liquidated",
+            GameOutcome::Withdrawn => "withdrawn",
+            GameOutcome::Forfeited => "forfeited",
+            GameOutcome::Expired => "expired",
+        };
+        f.write_str(name)
     }
 }
 
@@ -344,16 +1129,45 @@ impl ToString for GameOutcome {
 pub struct HistoricalBet {
     pub id: String,
     pub owner: String,
-    pub responder: String,
-    pub winner: String,
+    pub responder: Option<String>,
+    pub winner: Option<String>,
     pub liquidator: Option<String>,
-    pub responder_side: u8,
+    pub mode: GameMode,
+    pub odds: Odds,
+    pub responder_side: Option<u8>,
+    pub responder_picks: Vec<u8>,
     pub asset: Asset,
+    pub responder_stake: Uint128,
+    pub creator_bond: Uint128,
     pub outcome: GameOutcome,
+    pub payouts: Vec<Payout>,
+    pub revealed_side: Option<u8>,
+    pub passphrase: Option<String>,
+    pub responder_passphrase: Option<String>,
+    pub beacon_round: Option<u64>,
+    pub pool: Vec<PoolFill>,
     pub created_at: u64,
     pub completed_at: u64,
 }
 
+impl HistoricalRecord for HistoricalBet {
+    fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    fn responder(&self) -> Option<&str> {
+        self.responder.as_deref()
+    }
+
+    fn liquidator(&self) -> Option<&str> {
+        self.liquidator.as_deref()
+    }
+
+    fn denom(&self) -> &str {
+        &self.asset.denom
+    }
+}
+
 impl HistoricalBet {
     pub fn new(
         id: String,
@@ -361,7 +1175,7 @@ impl HistoricalBet {
         bet_responder: String,
         winner: String,
         liquidator: Option<String>,
-        responder_side: FlipSide,
+        responder_side: Option<FlipSide>,
         asset: Asset,
         outcome: GameOutcome,
         created_at: u64,
@@ -370,16 +1184,397 @@ impl HistoricalBet {
         HistoricalBet {
             id: id,
             owner: owner,
-            responder: bet_responder,
-            winner: winner,
+            responder: Some(bet_responder),
+            winner: Some(winner),
             liquidator: liquidator,
-            responder_side: responder_side.u8(),
+            mode: GameMode::Classic,
+            odds: Odds::coinflip(),
+            responder_side: responder_side.as_ref().map(|s| s.u8()),
+            responder_picks: responder_side.iter().map(|s| s.u8()).collect(),
+            responder_stake: asset.amount.multiply_ratio(1u128, 2u128),
             asset: asset,
+            creator_bond: Uint128::zero(),
             outcome: outcome,
+            payouts: vec![],
+            revealed_side: None,
+            passphrase: None,
+            responder_passphrase: None,
+            beacon_round: None,
+            pool: vec![],
             created_at: created_at,
             completed_at: completed_at,
         }
     }
+
+    // copies game parameters of a played bet
+    pub fn set_game(&mut self, bet: &OngoingBet) {
+        self.mode = bet.mode.clone();
+        self.odds = bet.odds.clone();
+        self.responder_picks = bet.responder_picks.clone();
+        self.responder_stake = bet.responder_stake;
+        self.pool = bet.pool.clone();
+    }
+
+    fn pool_fill(&self, addr: &str) -> Option<&PoolFill> {
+        self.pool.iter().find(|f| f.responder.as_str() == addr)
+    }
+
+    // pool responders win or lose together with the first of them
+    pub fn is_won_by(&self, addr: &str) -> bool {
+        if self.winner.as_deref() == Some(addr) {
+            return true;
+        }
+
+        self.pool_fill(addr).is_some() && self.winner.is_some() && self.winner == self.responder
+    }
+
+    // withdrawn bets have no winner, so nobody has lost them
+    pub fn is_lost_by(&self, addr: &str) -> bool {
+        let is_player = self.owner == addr
+            || self.responder.as_deref() == Some(addr)
+            || self.pool_fill(addr).is_some();
+        is_player && self.winner.is_some() && !self.is_won_by(addr)
+    }
+
+    // sum of what addr has actually received from this bet after terra tax,
+    // liquidator rewards are paid for a service rather than won, so they are left out
+    pub fn received_by(&self, addr: &str) -> StdResult<Uint128> {
+        self.payouts
+            .iter()
+            .filter(|p| p.recipient == addr && p.role != PayoutRole::Liquidator)
+            .try_fold(Uint128::zero(), |acc, p| Ok(acc.checked_add(p.net_amount)?))
+    }
+
+    pub fn liquidator_reward(&self, addr: &str) -> StdResult<Uint128> {
+        self.payouts
+            .iter()
+            .filter(|p| p.recipient == addr && p.role == PayoutRole::Liquidator)
+            .try_fold(Uint128::zero(), |acc, p| Ok(acc.checked_add(p.net_amount)?))
+    }
+
+    // creator bond is counted as staked, so its refund is not a profit and its slashing is a loss
+    pub fn staked_by(&self, addr: &str) -> StdResult<Uint128> {
+        if !self.outcome.is_played() {
+            if self.owner == addr {
+                return Ok(self.asset.amount.checked_add(self.creator_bond)?);
+            }
+
+            return Ok(Uint128::zero());
+        }
+
+        // played pot consists of both stakes
+        if self.owner == addr {
+            let stake = self.asset.amount.checked_sub(self.responder_stake)?;
+            return Ok(stake.checked_add(self.creator_bond)?);
+        }
+
+        if let Some(fill) = self.pool_fill(addr) {
+            return Ok(fill.amount);
+        }
+
+        if self.responder.as_deref() == Some(addr) {
+            return Ok(self.responder_stake);
+        }
+
+        Ok(Uint128::zero())
+    }
+
+    // pending bet was taken back by its owner before anyone responded or expired unplayed
+    pub fn unplayed(
+        owner: String,
+        bet: &PendingBet,
+        outcome: GameOutcome,
+        completed_at: u64,
+    ) -> Self {
+        HistoricalBet {
+            id: bet.id.clone(),
+            owner,
+            responder: None,
+            winner: None,
+            liquidator: None,
+            mode: bet.mode.clone(),
+            odds: bet.odds.clone(),
+            responder_side: None,
+            responder_picks: vec![],
+            asset: bet.asset.clone(),
+            responder_stake: Uint128::zero(),
+            creator_bond: bet.creator_bond,
+            outcome,
+            payouts: vec![],
+            revealed_side: None,
+            passphrase: None,
+            responder_passphrase: None,
+            beacon_round: None,
+            pool: vec![],
+            created_at: bet.created_at.seconds(),
+            completed_at,
+        }
+    }
+}
+
+// responder stake escrowed in advance to accept any new bet that fits the offer,
+// it keeps matching bets until the escrow drops below min_amount
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StandingOffer {
+    pub id: u64,
+    pub owner: Addr,
+    pub side: FlipSide,
+    pub asset: Asset,
+    pub min_amount: Uint128,
+    pub max_amount: Uint128,
+    pub max_until_liquidation: Duration,
+    pub created_at: Timestamp,
+}
+
+impl StandingOffer {
+    // only classic and beacon coinflip bets are matched, as the responder side is given in the clear
+    pub fn matches(&self, bet: &PendingBet, bet_owner: &Addr) -> bool {
+        if self.owner.eq(bet_owner) || bet.odds != Odds::coinflip() {
+            return false;
+        }
+
+        if !matches!(bet.mode, GameMode::Classic | GameMode::Beacon) {
+            return false;
+        }
+
+        let liquidation_fits = match (&bet.until_liquidation, &self.max_until_liquidation) {
+            (Duration::Height(bet), Duration::Height(max)) => bet <= max,
+            (Duration::Time(bet), Duration::Time(max)) => bet <= max,
+            _ => false,
+        };
+
+        let stake = bet.responder_stake_asset();
+        liquidation_fits
+            && stake.denom == self.asset.denom
+            && stake.amount >= self.min_amount
+            && stake.amount <= self.max_stake()
+    }
+
+    // escrow left caps the stake the offer can take
+    pub fn max_stake(&self) -> Uint128 {
+        self.max_amount.min(self.asset.amount)
+    }
+
+    pub fn stake_bands(&self) -> RangeInclusive<u8> {
+        stake_band(self.min_amount)..=stake_band(self.max_stake())
+    }
+}
+
+// band n holds stakes from 2^n up to 2^(n+1) - 1
+pub fn stake_band(amount: Uint128) -> u8 {
+    (127 - amount.u128().max(1).leading_zeros()) as u8
+}
+
+// tells where a bet lives at the moment, so it can be found by id only
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BetLocation {
+    Pending { owner: Addr },
+    Ongoing {},
+    Historical { seq: u64 },
+}
+
+// profit and loss are kept apart, net P&L is profit minus loss,
+// rewards for liquidating other players bets are counted apart from it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PlayerStats {
+    pub games_as_creator: u64,
+    pub games_as_responder: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub volume: Uint128,
+    pub profit: Uint128,
+    pub loss: Uint128,
+    pub liquidations_suffered: u64,
+    pub liquidations_performed: u64,
+    pub liquidator_rewards: Uint128,
+    pub win_streak: u64,
+    pub max_win_streak: u64,
+}
+
+impl PlayerStats {
+    pub fn add_game(&mut self, is_creator: bool, stake: Uint128) -> StdResult<()> {
+        if is_creator {
+            self.games_as_creator += 1;
+        } else {
+            self.games_as_responder += 1;
+        }
+
+        self.volume = self.volume.checked_add(stake)?;
+        Ok(())
+    }
+
+    pub fn settle(&mut self, bet: &HistoricalBet, addr: &str) -> StdResult<()> {
+        if bet.is_won_by(addr) {
+            self.wins += 1;
+            self.win_streak += 1;
+            self.max_win_streak = self.max_win_streak.max(self.win_streak);
+        } else if bet.is_lost_by(addr) {
+            self.losses += 1;
+            self.win_streak = 0;
+        }
+
+        if bet.outcome == GameOutcome::Liquidated {
+            if bet.is_lost_by(addr) {
+                self.liquidations_suffered += 1;
+            }
+
+            if bet.liquidator.as_deref() == Some(addr) {
+                self.liquidations_performed += 1;
+            }
+        }
+        self.liquidator_rewards = self
+            .liquidator_rewards
+            .checked_add(bet.liquidator_reward(addr)?)?;
+
+        let received = bet.received_by(addr)?;
+        let staked = bet.staked_by(addr)?;
+        if received > staked {
+            self.profit = self.profit.checked_add(received.checked_sub(staked)?)?;
+        } else {
+            self.loss = self.loss.checked_add(staked.checked_sub(received)?)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardMetric {
+    Volume,
+    Profit,
+    WinStreak,
+}
+
+impl LeaderboardMetric {
+    pub fn all() -> Vec<LeaderboardMetric> {
+        vec![
+            LeaderboardMetric::Volume,
+            LeaderboardMetric::Profit,
+            LeaderboardMetric::WinStreak,
+        ]
+    }
+
+    // players with net loss have zero profit and drop out of the profit leaderboard
+    pub fn value(&self, stats: &PlayerStats) -> Uint128 {
+        match self {
+            LeaderboardMetric::Volume => stats.volume,
+            LeaderboardMetric::Profit => stats.profit.saturating_sub(stats.loss),
+            LeaderboardMetric::WinStreak => Uint128::from(stats.max_win_streak),
+        }
+    }
+
+    fn storage_key(&self, epoch: Option<u64>) -> String {
+        let metric = match self {
+            LeaderboardMetric::Volume => "volume",
+            LeaderboardMetric::Profit => "profit",
+            LeaderboardMetric::WinStreak => "win_streak",
+        };
+
+        match epoch {
+            Some(epoch) => format!("{}:{}", metric, epoch),
+            None => format!("{}:all", metric),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardEntry {
+    pub address: String,
+    pub value: Uint128,
+}
+
+// entries are kept sorted by value, highest first
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    pub fn update(&mut self, address: &Addr, value: Uint128, size: u64) {
+        self.entries.retain(|e| e.address != address.as_str());
+
+        if value.is_zero() {
+            return;
+        }
+
+        // on equal values whoever got there first stays higher
+        let position = self
+            .entries
+            .iter()
+            .position(|e| e.value < value)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(
+            position,
+            LeaderboardEntry {
+                address: address.to_string(),
+                value,
+            },
+        );
+        self.entries.truncate(size as usize);
+    }
+}
+
+// latencies of the most recent reveals used for the median
+const REVEAL_LATENCY_WINDOW: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct CreatorReliability {
+    pub resolved_games: u64,
+    pub liquidated_games: u64,
+    pub reveal_latencies: Vec<u64>,
+}
+
+impl CreatorReliability {
+    pub fn add_resolved(&mut self, latency: u64) {
+        self.resolved_games += 1;
+        self.reveal_latencies.push(latency);
+        if self.reveal_latencies.len() > REVEAL_LATENCY_WINDOW {
+            self.reveal_latencies.remove(0);
+        }
+    }
+
+    pub fn add_liquidated(&mut self) {
+        self.liquidated_games += 1;
+    }
+
+    pub fn games(&self) -> u64 {
+        self.resolved_games + self.liquidated_games
+    }
+
+    // share of games resolved by the creator, a fresh address has no score at all
+    // so that it can not pass for a reliable one
+    pub fn score(&self) -> Option<Decimal> {
+        match self.games() {
+            0 => None,
+            total => Some(Decimal::from_ratio(self.resolved_games, total)),
+        }
+    }
+
+    pub fn meets(&self, min_score: Option<Decimal>, min_games: Option<u64>) -> bool {
+        let enough_score = match min_score {
+            Some(min_score) => matches!(self.score(), Some(score) if score >= min_score),
+            None => true,
+        };
+        enough_score && self.games() >= min_games.unwrap_or(0)
+    }
+
+    pub fn median_reveal_latency(&self) -> Option<u64> {
+        if self.reveal_latencies.is_empty() {
+            return None;
+        }
+
+        let mut latencies = self.reveal_latencies.clone();
+        latencies.sort_unstable();
+
+        let mid = latencies.len() / 2;
+        if latencies.len() % 2 == 1 {
+            Some(latencies[mid])
+        } else {
+            Some((latencies[mid - 1] + latencies[mid]) / 2)
+        }
+    }
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -395,6 +1590,22 @@ pub fn store_pending_bets(
     addr: &Addr,
     pending_bets: &AddrPendingBets,
 ) -> StdResult<()> {
+    let previous = load_pending_bets(storage, addr)?;
+    for bet in &previous.bets {
+        if !pending_bets.bets.iter().any(|b| b.id.eq(&bet.id)) {
+            PENDING_BETS_BY_CREATION.remove(storage, bet.creation_key(addr));
+        }
+    }
+    for bet in &pending_bets.bets {
+        if !previous.bets.iter().any(|b| b.id.eq(&bet.id)) {
+            PENDING_BETS_BY_CREATION.save(
+                storage,
+                bet.creation_key(addr),
+                &(addr.clone(), bet.id.clone()),
+            )?;
+        }
+    }
+
     PENDING_BETS.save(storage, addr, pending_bets)
 }
 
@@ -433,20 +1644,432 @@ pub fn remove_ongoing_bet(storage: &mut dyn Storage, bet_id: String) {
     ONGOING_BETS.remove(storage, bet_id)
 }
 
-pub fn store_historical_bets(
+pub fn store_bet_location(
+    storage: &mut dyn Storage,
+    bet_id: &str,
+    location: &BetLocation,
+) -> StdResult<()> {
+    BET_LOCATIONS.save(storage, bet_id, location)
+}
+
+pub fn may_load_bet_location(
+    storage: &dyn Storage,
+    bet_id: &str,
+) -> StdResult<Option<BetLocation>> {
+    BET_LOCATIONS.may_load(storage, bet_id)
+}
+
+pub fn remove_bet_location(storage: &mut dyn Storage, bet_id: &str) {
+    BET_LOCATIONS.remove(storage, bet_id)
+}
+
+pub fn store_player_stats(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    denom: &str,
+    stats: &PlayerStats,
+) -> StdResult<()> {
+    PLAYER_STATS.save(storage, (addr, denom), stats)
+}
+
+pub fn load_player_stats(
+    storage: &dyn Storage,
+    addr: &Addr,
+    denom: &str,
+) -> StdResult<PlayerStats> {
+    PLAYER_STATS
+        .may_load(storage, (addr, denom))
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn read_player_stats(
+    storage: &dyn Storage,
+    addr: &Addr,
+) -> StdResult<Vec<(String, PlayerStats)>> {
+    PLAYER_STATS
+        .prefix(addr)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (k, v) = item?;
+            let denom = std::str::from_utf8(&k)?.to_string();
+            Ok((denom, v))
+        })
+        .collect()
+}
+
+pub fn may_load_referrer(storage: &dyn Storage, player: &Addr) -> StdResult<Option<Addr>> {
+    REFERRERS.may_load(storage, player)
+}
+
+// player is bound to their first referrer for good
+pub fn store_referrer(storage: &mut dyn Storage, player: &Addr, referrer: &Addr) -> StdResult<()> {
+    REFERRERS.save(storage, player, referrer)?;
+    REFERRED_COUNTS.update(storage, referrer, |count| -> StdResult<_> {
+        Ok(count.unwrap_or_default() + 1)
+    })?;
+    Ok(())
+}
+
+pub fn load_referred_count(storage: &dyn Storage, referrer: &Addr) -> StdResult<u64> {
+    REFERRED_COUNTS
+        .may_load(storage, referrer)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn add_referral_earnings(
+    storage: &mut dyn Storage,
+    referrer: &Addr,
+    asset: &Asset,
+) -> StdResult<()> {
+    REFERRAL_EARNINGS.update(
+        storage,
+        (referrer, &asset.denom),
+        |earnings| -> StdResult<_> { Ok(earnings.unwrap_or_default().checked_add(asset.amount)?) },
+    )?;
+    Ok(())
+}
+
+pub fn load_referral_earnings(
+    storage: &dyn Storage,
+    referrer: &Addr,
+    denom: &str,
+) -> StdResult<Uint128> {
+    REFERRAL_EARNINGS
+        .may_load(storage, (referrer, denom))
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn remove_referral_earnings(storage: &mut dyn Storage, referrer: &Addr, denom: &str) {
+    REFERRAL_EARNINGS.remove(storage, (referrer, denom))
+}
+
+pub fn read_referral_earnings(storage: &dyn Storage, referrer: &Addr) -> StdResult<Vec<Asset>> {
+    REFERRAL_EARNINGS
+        .prefix(referrer)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (k, amount) = item?;
+            let denom = std::str::from_utf8(&k)?.to_string();
+            Ok(Asset { denom, amount })
+        })
+        .collect()
+}
+
+pub fn load_player_limits(storage: &dyn Storage, addr: &Addr) -> StdResult<PlayerLimits> {
+    PLAYER_LIMITS
+        .may_load(storage, addr)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_player_limits(
     storage: &mut dyn Storage,
-    bets: &Vec<HistoricalBet>,
+    addr: &Addr,
+    limits: &PlayerLimits,
 ) -> StdResult<()> {
-    HISTORICAL_BETS.save(storage, bets)
+    PLAYER_LIMITS.save(storage, addr, limits)
+}
+
+pub fn usage_day(time: u64) -> u64 {
+    time / SECONDS_PER_DAY
+}
+
+pub fn update_daily_usage<F>(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    denom: &str,
+    day: u64,
+    action: F,
+) -> StdResult<()>
+where
+    F: FnOnce(&mut LimitUsage) -> StdResult<()>,
+{
+    let key = (addr, (denom, U64Key::new(day)));
+    let mut usage = DAILY_USAGE
+        .may_load(storage, key.clone())?
+        .unwrap_or_default();
+    action(&mut usage)?;
+    DAILY_USAGE.save(storage, key, &usage)
+}
+
+// usage summed over the limit window ending with the given day
+pub fn load_window_usage(
+    storage: &dyn Storage,
+    addr: &Addr,
+    limit: &WagerLimit,
+    day: u64,
+) -> StdResult<LimitUsage> {
+    let first = (day + 1).saturating_sub(limit.window_days);
+    (first..=day).try_fold(LimitUsage::default(), |mut total, d| {
+        let key = (addr, (limit.denom.as_str(), U64Key::new(d)));
+        if let Some(usage) = DAILY_USAGE.may_load(storage, key)? {
+            total.wagered = total.wagered.checked_add(usage.wagered)?;
+            total.lost = total.lost.checked_add(usage.lost)?;
+        }
+        Ok(total)
+    })
 }
 
-pub fn load_historical_bets(storage: &dyn Storage) -> StdResult<Vec<HistoricalBet>> {
-    HISTORICAL_BETS
-        .may_load(storage)
+pub fn load_jackpot(storage: &dyn Storage, denom: &str) -> StdResult<Uint128> {
+    JACKPOTS
+        .may_load(storage, denom)
         .map(|res| res.unwrap_or_default())
 }
 
+pub fn store_jackpot(storage: &mut dyn Storage, denom: &str, amount: Uint128) -> StdResult<()> {
+    JACKPOTS.save(storage, denom, &amount)
+}
+
+pub fn read_jackpots(storage: &dyn Storage) -> StdResult<Vec<Asset>> {
+    JACKPOTS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (k, amount) = item?;
+            let denom = std::str::from_utf8(&k)?.to_string();
+            Ok(Asset { denom, amount })
+        })
+        .collect()
+}
+
+pub fn append_jackpot_winner(storage: &mut dyn Storage, winner: &JackpotWinner) -> StdResult<()> {
+    let seq = JACKPOT_WINNERS_SEQ.may_load(storage)?.unwrap_or_default();
+    JACKPOT_WINNERS.save(storage, U64Key::new(seq), winner)?;
+    JACKPOT_WINNERS_SEQ.save(storage, &(seq + 1))
+}
+
+// latest winners first
+pub fn read_jackpot_winners(
+    storage: &dyn Storage,
+    skip: u32,
+    limit: Option<u32>,
+) -> StdResult<Vec<JackpotWinner>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    JACKPOT_WINNERS
+        .range(storage, None, None, Order::Descending)
+        .map(|item| item.map(|(_, winner)| winner))
+        .skip(skip as usize)
+        .take(limit)
+        .collect()
+}
+
+pub fn next_offer_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let id = OFFERS_SEQ.may_load(storage)?.unwrap_or_default();
+    OFFERS_SEQ.save(storage, &(id + 1))?;
+    Ok(id)
+}
+
+pub fn store_offer(storage: &mut dyn Storage, offer: &StandingOffer) -> StdResult<()> {
+    if let Some(previous) = offers().may_load(storage, U64Key::new(offer.id))? {
+        remove_offer_bands(storage, &previous);
+    }
+    for band in offer.stake_bands() {
+        OFFER_BANDS.save(
+            storage,
+            (&offer.asset.denom, U8Key::new(band), U64Key::new(offer.id)),
+            &true,
+        )?;
+    }
+
+    offers().save(storage, U64Key::new(offer.id), offer)
+}
+
+fn remove_offer_bands(storage: &mut dyn Storage, offer: &StandingOffer) {
+    for band in offer.stake_bands() {
+        OFFER_BANDS.remove(
+            storage,
+            (&offer.asset.denom, U8Key::new(band), U64Key::new(offer.id)),
+        );
+    }
+}
+
+pub fn load_offer(storage: &dyn Storage, offer_id: u64) -> StdResult<StandingOffer> {
+    match offers().may_load(storage, U64Key::new(offer_id))? {
+        Some(o) => Ok(o),
+        None => Err(StdError::generic_err("offer by id not found")),
+    }
+}
+
+pub fn remove_offer(storage: &mut dyn Storage, offer_id: u64) -> StdResult<()> {
+    if let Some(offer) = offers().may_load(storage, U64Key::new(offer_id))? {
+        remove_offer_bands(storage, &offer);
+    }
+
+    offers().remove(storage, U64Key::new(offer_id))
+}
+
+pub fn read_offers_by_addr(storage: &dyn Storage, addr: &Addr) -> StdResult<Vec<StandingOffer>> {
+    offers()
+        .idx
+        .owner
+        .prefix(addr.as_bytes().to_vec())
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, offer)| offer))
+        .collect()
+}
+
+pub fn read_offers(
+    storage: &dyn Storage,
+    denom: Option<String>,
+    skip: u32,
+    limit: Option<u32>,
+) -> StdResult<Vec<StandingOffer>> {
+    let skip = skip as usize;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let offers = offers();
+    let candidates: Box<dyn Iterator<Item = StdResult<(Vec<u8>, StandingOffer)>>> = match denom {
+        Some(denom) => Box::new(offers.idx.denom.prefix(denom.into_bytes()).range(
+            storage,
+            None,
+            None,
+            Order::Ascending,
+        )),
+        None => Box::new(offers.range(storage, None, None, Order::Ascending)),
+    };
+
+    candidates
+        .map(|item| item.map(|(_, offer)| offer))
+        .skip(skip)
+        .take(limit)
+        .collect()
+}
+
+// the oldest offer wins, all matching offers give the creator the same game
+// only offers reaching the stake band of the bet are scanned, so offers of other sizes can not
+// crowd it out, the scan is still bounded as offers of the same band may not fit the bet,
+// accepts skips offers that can not take the bet now
+pub fn find_matching_offer(
+    storage: &dyn Storage,
+    bet: &PendingBet,
+    bet_owner: &Addr,
+    accepts: impl Fn(&StandingOffer) -> bool,
+) -> StdResult<Option<StandingOffer>> {
+    let band = stake_band(bet.responder_stake_asset().amount);
+    for key in OFFER_BANDS
+        .prefix((bet.asset.denom.as_str(), U8Key::new(band)))
+        .keys(storage, None, None, Order::Ascending)
+        .take(MAX_OFFER_SCAN)
+    {
+        let offer = offers().load(storage, U64Key::from(key))?;
+        if offer.matches(bet, bet_owner) && accepts(&offer) {
+            return Ok(Some(offer));
+        }
+    }
+
+    Ok(None)
+}
+
+pub fn store_epoch_player_stats(
+    storage: &mut dyn Storage,
+    epoch: u64,
+    addr: &Addr,
+    denom: &str,
+    stats: &PlayerStats,
+) -> StdResult<()> {
+    EPOCH_PLAYER_STATS.save(storage, (U64Key::new(epoch), (addr, denom)), stats)
+}
+
+pub fn load_epoch_player_stats(
+    storage: &dyn Storage,
+    epoch: u64,
+    addr: &Addr,
+    denom: &str,
+) -> StdResult<PlayerStats> {
+    EPOCH_PLAYER_STATS
+        .may_load(storage, (U64Key::new(epoch), (addr, denom)))
+        .map(|res| res.unwrap_or_default())
+}
+
+// volume of epochs overlapping the last fee_tier_window_blocks, so the window is rounded
+// out to whole epochs
+pub fn load_window_volume(
+    storage: &dyn Storage,
+    config: &Config,
+    block: u64,
+    addr: &Addr,
+    denom: &str,
+) -> StdResult<Uint128> {
+    let first = config.leaderboard_epoch(block.saturating_sub(config.fee_tier_window_blocks));
+    let last = config.leaderboard_epoch(block);
+    (first..=last).try_fold(Uint128::zero(), |volume, epoch| {
+        let stats = load_epoch_player_stats(storage, epoch, addr, denom)?;
+        Ok(volume.checked_add(stats.volume)?)
+    })
+}
+
+// epoch None stands for all-time leaderboard
+pub fn store_leaderboard(
+    storage: &mut dyn Storage,
+    denom: &str,
+    metric: &LeaderboardMetric,
+    epoch: Option<u64>,
+    leaderboard: &Leaderboard,
+) -> StdResult<()> {
+    LEADERBOARDS.save(storage, (denom, &metric.storage_key(epoch)), leaderboard)
+}
+
+pub fn load_leaderboard(
+    storage: &dyn Storage,
+    denom: &str,
+    metric: &LeaderboardMetric,
+    epoch: Option<u64>,
+) -> StdResult<Leaderboard> {
+    LEADERBOARDS
+        .may_load(storage, (denom, &metric.storage_key(epoch)))
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn store_creator_reliability(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    reliability: &CreatorReliability,
+) -> StdResult<()> {
+    CREATOR_RELIABILITY.save(storage, addr, reliability)
+}
+
+pub fn load_creator_reliability(
+    storage: &dyn Storage,
+    addr: &Addr,
+) -> StdResult<CreatorReliability> {
+    CREATOR_RELIABILITY
+        .may_load(storage, addr)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn append_historical_bet(storage: &mut dyn Storage, bet: &HistoricalBet) -> StdResult<u64> {
+    let seq = history::append_historical_bet(storage, bet)?;
+    for fill in &bet.pool {
+        POOL_RESPONDERS.save(storage, (&fill.responder, U64Key::new(seq)), &true)?;
+    }
+
+    Ok(seq)
+}
+
+pub fn load_historical_bet(storage: &dyn Storage, seq: u64) -> StdResult<HistoricalBet> {
+    history::load_historical_bet(storage, seq)
+}
+
+// removes at most batch_size of the oldest historical bets
+pub fn remove_oldest_historical_bets(storage: &mut dyn Storage, batch_size: u64) -> StdResult<()> {
+    let removed: Vec<(u64, HistoricalBet)> =
+        history::remove_oldest_historical_bets(storage, batch_size)?;
+
+    for (seq, bet) in removed {
+        remove_bet_location(storage, &bet.id);
+        for fill in &bet.pool {
+            POOL_RESPONDERS.remove(storage, (&fill.responder, U64Key::new(seq)));
+        }
+    }
+
+    Ok(())
+}
+
 const MAX_LIMIT: u32 = 100;
+const MAX_FEE_TIER_EPOCHS: u64 = 100;
+const MAX_LIMIT_WINDOW_DAYS: u64 = 30;
+const MAX_POOL_FILLS: u64 = 50;
+const MAX_OFFER_SCAN: usize = 30;
+const MAX_BEST_MATCH_SCAN: usize = 100;
+const SECONDS_PER_DAY: u64 = 86400;
 const DEFAULT_LIMIT: u32 = 10;
 pub fn read_pending_bets(
     storage: &dyn Storage,
@@ -456,20 +2079,76 @@ pub fn read_pending_bets(
     let skip = filter.skip as usize;
     let limit = filter.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
+    let pending_bets = PENDING_BETS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (_, addr_bets) = item.unwrap_or_default();
+            addr_bets.bets
+        })
+        .flatten();
+
+    let pending_bets = filter_pending_bets(storage, api, filter, pending_bets)?;
+    Ok(pending_bets.into_iter().skip(skip).take(limit).collect())
+}
+
+// first bet in filter sort order that responder can accept with the given stake and side,
+// skip and limit are ignored, only the oldest bets are scanned, or the newest ones
+// when sorted by creation descending
+pub fn find_best_match(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    filter: &PendingBetsFilter,
+    responder: &Addr,
+    stake: &Asset,
+    side: u8,
+) -> StdResult<Option<PendingBet>> {
+    let responder = api.addr_canonicalize(responder.as_str())?;
+    let order = match filter.sort_by {
+        PendingBetsSort::Creation { asc: false } => Order::Descending,
+        _ => Order::Ascending,
+    };
+    let scanned = PENDING_BETS_BY_CREATION
+        .range(storage, None, None, order)
+        .take(MAX_BEST_MATCH_SCAN)
+        .map(|item| {
+            let (_, (owner, bet_id)) = item?;
+            load_pending_bets(storage, &owner)?.find_by_id(&bet_id)
+        })
+        .collect::<StdResult<Vec<PendingBet>>>()?;
+
+    let bet = filter_pending_bets(storage, api, filter, scanned.into_iter())?
+        .into_iter()
+        .find(|bet| {
+            bet.owner.ne(&responder)
+                && matches!(bet.mode, GameMode::Classic | GameMode::Beacon)
+                && bet.odds.responder_picks == 1
+                && side < bet.odds.sides
+                && bet.responder_stake_asset().eq(stake)
+        });
+
+    Ok(bet)
+}
+
+// given pending bets matching the filter in its sort order
+fn filter_pending_bets(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    filter: &PendingBetsFilter,
+    pending_bets: impl Iterator<Item = PendingBet>,
+) -> StdResult<Vec<PendingBet>> {
     let exclude_addr = match &filter.exclude_address {
         Some(v) => Some(api.addr_canonicalize(&v)?),
         None => None,
     };
     let asset_filters = filter.to_asset_map();
 
-    let mut pending_bets: Vec<PendingBet> = PENDING_BETS
-        .range(storage, None, None, Order::Ascending)
-        .map(|item| {
-            let (_, addr_bets) = item.unwrap_or_default();
-            addr_bets.bets
-        })
-        .flatten()
+    let mut pending_bets: Vec<PendingBet> = pending_bets
         .filter(|bet| {
+            // rematches are private
+            if bet.rematch.is_some() {
+                return false;
+            }
+
             if let Some(exclude_address) = &exclude_addr {
                 if bet.owner.eq(exclude_address) {
                     return false;
@@ -494,15 +2173,42 @@ pub fn read_pending_bets(
                 }
             }
 
+            if filter.min_creator_reliability.is_some() || filter.min_creator_games.is_some() {
+                let reliable = api
+                    .addr_humanize(&bet.owner)
+                    .and_then(|owner| load_creator_reliability(storage, &owner))
+                    .map(|reliability| {
+                        reliability.meets(filter.min_creator_reliability, filter.min_creator_games)
+                    })
+                    .unwrap_or(false);
+                if !reliable {
+                    return false;
+                }
+            }
+
             if let Some(liquidation) = &filter.liquidation {
-                if let Some(from) = liquidation.blocks_until_liquidation_from {
-                    if bet.blocks_until_liquidation < from {
+                // bounds apply to bets with deadlines in the same units
+                let (value, from, to) = match bet.until_liquidation {
+                    Duration::Height(blocks) => (
+                        blocks,
+                        liquidation.blocks_until_liquidation_from,
+                        liquidation.blocks_until_liquidation_to,
+                    ),
+                    Duration::Time(seconds) => (
+                        seconds,
+                        liquidation.seconds_until_liquidation_from,
+                        liquidation.seconds_until_liquidation_to,
+                    ),
+                };
+
+                if let Some(from) = from {
+                    if value < from {
                         return false;
                     }
                 }
 
-                if let Some(to) = liquidation.blocks_until_liquidation_to {
-                    if bet.blocks_until_liquidation > to {
+                if let Some(to) = to {
+                    if value > to {
                         return false;
                     }
                 }
@@ -530,7 +2236,7 @@ pub fn read_pending_bets(
     };
 
     pending_bets.sort_by(sort);
-    Ok(pending_bets.into_iter().skip(skip).take(limit).collect())
+    Ok(pending_bets)
 }
 
 pub fn read_ongoing_bets_by_addr(
@@ -542,7 +2248,7 @@ pub fn read_ongoing_bets_by_addr(
         .filter(|item| match item {
             Ok(v) => {
                 let (_, bet) = v;
-                bet.bet_creator.eq(addr) || bet.bet_responder.eq(addr)
+                bet.bet_creator.eq(addr) || bet.is_responder(addr)
             }
             Err(_) => false,
         })
@@ -556,7 +2262,7 @@ pub fn read_ongoing_bets_by_addr(
 
 pub fn read_public_liquidatable_bets(
     storage: &dyn Storage,
-    current_block: u64,
+    block: &BlockInfo,
     skip: u32,
     limit: Option<u32>,
     exclude_addr: Option<Addr>,
@@ -576,7 +2282,7 @@ pub fn read_public_liquidatable_bets(
                     }
                 }
 
-                bet.responder_liquidation_blocks_gap < current_block
+                bet.responder_liquidation.is_expired(block)
             }
             Err(_) => false,
         })
@@ -589,3 +2295,153 @@ pub fn read_public_liquidatable_bets(
         .take(limit)
         .collect()
 }
+
+pub fn read_historical_bets(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    filter: &HistoricalBetsFilter,
+) -> StdResult<(Vec<HistoricalBet>, Vec<HistoricalBetsTotals>)> {
+    let skip = filter.skip as usize;
+    let limit = filter.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let addr = match &filter.address {
+        Some(v) => Some(api.addr_validate(v)?.to_string()),
+        None => None,
+    };
+
+    if addr.is_none() && (filter.role.is_some() || filter.result.is_some()) {
+        return Err(StdError::generic_err(
+            "address must be provided to filter by role or result",
+        ));
+    }
+
+    let bets = historical_bets::<HistoricalBet>();
+
+    // pick the most selective index, everything else is filtered in place
+    let keys: Option<BTreeSet<Vec<u8>>> = if let Some(addr) = &addr {
+        let prefix = addr.as_bytes().to_vec();
+        let roles = match &filter.role {
+            Some(role) => vec![role.clone()],
+            None => vec![
+                HistoricalBetRole::Owner,
+                HistoricalBetRole::Responder,
+                HistoricalBetRole::Liquidator,
+            ],
+        };
+
+        // responder can liquidate their own bet, so the same key may appear in several indexes
+        let mut keys = BTreeSet::new();
+        for role in roles {
+            let index = match role {
+                HistoricalBetRole::Owner => &bets.idx.owner,
+                HistoricalBetRole::Responder => &bets.idx.responder,
+                HistoricalBetRole::Liquidator => &bets.idx.liquidator,
+            };
+            keys.extend(
+                index
+                    .prefix(prefix.clone())
+                    .keys(storage, None, None, Order::Ascending),
+            );
+
+            if role == HistoricalBetRole::Responder {
+                keys.extend(POOL_RESPONDERS.prefix(&Addr::unchecked(addr)).keys(
+                    storage,
+                    None,
+                    None,
+                    Order::Ascending,
+                ));
+            }
+        }
+
+        Some(keys)
+    } else if let Some(denoms) = &filter.denoms {
+        let mut keys = BTreeSet::new();
+        for denom in denoms {
+            keys.extend(bets.idx.denom.prefix(denom.as_bytes().to_vec()).keys(
+                storage,
+                None,
+                None,
+                Order::Ascending,
+            ));
+        }
+
+        Some(keys)
+    } else {
+        None
+    };
+
+    let candidates: Box<dyn Iterator<Item = StdResult<HistoricalBet>>> = match keys {
+        Some(keys) => Box::new(
+            keys.into_iter()
+                .rev()
+                .map(|k| bets.load(storage, U64Key::from(k))),
+        ),
+        None => Box::new(
+            bets.range(storage, None, None, Order::Descending)
+                .map(|item| item.map(|(_, bet)| bet)),
+        ),
+    };
+
+    let (completed_at_from, completed_at_to) = match &filter.completed_at {
+        Some(c) => (c.from, c.to),
+        None => (None, None),
+    };
+
+    let mut history: Vec<HistoricalBet> = vec![];
+    let mut totals: Vec<HistoricalBetsTotals> = vec![];
+    let mut matched: usize = 0;
+    for bet in candidates {
+        let bet = bet?;
+
+        // bets are stored in completion order, so nothing older can match anymore
+        if let Some(from) = completed_at_from {
+            if bet.completed_at < from {
+                break;
+            }
+        }
+
+        if let Some(to) = completed_at_to {
+            if bet.completed_at > to {
+                continue;
+            }
+        }
+
+        if let Some(denoms) = &filter.denoms {
+            if !denoms.contains(&bet.asset.denom) {
+                continue;
+            }
+        }
+
+        if let Some(outcomes) = &filter.outcomes {
+            if !outcomes.contains(&bet.outcome) {
+                continue;
+            }
+        }
+
+        if let Some(result) = &filter.result {
+            let matches_result = match result {
+                HistoricalBetResult::Won => bet.is_won_by(addr.as_ref().unwrap()),
+                HistoricalBetResult::Lost => bet.is_lost_by(addr.as_ref().unwrap()),
+            };
+            if !matches_result {
+                continue;
+            }
+        }
+
+        let total = match totals.iter_mut().find(|t| t.denom == bet.asset.denom) {
+            Some(t) => t,
+            None => {
+                totals.push(HistoricalBetsTotals::new(bet.asset.denom.clone()));
+                totals.last_mut().unwrap()
+            }
+        };
+        total.add_bet(&bet, addr.as_ref())?;
+
+        if matched >= skip && history.len() < limit {
+            history.push(bet);
+        }
+        matched += 1;
+    }
+
+    Ok((history, totals))
+}
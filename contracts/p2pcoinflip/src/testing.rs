@@ -1,23 +1,53 @@
 use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{
-    attr, Addr, Api, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Response, SubMsg, Timestamp,
-    Uint128, Uint64,
+    attr, from_slice, Addr, Api, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, Response,
+    Storage, SubMsg, Timestamp, Uint128, Uint64,
 };
 
-use crate::commands::{liquidate_bet, place_bet, resolve_bet, respond_bet, withdraw_pending_bet};
-use crate::contract::instantiate;
-use crate::mock_querier::{mock_dependencies, mock_env_custom};
-use crate::msg::{InstantiateCoinLimitMsg, InstantiateMsg};
+use cw0::{Duration, Expiration};
+
+use crate::commands::{
+    accept_rematch, cancel_offer, claim_referral_earnings, close_pool_bet, commit_respond_bet,
+    decline_rematch, fill_pool_bet, forfeit_bet, liquidate_bet, offer_rematch, place_bet,
+    post_offer, resolve_bet, respond_best_match, respond_bet, respond_dice_bet, self_exclude,
+    set_wager_limit, settle_with_beacon, update_pending_bet, withdraw_from_offer,
+    withdraw_pending_bet, withdraw_pool_fill,
+};
+use crate::contract::{execute, instantiate};
+use crate::msg::{
+    BetState, CompletedAtFilter, CreatorReliabilityResponse, DenomPlayerStats, ExecuteMsg,
+    HistoricalBetResult, HistoricalBetRole, HistoricalBetsFilter, HistoricalBetsTotals,
+    InstantiateMsg, LeaderboardPeriod, PendingBetsFilter, PendingBetsSort,
+};
+use crate::queries::{
+    query_bet, query_fee_tier, query_historical_bet, query_jackpot, query_jackpot_winners,
+    query_leaderboard, query_offers, query_offers_by_addr, query_pending_bets, query_player_limits,
+    query_player_stats, query_public_liquidatable_bets, query_referral,
+};
 use crate::state::{
-    load_historical_bets, load_ongoing_bet, load_pending_bets, load_pending_bets_count, FlipSide,
-    GameOutcome, HistoricalBet, OngoingBet, PendingBet,
+    jackpot_roll, load_config, load_creator_reliability, load_historical_bet, load_ongoing_bet,
+    load_pending_bets, load_pending_bets_count, store_config, CreatorBondPercent,
+    CreatorReliability, FeeTier, FlipSide, GameMode, GameOutcome, HistoricalBet, JackpotWinner,
+    Leaderboard, LeaderboardEntry, LeaderboardMetric, LimitUsage, Odds, OngoingBet, PendingBet,
+    PendingWagerLimit, PlaceBetParams, PlayerStats, PoolFill, UpdatePendingBetParams, WagerLimit,
 };
 use crate::ContractError;
-use tefiluck::asset::Asset;
+use tefiluck::{
+    asset::Asset,
+    config::InstantiateCoinLimitMsg,
+    hash::calculate_sha256,
+    history::load_historical_bets_state,
+    mock_querier::{mock_dependencies, mock_env_custom},
+    payout::{Payout, PayoutRole},
+};
 
 const MOCK_SIGNATURE: &'static str =
     "84dbedafb3b595f6d2d1c9719520f0e0a19fabd953afd4e5befc22a8fd0d7510";
 const MOCK_PASSPHRASE: &'static str = "0_tefiluck";
+// drand mainnet round 72785
+const DRAND_PUBLIC_KEY: &str = "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31";
+const DRAND_PREVIOUS_SIGNATURE: &str = "a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747";
+const DRAND_SIGNATURE: &str = "82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42";
 
 fn proper_instantiate(deps: DepsMut) -> Result<Response, ContractError> {
     let msg = InstantiateMsg {
@@ -30,12 +60,33 @@ fn proper_instantiate(deps: DepsMut) -> Result<Response, ContractError> {
         }],
         min_blocks_until_liquidation: 100,
         max_blocks_until_liquidation: 500,
+        min_seconds_until_liquidation: 60,
+        max_seconds_until_liquidation: 86400,
         blocks_for_responder_liquidation: 20,
+        seconds_for_responder_liquidation: 120,
         bet_responder_liquidation_percent: 90,
         bet_liquidator_percent: 7,
         treasury_liquidation_percent: 3,
         historical_bets_max_storage_size: 100,
         historical_bets_clear_batch_size: 10,
+        leaderboard_size: 10,
+        leaderboard_epoch_blocks: 100000,
+        creator_bond_percents: vec![CreatorBondPercent {
+            denom: "uusd".to_string(),
+            percent: 10,
+        }],
+        creator_bond_slash_percent: 50,
+        drand_public_key: DRAND_PUBLIC_KEY.to_string(),
+        drand_genesis_time: 1595431050,
+        drand_period_seconds: 30,
+        beacon_round_delay: 2,
+        max_pool_fills: 10,
+        referral_percent: 20,
+        fee_tiers: vec![],
+        fee_tier_window_blocks: 100000,
+        jackpot_percent: 0,
+        jackpot_odds: 100,
+        limit_loosening_cooldown: 86400,
     };
 
     let env = mock_env();
@@ -63,12 +114,33 @@ fn test_invalid_initialization() {
         min_bet_amounts: vec![],
         min_blocks_until_liquidation: 100,
         max_blocks_until_liquidation: 500,
+        min_seconds_until_liquidation: 60,
+        max_seconds_until_liquidation: 86400,
         blocks_for_responder_liquidation: 20,
+        seconds_for_responder_liquidation: 120,
         bet_responder_liquidation_percent: 90,
         bet_liquidator_percent: 7,
         treasury_liquidation_percent: 3,
         historical_bets_max_storage_size: 100,
         historical_bets_clear_batch_size: 10,
+        leaderboard_size: 10,
+        leaderboard_epoch_blocks: 100000,
+        creator_bond_percents: vec![CreatorBondPercent {
+            denom: "uusd".to_string(),
+            percent: 10,
+        }],
+        creator_bond_slash_percent: 50,
+        drand_public_key: DRAND_PUBLIC_KEY.to_string(),
+        drand_genesis_time: 1595431050,
+        drand_period_seconds: 30,
+        beacon_round_delay: 2,
+        max_pool_fills: 10,
+        referral_percent: 20,
+        fee_tiers: vec![],
+        fee_tier_window_blocks: 100000,
+        jackpot_percent: 0,
+        jackpot_odds: 100,
+        limit_loosening_cooldown: 86400,
     };
 
     let env = mock_env();
@@ -92,12 +164,33 @@ fn test_invalid_initialization() {
         }],
         min_blocks_until_liquidation: 100,
         max_blocks_until_liquidation: 500,
+        min_seconds_until_liquidation: 60,
+        max_seconds_until_liquidation: 86400,
         blocks_for_responder_liquidation: 20,
+        seconds_for_responder_liquidation: 120,
         bet_responder_liquidation_percent: 90,
         bet_liquidator_percent: 7,
         treasury_liquidation_percent: 3,
         historical_bets_max_storage_size: 100,
         historical_bets_clear_batch_size: 10,
+        leaderboard_size: 10,
+        leaderboard_epoch_blocks: 100000,
+        creator_bond_percents: vec![CreatorBondPercent {
+            denom: "uusd".to_string(),
+            percent: 10,
+        }],
+        creator_bond_slash_percent: 50,
+        drand_public_key: DRAND_PUBLIC_KEY.to_string(),
+        drand_genesis_time: 1595431050,
+        drand_period_seconds: 30,
+        beacon_round_delay: 2,
+        max_pool_fills: 10,
+        referral_percent: 20,
+        fee_tiers: vec![],
+        fee_tier_window_blocks: 100000,
+        jackpot_percent: 0,
+        jackpot_odds: 100,
+        limit_loosening_cooldown: 86400,
     };
 
     match instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err() {
@@ -119,12 +212,33 @@ fn test_invalid_initialization() {
         }],
         min_blocks_until_liquidation: 100,
         max_blocks_until_liquidation: 500,
+        min_seconds_until_liquidation: 60,
+        max_seconds_until_liquidation: 86400,
         blocks_for_responder_liquidation: 20,
+        seconds_for_responder_liquidation: 120,
         bet_responder_liquidation_percent: 90,
         bet_liquidator_percent: 7,
         treasury_liquidation_percent: 4,
         historical_bets_max_storage_size: 100,
         historical_bets_clear_batch_size: 10,
+        leaderboard_size: 10,
+        leaderboard_epoch_blocks: 100000,
+        creator_bond_percents: vec![CreatorBondPercent {
+            denom: "uusd".to_string(),
+            percent: 10,
+        }],
+        creator_bond_slash_percent: 50,
+        drand_public_key: DRAND_PUBLIC_KEY.to_string(),
+        drand_genesis_time: 1595431050,
+        drand_period_seconds: 30,
+        beacon_round_delay: 2,
+        max_pool_fills: 10,
+        referral_percent: 20,
+        fee_tiers: vec![],
+        fee_tier_window_blocks: 100000,
+        jackpot_percent: 0,
+        jackpot_odds: 100,
+        limit_loosening_cooldown: 86400,
     };
 
     match instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err() {
@@ -146,12 +260,33 @@ fn test_invalid_initialization() {
         }],
         min_blocks_until_liquidation: 501,
         max_blocks_until_liquidation: 500,
+        min_seconds_until_liquidation: 60,
+        max_seconds_until_liquidation: 86400,
         blocks_for_responder_liquidation: 20,
+        seconds_for_responder_liquidation: 120,
         bet_responder_liquidation_percent: 90,
         bet_liquidator_percent: 7,
         treasury_liquidation_percent: 3,
         historical_bets_max_storage_size: 100,
         historical_bets_clear_batch_size: 10,
+        leaderboard_size: 10,
+        leaderboard_epoch_blocks: 100000,
+        creator_bond_percents: vec![CreatorBondPercent {
+            denom: "uusd".to_string(),
+            percent: 10,
+        }],
+        creator_bond_slash_percent: 50,
+        drand_public_key: DRAND_PUBLIC_KEY.to_string(),
+        drand_genesis_time: 1595431050,
+        drand_period_seconds: 30,
+        beacon_round_delay: 2,
+        max_pool_fills: 10,
+        referral_percent: 20,
+        fee_tiers: vec![],
+        fee_tier_window_blocks: 100000,
+        jackpot_percent: 0,
+        jackpot_odds: 100,
+        limit_loosening_cooldown: 86400,
     };
 
     match instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err() {
@@ -163,6 +298,69 @@ fn test_invalid_initialization() {
     };
 }
 
+#[test]
+fn test_config_validation() {
+    let mut deps = mock_dependencies(&[]);
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+    let valid_config = load_config(&deps.storage).unwrap();
+
+    let mut config = valid_config.clone();
+    config.leaderboard_size = 0;
+    match config.validate().unwrap_err() {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: leaderboard_size must be higher than 0"
+        ),
+        _ => panic!("Must return validation err"),
+    };
+
+    let mut config = valid_config.clone();
+    config.beacon_round_delay = 1;
+    match config.validate().unwrap_err() {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: beacon_round_delay must be at least 2 drand rounds"
+        ),
+        _ => panic!("Must return validation err"),
+    };
+
+    assert_eq!(valid_config.creator_bond_percent("uusd"), 10);
+    assert_eq!(valid_config.creator_bond_percent("uluna"), 0);
+
+    let mut config = valid_config.clone();
+    config.creator_bond_percents[0].percent = 101;
+    match config.validate().unwrap_err() {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: creator_bond_percent must not exceed 100"
+        ),
+        _ => panic!("Must return validation err"),
+    };
+
+    let mut config = valid_config.clone();
+    config.max_pool_fills = 51;
+    match config.validate().unwrap_err() {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: max_pool_fills must be between 1 and 50"
+        ),
+        _ => panic!("Must return validation err"),
+    };
+
+    let mut config = valid_config;
+    config.creator_bond_percents.push(CreatorBondPercent {
+        denom: "uusd".to_string(),
+        percent: 20,
+    });
+    match config.validate().unwrap_err() {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: creator_bond_percents must have one percent per denom"
+        ),
+        _ => panic!("Must return validation err"),
+    };
+}
+
 #[test]
 fn test_place_bet() {
     let mut deps = mock_dependencies(&[]);
@@ -179,8 +377,14 @@ fn test_place_bet() {
         }],
     );
 
-    let place_bet_response =
-        place_bet(deps.as_mut(), env, info, MOCK_SIGNATURE.to_string(), 200).unwrap();
+    let place_bet_response = place_bet(
+        deps.as_mut(),
+        env,
+        info,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
+    )
+    .unwrap();
 
     let log_action = place_bet_response.attributes.get(0).expect("no log");
     assert_eq!(log_action, &attr("action", "place_bet"));
@@ -199,11 +403,19 @@ fn test_place_bet() {
             owner: deps.api.addr_canonicalize("addr0001").unwrap(),
             id: bet_id,
             signature: MOCK_SIGNATURE.to_string(),
-            blocks_until_liquidation: 200,
+            mode: GameMode::Classic,
+            odds: Odds::coinflip(),
+            until_liquidation: Duration::Height(200),
             asset: Asset {
                 denom: "uusd".to_string(),
                 amount: Uint128::new(1000000u128),
             },
+            relayer_tip: Uint128::zero(),
+            relayer: None,
+            creator_bond: Uint128::zero(),
+            pool_side: None,
+            pool: vec![],
+            rematch: None,
             created_at: Timestamp::from_nanos(100000),
         }
     );
@@ -212,6 +424,84 @@ fn test_place_bet() {
     assert_eq!(Uint64::from(1u64), bet_count,)
 }
 
+#[test]
+fn test_place_bet_legacy_deadline() {
+    let mut deps = mock_dependencies(&[]);
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let msg: ExecuteMsg = from_slice(
+        format!(
+            r#"{{"place_bet":{{"signature":"{}","blocks_until_liquidation":200}}}}"#,
+            MOCK_SIGNATURE
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+    let _ = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &uusd(1000000)),
+        msg,
+    )
+    .unwrap();
+
+    let pending_bets = load_pending_bets(&deps.storage, &Addr::unchecked("addr0000")).unwrap();
+    assert_eq!(
+        pending_bets.bets[0].until_liquidation,
+        Duration::Height(200)
+    );
+
+    let both = ExecuteMsg::PlaceBet {
+        signature: MOCK_SIGNATURE.to_string(),
+        blocks_until_liquidation: Some(200),
+        until_liquidation: Some(Duration::Height(200)),
+        relayer_tip: None,
+        relayer: None,
+        creator_bond: None,
+        mode: None,
+        odds: None,
+        referrer: None,
+    };
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &uusd(1000000)),
+        both,
+    ) {
+        Err(ContractError::Std(err)) => assert_eq!(
+            err.to_string(),
+            "Generic error: blocks_until_liquidation and until_liquidation are mutually exclusive"
+        ),
+        _ => panic!("Must return generic err"),
+    }
+
+    let neither = ExecuteMsg::PlaceBet {
+        signature: MOCK_SIGNATURE.to_string(),
+        blocks_until_liquidation: None,
+        until_liquidation: None,
+        relayer_tip: None,
+        relayer: None,
+        creator_bond: None,
+        mode: None,
+        odds: None,
+        referrer: None,
+    };
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &uusd(1000000)),
+        neither,
+    ) {
+        Err(ContractError::Std(err)) => {
+            assert_eq!(
+                err.to_string(),
+                "Generic error: until_liquidation is required"
+            )
+        }
+        _ => panic!("Must return generic err"),
+    }
+}
+
 #[test]
 fn test_place_bet_validation_error() {
     let mut deps = mock_dependencies(&[]);
@@ -225,8 +515,8 @@ fn test_place_bet_validation_error() {
         deps.as_mut(),
         env.clone(),
         info,
-        MOCK_SIGNATURE.to_string(),
-        200,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
     )
     .unwrap_err()
     {
@@ -249,8 +539,8 @@ fn test_place_bet_validation_error() {
         deps.as_mut(),
         env.clone(),
         info.clone(),
-        MOCK_SIGNATURE.to_string(),
-        10,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(10)),
+        None,
     )
     .unwrap_err()
     {
@@ -265,8 +555,8 @@ fn test_place_bet_validation_error() {
         deps.as_mut(),
         env.clone(),
         info.clone(),
-        MOCK_SIGNATURE.to_string(),
-        10000,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(10000)),
+        None,
     )
     .unwrap_err()
     {
@@ -288,8 +578,8 @@ fn test_place_bet_validation_error() {
         deps.as_mut(),
         env.clone(),
         info,
-        MOCK_SIGNATURE.to_string(),
-        200,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
     )
     .unwrap_err()
     {
@@ -310,8 +600,8 @@ fn test_place_bet_validation_error() {
         deps.as_mut(),
         env.clone(),
         info,
-        MOCK_SIGNATURE.to_string(),
-        200,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
     )
     .unwrap_err()
     {
@@ -334,8 +624,8 @@ fn test_place_bet_validation_error() {
         deps.as_mut(),
         env.clone(),
         info.clone(),
-        MOCK_SIGNATURE.to_string(),
-        200,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
     )
     .unwrap();
 
@@ -344,8 +634,8 @@ fn test_place_bet_validation_error() {
         deps.as_mut(),
         env.clone(),
         info,
-        MOCK_SIGNATURE.to_string(),
-        200,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
     )
     .unwrap_err()
     {
@@ -376,8 +666,8 @@ fn test_respond_bet() {
         deps.as_mut(),
         env.clone(),
         info,
-        MOCK_SIGNATURE.to_string(),
-        200,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
     )
     .unwrap();
 
@@ -401,6 +691,7 @@ fn test_respond_bet() {
         Addr::unchecked("addr0001"),
         bet_id.clone(),
         0,
+        None,
     )
     .unwrap();
 
@@ -426,15 +717,31 @@ fn test_respond_bet() {
             signature: MOCK_SIGNATURE.to_string(),
             bet_creator: Addr::unchecked("addr0001"),
             bet_responder: Addr::unchecked("addr0002"),
-            responder_side: FlipSide::Heads,
+            mode: GameMode::Classic,
+            odds: Odds::coinflip(),
+            responder_side: Some(FlipSide::Heads),
+            responder_picks: vec![0],
+            responder_signature: None,
+            creator_passphrase: None,
+            responder_passphrase: None,
+            beacon_round: None,
             asset: Asset {
                 denom: "uusd".to_string(),
                 amount: Uint128::new(2000000u128),
             },
+            responder_stake: Uint128::new(1000000u128),
             started_at_block: env.block.height,
-            blocks_until_liquidation: 200,
-            liquidation_block: 12345 + 200,
-            responder_liquidation_blocks_gap: 12345 + 200 + 20,
+            until_liquidation: Duration::Height(200),
+            liquidation: Expiration::AtHeight(12345 + 200 + 1),
+            responder_liquidation: Expiration::AtHeight(12345 + 200 + 1 + 20),
+            relayer_tip: Uint128::zero(),
+            relayer: None,
+            creator_revealed_by: None,
+            creator_revealed_late: false,
+            creator_bond: Uint128::zero(),
+            pool: vec![],
+            creator_rematch_signature: None,
+            responder_rematch_signature: None,
             created_at: Timestamp::from_seconds(10000),
         },
     );
@@ -462,8 +769,8 @@ fn test_respond_bet_validation_error() {
         deps.as_mut(),
         env.clone(),
         info,
-        MOCK_SIGNATURE.to_string(),
-        200,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
     )
     .unwrap();
 
@@ -483,6 +790,7 @@ fn test_respond_bet_validation_error() {
         Addr::unchecked("addr0001"),
         "nf".to_string(),
         0,
+        None,
     )
     .unwrap_err()
     {
@@ -504,6 +812,7 @@ fn test_respond_bet_validation_error() {
         Addr::unchecked("addr0001"),
         bet_id.clone(),
         0,
+        None,
     )
     .unwrap_err()
     {
@@ -525,6 +834,7 @@ fn test_respond_bet_validation_error() {
         Addr::unchecked("addr0001"),
         bet_id.clone(),
         2,
+        None,
     )
     .unwrap_err()
     {
@@ -549,8 +859,8 @@ fn create_valid_pending_bet(deps: DepsMut) -> String {
         deps,
         env.clone(),
         info.clone(),
-        MOCK_SIGNATURE.to_string(),
-        200,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
     )
     .unwrap();
 
@@ -574,6 +884,7 @@ fn create_valid_ongoing_bet(deps: DepsMut, bet_id: String) {
         Addr::unchecked("addr0001"),
         bet_id,
         0,
+        None,
     )
     .unwrap();
 }
@@ -632,29 +943,55 @@ fn test_resolve_bet() {
         true,
     );
 
-    let historical_bet = load_historical_bets(&deps.storage)
-        .unwrap()
-        .get(0)
-        .unwrap()
-        .clone();
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
     assert_eq!(
         historical_bet,
         HistoricalBet {
             id: bet_id.clone(),
             owner: "addr0001".to_string(),
-            responder: "addr0002".to_string(),
-            winner: "addr0002".to_string(),
+            responder: Some("addr0002".to_string()),
+            winner: Some("addr0002".to_string()),
             liquidator: None,
-            responder_side: FlipSide::Heads.u8(),
+            mode: GameMode::Classic,
+            odds: Odds::coinflip(),
+            responder_side: Some(FlipSide::Heads.u8()),
+            responder_picks: vec![0],
             asset: Asset {
                 denom: "uusd".to_string(),
                 amount: Uint128::new(2000000u128),
             },
+            responder_stake: Uint128::new(1000000u128),
+            creator_bond: Uint128::zero(),
             outcome: GameOutcome::Resolved,
+            payouts: vec![
+                Payout {
+                    recipient: "addr0002".to_string(),
+                    role: PayoutRole::Winner,
+                    gross_amount: Uint128::new(1980000u128),
+                    net_amount: Uint128::new(1960396u128),
+                },
+                Payout {
+                    recipient: "addr0000".to_string(),
+                    role: PayoutRole::Treasury,
+                    gross_amount: Uint128::new(20000u128),
+                    net_amount: Uint128::new(19801u128),
+                },
+            ],
+            revealed_side: Some(FlipSide::Heads.u8()),
+            passphrase: Some(MOCK_PASSPHRASE.to_string()),
+            responder_passphrase: None,
+            beacon_round: None,
+            pool: vec![],
             created_at: env.block.time.seconds(),
             completed_at: env.block.time.seconds(),
         }
     );
+
+    // anyone can re-verify the flip from history
+    assert_eq!(
+        calculate_sha256(historical_bet.passphrase.as_ref().unwrap()),
+        MOCK_SIGNATURE.to_string(),
+    );
 }
 
 #[test]
@@ -688,20 +1025,6 @@ fn test_resolve_bet_validation_error() {
         _ => panic!("no error"),
     }
 
-    let info = mock_info("addr0009", &[]);
-    match resolve_bet(
-        deps.as_mut(),
-        env.clone(),
-        info,
-        bet_id.clone(),
-        MOCK_PASSPHRASE.to_string(),
-    )
-    .unwrap_err()
-    {
-        ContractError::OnlyBetCreatorAllowedToResolve {} => assert_eq!(true, true,),
-        _ => panic!("no error"),
-    }
-
     let info = mock_info("addr0001", &[]);
     match resolve_bet(
         deps.as_mut(),
@@ -777,25 +1100,51 @@ fn test_liquidate_bet() {
         true,
     );
 
-    let historical_bet = load_historical_bets(&deps.storage)
-        .unwrap()
-        .get(0)
-        .unwrap()
-        .clone();
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
     assert_eq!(
         historical_bet,
         HistoricalBet {
             id: bet_id.clone(),
             owner: "addr0001".to_string(),
-            responder: "addr0002".to_string(),
-            winner: "addr0002".to_string(),
+            responder: Some("addr0002".to_string()),
+            winner: Some("addr0002".to_string()),
             liquidator: Some("addr0003".to_string()),
-            responder_side: FlipSide::Heads.u8(),
+            mode: GameMode::Classic,
+            odds: Odds::coinflip(),
+            responder_side: Some(FlipSide::Heads.u8()),
+            responder_picks: vec![0],
             asset: Asset {
                 denom: "uusd".to_string(),
                 amount: Uint128::new(2000000u128),
             },
+            responder_stake: Uint128::new(1000000u128),
+            creator_bond: Uint128::zero(),
             outcome: GameOutcome::Liquidated,
+            payouts: vec![
+                Payout {
+                    recipient: "addr0002".to_string(),
+                    role: PayoutRole::Responder,
+                    gross_amount: Uint128::new(1800000u128),
+                    net_amount: Uint128::new(1782178u128),
+                },
+                Payout {
+                    recipient: "addr0003".to_string(),
+                    role: PayoutRole::Liquidator,
+                    gross_amount: Uint128::new(140000u128),
+                    net_amount: Uint128::new(138613u128),
+                },
+                Payout {
+                    recipient: "addr0000".to_string(),
+                    role: PayoutRole::Treasury,
+                    gross_amount: Uint128::new(60000u128),
+                    net_amount: Uint128::new(59405u128),
+                },
+            ],
+            revealed_side: None,
+            passphrase: None,
+            responder_passphrase: None,
+            beacon_round: None,
+            pool: vec![],
             created_at: env.block.time.seconds(),
             completed_at: env.block.time.seconds(),
         }
@@ -863,8 +1212,10 @@ fn test_withdraw_pending_bet() {
     let bet_count = load_pending_bets_count(&deps.storage).unwrap();
     assert_eq!(Uint64::from(1u64), bet_count,);
 
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(20000);
     let info = mock_info("addr0001", &[]);
-    let response = withdraw_pending_bet(deps.as_mut(), info, bet_id.clone()).unwrap();
+    let response = withdraw_pending_bet(deps.as_mut(), env, info, bet_id.clone()).unwrap();
 
     let bet_count = load_pending_bets_count(&deps.storage).unwrap();
     assert_eq!(Uint64::from(0u64), bet_count,);
@@ -883,4 +1234,3622 @@ fn test_withdraw_pending_bet() {
 
     let mut pending_bets = load_pending_bets(&deps.storage, &Addr::unchecked("addr0001")).unwrap();
     assert_eq!(pending_bets.find_by_id(&bet_id).is_err(), true,);
+
+    let log_amount = response.attributes.get(4).expect("no log");
+    assert_eq!(log_amount, &attr("amount", "1000000"));
+
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    assert_eq!(
+        historical_bet,
+        HistoricalBet {
+            id: bet_id.clone(),
+            owner: "addr0001".to_string(),
+            responder: None,
+            winner: None,
+            liquidator: None,
+            mode: GameMode::Classic,
+            odds: Odds::coinflip(),
+            responder_side: None,
+            responder_picks: vec![],
+            asset: Asset {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(1000000u128),
+            },
+            responder_stake: Uint128::zero(),
+            creator_bond: Uint128::zero(),
+            outcome: GameOutcome::Withdrawn,
+            payouts: vec![Payout {
+                recipient: "addr0001".to_string(),
+                role: PayoutRole::Owner,
+                gross_amount: Uint128::new(1000000u128),
+                net_amount: Uint128::new(990099u128),
+            }],
+            revealed_side: None,
+            passphrase: None,
+            responder_passphrase: None,
+            beacon_round: None,
+            pool: vec![],
+            created_at: mock_env().block.time.seconds(),
+            completed_at: 20000,
+        }
+    );
+}
+
+#[test]
+fn test_update_pending_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(20000);
+
+    let top_up = |amount: u128| UpdatePendingBetParams {
+        add_funds: Uint128::new(amount),
+        ..UpdatePendingBetParams::default()
+    };
+    let withdrawal = |amount: u128| UpdatePendingBetParams {
+        withdraw_funds: Uint128::new(amount),
+        ..UpdatePendingBetParams::default()
+    };
+
+    // only bet owner can update it
+    let res = update_pending_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &uusd(1000000)),
+        bet_id.clone(),
+        top_up(1000000),
+    );
+    match res {
+        Err(ContractError::GameWasAlreadyAccepted {}) => {}
+        _ => panic!("Must return GameWasAlreadyAccepted error"),
+    }
+
+    // attached funds must match the increase
+    let res = update_pending_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &uusd(500000)),
+        bet_id.clone(),
+        top_up(1000000),
+    );
+    match res {
+        Err(ContractError::TopUpMismatch {}) => {}
+        _ => panic!("Must return TopUpMismatch error"),
+    }
+
+    let res = update_pending_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &uusd(1000000)),
+        bet_id.clone(),
+        UpdatePendingBetParams {
+            withdraw_funds: Uint128::new(500000u128),
+            ..top_up(1000000)
+        },
+    );
+    match res {
+        Err(ContractError::Std(err)) => assert_eq!(
+            err.to_string(),
+            "Generic error: add_funds and withdraw_funds are mutually exclusive"
+        ),
+        _ => panic!("Must return generic err"),
+    }
+
+    // same validation as place bet
+    let res = update_pending_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        withdrawal(100000),
+    );
+    match res {
+        Err(ContractError::Std(_)) => {}
+        _ => panic!("Must return min amount error"),
+    }
+
+    let res = update_pending_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        UpdatePendingBetParams {
+            until_liquidation: Some(Duration::Height(600)),
+            ..UpdatePendingBetParams::default()
+        },
+    );
+    match res {
+        Err(ContractError::Std(_)) => {}
+        _ => panic!("Must return max blocks error"),
+    }
+
+    let response = update_pending_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &uusd(1000000)),
+        bet_id.clone(),
+        UpdatePendingBetParams {
+            until_liquidation: Some(Duration::Height(300)),
+            ..top_up(1000000)
+        },
+    )
+    .unwrap();
+    assert_eq!(response.messages.len(), 0);
+
+    let mut pending_bets = load_pending_bets(&deps.storage, &Addr::unchecked("addr0001")).unwrap();
+    let pending_bet = pending_bets.find_by_id(&bet_id).unwrap();
+    assert_eq!(pending_bet.asset.amount, Uint128::new(2000000u128));
+    assert_eq!(pending_bet.until_liquidation, Duration::Height(300));
+    assert_eq!(pending_bet.created_at, mock_env().block.time);
+
+    // funds are not accepted when the stake is lowered
+    let res = update_pending_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &uusd(1000000)),
+        bet_id.clone(),
+        withdrawal(500000),
+    );
+    match res {
+        Err(ContractError::ExecuteWithoutFunds {}) => {}
+        _ => panic!("Must return ExecuteWithoutFunds error"),
+    }
+
+    let response = update_pending_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        withdrawal(500000),
+    )
+    .unwrap();
+    assert_eq!(
+        response.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0001".to_string(),
+            amount: uusd(495049),
+        }))]
+    );
+
+    let mut pending_bets = load_pending_bets(&deps.storage, &Addr::unchecked("addr0001")).unwrap();
+    let pending_bet = pending_bets.find_by_id(&bet_id).unwrap();
+    assert_eq!(pending_bet.asset.amount, Uint128::new(1500000u128));
+    assert_eq!(pending_bet.until_liquidation, Duration::Height(300));
+
+    // bond must cover creator_bond_percent of the updated stake
+    let res = update_pending_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &uusd(600000)),
+        bet_id.clone(),
+        UpdatePendingBetParams {
+            add_bond: Uint128::new(100000u128),
+            ..top_up(500000)
+        },
+    );
+    match res {
+        Err(ContractError::Std(_)) => {}
+        _ => panic!("Must return min bond error"),
+    }
+
+    // top-up and bond are attached together, deadline may be given in blocks
+    let msg = ExecuteMsg::UpdatePendingBet {
+        bet_id: bet_id.clone(),
+        add_funds: Some(Uint128::new(500000u128)),
+        withdraw_funds: None,
+        add_bond: Some(Uint128::new(200000u128)),
+        blocks_until_liquidation: Some(250),
+        until_liquidation: None,
+    };
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &uusd(700000)),
+        msg,
+    )
+    .unwrap();
+
+    let mut pending_bets = load_pending_bets(&deps.storage, &Addr::unchecked("addr0001")).unwrap();
+    let pending_bet = pending_bets.find_by_id(&bet_id).unwrap();
+    assert_eq!(pending_bet.asset.amount, Uint128::new(2000000u128));
+    assert_eq!(pending_bet.creator_bond, Uint128::new(200000u128));
+    assert_eq!(pending_bet.until_liquidation, Duration::Height(250));
+
+    let bet_count = load_pending_bets_count(&deps.storage).unwrap();
+    assert_eq!(Uint64::from(1u64), bet_count);
+}
+
+fn history_filter() -> HistoricalBetsFilter {
+    HistoricalBetsFilter {
+        skip: 0,
+        limit: None,
+        address: None,
+        role: None,
+        result: None,
+        outcomes: None,
+        denoms: None,
+        completed_at: None,
+    }
+}
+
+fn play_resolved_bet(mut deps: DepsMut, owner: &str, responder: &str, time: u64) -> String {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+
+    let info = mock_info(
+        owner,
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+    let pb = place_bet(
+        deps.branch(),
+        env.clone(),
+        info,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
+    )
+    .unwrap();
+    let bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+    let info = mock_info(
+        responder,
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+    let _ = respond_bet(
+        deps.branch(),
+        env.clone(),
+        info,
+        Addr::unchecked(owner),
+        bet_id.clone(),
+        0,
+        None,
+    )
+    .unwrap();
+
+    let info = mock_info(owner, &[]);
+    let _ = resolve_bet(deps, env, info, bet_id.clone(), MOCK_PASSPHRASE.to_string()).unwrap();
+
+    bet_id
+}
+
+#[test]
+fn test_historical_bets() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let first_bet_id = play_resolved_bet(deps.as_mut(), "addr0001", "addr0002", 1000);
+    let second_bet_id = play_resolved_bet(deps.as_mut(), "addr0003", "addr0001", 2000);
+    let _ = play_resolved_bet(deps.as_mut(), "addr0003", "addr0004", 3000);
+
+    // newest bets go first
+    let history = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            skip: 0,
+            limit: Some(10),
+            address: Some("addr0001".to_string()),
+            ..history_filter()
+        },
+    )
+    .unwrap()
+    .history;
+    assert_eq!(
+        history
+            .iter()
+            .map(|b| b.id.clone())
+            .collect::<Vec<String>>(),
+        vec![second_bet_id.clone(), first_bet_id],
+    );
+
+    let history = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            skip: 0,
+            limit: Some(1),
+            address: Some("addr0001".to_string()),
+            ..history_filter()
+        },
+    )
+    .unwrap()
+    .history;
+    assert_eq!(
+        history
+            .iter()
+            .map(|b| b.id.clone())
+            .collect::<Vec<String>>(),
+        vec![second_bet_id],
+    );
+
+    let history = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            skip: 0,
+            limit: Some(10),
+            address: Some("addr0004".to_string()),
+            ..history_filter()
+        },
+    )
+    .unwrap()
+    .history;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].completed_at, 3000);
+}
+
+#[test]
+fn test_historical_bets_pruning() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let mut bet_ids = vec![];
+    for i in 0..105u64 {
+        bet_ids.push(play_resolved_bet(
+            deps.as_mut(),
+            "addr0001",
+            "addr0002",
+            1000 + i,
+        ));
+    }
+
+    // 100 bets were stored, then the oldest batch of 10 was removed before storing the rest
+    let state = load_historical_bets_state(&deps.storage).unwrap();
+    assert_eq!(state.first_seq, 10);
+    assert_eq!(state.next_seq, 105);
+    assert_eq!(state.len(), 95);
+
+    assert!(load_historical_bet(&deps.storage, 9).is_err());
+    assert_eq!(
+        load_historical_bet(&deps.storage, 10).unwrap().created_at,
+        1010
+    );
+
+    let history = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            skip: 90,
+            limit: Some(10),
+            address: Some("addr0002".to_string()),
+            ..history_filter()
+        },
+    )
+    .unwrap()
+    .history;
+    assert_eq!(
+        history.iter().map(|b| b.created_at).collect::<Vec<u64>>(),
+        vec![1014, 1013, 1012, 1011, 1010],
+    );
+}
+
+#[test]
+fn test_historical_bets_filter() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    // addr0002 wins as responder, then loses as owner
+    let first_bet_id = play_resolved_bet(deps.as_mut(), "addr0001", "addr0002", 1000);
+    let second_bet_id = play_resolved_bet(deps.as_mut(), "addr0002", "addr0003", 2000);
+
+    // addr0002 liquidates the bet that addr0004 did not resolve
+    let mut env = mock_env_custom(12_345);
+    env.block.time = Timestamp::from_seconds(3000);
+    let third_bet_id = create_valid_pending_bet(deps.as_mut());
+    let info = mock_info(
+        "addr0004",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+    let _ = respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        Addr::unchecked("addr0001"),
+        third_bet_id.clone(),
+        0,
+        None,
+    )
+    .unwrap();
+    env.block.height = 13_345;
+    let _ = liquidate_bet(
+        deps.as_mut(),
+        env,
+        mock_info("addr0002", &[]),
+        third_bet_id.clone(),
+    )
+    .unwrap();
+
+    let ids = |resp: &crate::msg::HistoricalBetResponse| {
+        resp.history
+            .iter()
+            .map(|b| b.id.clone())
+            .collect::<Vec<String>>()
+    };
+
+    let resp = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            address: Some("addr0002".to_string()),
+            ..history_filter()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        ids(&resp),
+        vec![
+            third_bet_id.clone(),
+            second_bet_id.clone(),
+            first_bet_id.clone()
+        ],
+    );
+    assert_eq!(
+        resp.totals,
+        vec![HistoricalBetsTotals {
+            denom: "uusd".to_string(),
+            count: 3,
+            volume: Uint128::new(6000000u128),
+            // won 980000 after treasury fee, liquidator reward is not a game profit
+            profit: Uint128::new(980000u128),
+            loss: Uint128::new(1000000u128),
+        }],
+    );
+
+    let resp = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            address: Some("addr0002".to_string()),
+            role: Some(HistoricalBetRole::Liquidator),
+            ..history_filter()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(&resp), vec![third_bet_id.clone()]);
+
+    let resp = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            address: Some("addr0002".to_string()),
+            result: Some(HistoricalBetResult::Won),
+            ..history_filter()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(&resp), vec![first_bet_id.clone()]);
+
+    let resp = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            address: Some("addr0002".to_string()),
+            result: Some(HistoricalBetResult::Lost),
+            ..history_filter()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(&resp), vec![second_bet_id.clone()]);
+
+    let resp = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            outcomes: Some(vec![GameOutcome::Liquidated]),
+            ..history_filter()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(&resp), vec![third_bet_id]);
+
+    let resp = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            completed_at: Some(CompletedAtFilter {
+                from: Some(1500),
+                to: Some(2500),
+            }),
+            ..history_filter()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(&resp), vec![second_bet_id.clone()]);
+    assert_eq!(resp.totals[0].count, 1);
+    assert_eq!(resp.totals[0].profit, Uint128::zero());
+
+    let resp = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            denoms: Some(vec!["uluna".to_string()]),
+            ..history_filter()
+        },
+    )
+    .unwrap();
+    assert!(resp.history.is_empty());
+    assert!(resp.totals.is_empty());
+
+    let resp = query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            skip: 1,
+            limit: Some(1),
+            denoms: Some(vec!["uusd".to_string()]),
+            ..history_filter()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(&resp), vec![second_bet_id]);
+    assert_eq!(resp.totals[0].count, 3);
+
+    match query_historical_bet(
+        deps.as_ref(),
+        HistoricalBetsFilter {
+            result: Some(HistoricalBetResult::Won),
+            ..history_filter()
+        },
+    )
+    .unwrap_err()
+    {
+        cosmwasm_std::StdError::GenericErr { msg, .. } => assert_eq!(
+            msg,
+            "address must be provided to filter by role or result".to_string(),
+        ),
+        _ => panic!("no error"),
+    }
+}
+
+#[test]
+fn test_query_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    match query_bet(deps.as_ref(), bet_id.clone()).unwrap() {
+        BetState::Pending(bet) => {
+            assert_eq!(bet.id, bet_id);
+            assert_eq!(bet.owner, "addr0001".to_string());
+        }
+        _ => panic!("bet must be pending"),
+    }
+
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone());
+    match query_bet(deps.as_ref(), bet_id.clone()).unwrap() {
+        BetState::Ongoing(bet) => {
+            assert_eq!(bet.id, bet_id);
+            assert_eq!(bet.responder, "addr0002".to_string());
+        }
+        _ => panic!("bet must be ongoing"),
+    }
+
+    let _ = resolve_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+    match query_bet(deps.as_ref(), bet_id.clone()).unwrap() {
+        BetState::Resolved(bet) => {
+            assert_eq!(bet.id, bet_id);
+            assert_eq!(bet.winner, Some("addr0002".to_string()));
+        }
+        _ => panic!("bet must be resolved"),
+    }
+
+    // withdrawn bets stay in history
+    let mut env = mock_env();
+    env.block.height += 1;
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+    let pb = place_bet(
+        deps.as_mut(),
+        env,
+        info,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
+    )
+    .unwrap();
+    let bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+    let _ = withdraw_pending_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+    )
+    .unwrap();
+    match query_bet(deps.as_ref(), bet_id.clone()).unwrap() {
+        BetState::Withdrawn(bet) => {
+            assert_eq!(bet.id, bet_id);
+            assert_eq!(bet.responder, None);
+        }
+        _ => panic!("bet must be withdrawn"),
+    }
+
+    match query_bet(deps.as_ref(), "nf".to_string()).unwrap_err() {
+        cosmwasm_std::StdError::GenericErr { msg, .. } => {
+            assert_eq!(msg, "bet by id not found".to_string())
+        }
+        _ => panic!("no error"),
+    }
+}
+
+#[test]
+fn test_player_stats() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let _ = play_resolved_bet(deps.as_mut(), "addr0001", "addr0002", 1000);
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone());
+    let _ = liquidate_bet(
+        deps.as_mut(),
+        mock_env_custom(13_345),
+        mock_info("addr0003", &[]),
+        bet_id,
+    )
+    .unwrap();
+
+    let stats = query_player_stats(deps.as_ref(), Addr::unchecked("addr0001")).unwrap();
+    assert_eq!(
+        stats.stats,
+        vec![DenomPlayerStats {
+            denom: "uusd".to_string(),
+            stats: PlayerStats {
+                games_as_creator: 2,
+                games_as_responder: 0,
+                wins: 0,
+                losses: 2,
+                volume: Uint128::new(2000000u128),
+                profit: Uint128::zero(),
+                loss: Uint128::new(2000000u128),
+                liquidations_suffered: 1,
+                liquidations_performed: 0,
+                liquidator_rewards: Uint128::zero(),
+                win_streak: 0,
+                max_win_streak: 0,
+            },
+        }],
+    );
+
+    let stats = query_player_stats(deps.as_ref(), Addr::unchecked("addr0002")).unwrap();
+    assert_eq!(
+        stats.stats,
+        vec![DenomPlayerStats {
+            denom: "uusd".to_string(),
+            stats: PlayerStats {
+                games_as_creator: 0,
+                games_as_responder: 2,
+                wins: 2,
+                losses: 0,
+                volume: Uint128::new(2000000u128),
+                profit: Uint128::new(1780000u128),
+                loss: Uint128::zero(),
+                liquidations_suffered: 0,
+                liquidations_performed: 0,
+                liquidator_rewards: Uint128::zero(),
+                win_streak: 2,
+                max_win_streak: 2,
+            },
+        }],
+    );
+
+    let stats = query_player_stats(deps.as_ref(), Addr::unchecked("addr0003")).unwrap();
+    assert_eq!(
+        stats.stats,
+        vec![DenomPlayerStats {
+            denom: "uusd".to_string(),
+            stats: PlayerStats {
+                liquidations_performed: 1,
+                liquidator_rewards: Uint128::new(140000u128),
+                ..PlayerStats::default()
+            },
+        }],
+    );
+
+    // liquidator rewards are not a game profit
+    let leaderboard = query_leaderboard(
+        deps.as_ref(),
+        13_345,
+        "uusd".to_string(),
+        LeaderboardMetric::Profit,
+        LeaderboardPeriod::AllTime {},
+    )
+    .unwrap();
+    assert!(!leaderboard.entries.iter().any(|e| e.address == "addr0003"));
+
+    let stats = query_player_stats(deps.as_ref(), Addr::unchecked("addr0009")).unwrap();
+    assert!(stats.stats.is_empty());
+}
+
+#[test]
+fn test_leaderboards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let _ = play_resolved_bet(deps.as_mut(), "addr0001", "addr0002", 1000);
+    let _ = play_resolved_bet(deps.as_mut(), "addr0003", "addr0004", 1001);
+    let _ = play_resolved_bet(deps.as_mut(), "addr0003", "addr0004", 1002);
+
+    let entry = |address: &str, value: u128| LeaderboardEntry {
+        address: address.to_string(),
+        value: Uint128::new(value),
+    };
+
+    let leaderboard = query_leaderboard(
+        deps.as_ref(),
+        12_345,
+        "uusd".to_string(),
+        LeaderboardMetric::Volume,
+        LeaderboardPeriod::AllTime {},
+    )
+    .unwrap();
+    assert_eq!(leaderboard.epoch, None);
+    assert_eq!(
+        leaderboard.entries,
+        vec![
+            entry("addr0003", 2000000),
+            entry("addr0004", 2000000),
+            entry("addr0001", 1000000),
+            entry("addr0002", 1000000),
+        ],
+    );
+
+    // losing players are not ranked by profit
+    let leaderboard = query_leaderboard(
+        deps.as_ref(),
+        12_345,
+        "uusd".to_string(),
+        LeaderboardMetric::Profit,
+        LeaderboardPeriod::Epoch { epoch: None },
+    )
+    .unwrap();
+    assert_eq!(leaderboard.epoch, Some(0));
+    assert_eq!(
+        leaderboard.entries,
+        vec![entry("addr0004", 1960000), entry("addr0002", 980000)],
+    );
+
+    let leaderboard = query_leaderboard(
+        deps.as_ref(),
+        12_345,
+        "uusd".to_string(),
+        LeaderboardMetric::WinStreak,
+        LeaderboardPeriod::AllTime {},
+    )
+    .unwrap();
+    assert_eq!(
+        leaderboard.entries,
+        vec![entry("addr0004", 2), entry("addr0002", 1)],
+    );
+
+    // next epoch starts empty
+    let leaderboard = query_leaderboard(
+        deps.as_ref(),
+        112_345,
+        "uusd".to_string(),
+        LeaderboardMetric::Volume,
+        LeaderboardPeriod::Epoch { epoch: None },
+    )
+    .unwrap();
+    assert_eq!(leaderboard.epoch, Some(1));
+    assert!(leaderboard.entries.is_empty());
+
+    let mut leaderboard = Leaderboard::default();
+    leaderboard.update(&Addr::unchecked("addr0001"), Uint128::new(10), 2);
+    leaderboard.update(&Addr::unchecked("addr0002"), Uint128::new(20), 2);
+    leaderboard.update(&Addr::unchecked("addr0003"), Uint128::new(5), 2);
+    leaderboard.update(&Addr::unchecked("addr0001"), Uint128::new(30), 2);
+    assert_eq!(
+        leaderboard.entries,
+        vec![entry("addr0001", 30), entry("addr0002", 20)],
+    );
+}
+
+#[test]
+fn test_creator_reliability() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let _ = play_resolved_bet(deps.as_mut(), "addr0001", "addr0002", 1000);
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone());
+    let _ = liquidate_bet(
+        deps.as_mut(),
+        mock_env_custom(13_345),
+        mock_info("addr0003", &[]),
+        bet_id,
+    )
+    .unwrap();
+
+    let _ = create_valid_pending_bet(deps.as_mut());
+    let info = mock_info(
+        "addr0005",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+    let _ = place_bet(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
+    )
+    .unwrap();
+
+    let filter = |min_creator_reliability: Option<Decimal>, min_creator_games: Option<u64>| {
+        PendingBetsFilter {
+            skip: 0,
+            limit: None,
+            exclude_address: None,
+            assets: None,
+            liquidation: None,
+            min_creator_reliability,
+            min_creator_games,
+            sort_by: PendingBetsSort::Creation { asc: true },
+        }
+    };
+
+    let bets = query_pending_bets(deps.as_ref(), filter(None, None)).unwrap();
+    assert_eq!(bets.len(), 2);
+    let bet = bets.iter().find(|b| b.owner == "addr0005").unwrap();
+    assert_eq!(bet.creator_reliability.reliability, None);
+    assert_eq!(bet.creator_reliability.median_reveal_latency, None);
+
+    // creators without games have no score to pass the filter with
+    let bets = query_pending_bets(deps.as_ref(), filter(Some(Decimal::percent(50)), None)).unwrap();
+    assert_eq!(bets.len(), 1);
+    assert_eq!(
+        bets[0].creator_reliability,
+        CreatorReliabilityResponse {
+            resolved_games: 1,
+            liquidated_games: 1,
+            reliability: Some(Decimal::percent(50)),
+            median_reveal_latency: Some(0),
+        },
+    );
+
+    let bets = query_pending_bets(deps.as_ref(), filter(Some(Decimal::percent(60)), None)).unwrap();
+    assert_eq!(bets.len(), 0);
+
+    let bets = query_pending_bets(deps.as_ref(), filter(None, Some(2))).unwrap();
+    assert_eq!(bets.len(), 1);
+    assert_eq!(bets[0].owner, "addr0001".to_string());
+
+    let bets =
+        query_pending_bets(deps.as_ref(), filter(Some(Decimal::percent(50)), Some(3))).unwrap();
+    assert_eq!(bets.len(), 0);
+
+    let mut reliability = CreatorReliability::default();
+    for latency in [10, 30, 20, 40] {
+        reliability.add_resolved(latency);
+    }
+    assert_eq!(reliability.median_reveal_latency(), Some(25));
+}
+
+#[test]
+fn test_forfeit_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone());
+
+    let res = forfeit_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        bet_id.clone(),
+    )
+    .unwrap_err();
+    match res {
+        ContractError::OnlyBetCreatorAllowedToForfeit {} => {}
+        _ => panic!("unexpected error"),
+    }
+
+    let response = forfeit_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        response.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0002".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::new(1980000u128),
+                }]
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::new(20000u128),
+                }]
+            })),
+        ],
+    );
+
+    match query_bet(deps.as_ref(), bet_id.clone()).unwrap() {
+        BetState::Forfeited(bet) => {
+            assert_eq!(bet.winner, Some("addr0002".to_string()));
+            assert_eq!(bet.liquidator, None);
+        }
+        _ => panic!("bet must be forfeited"),
+    }
+
+    let res = forfeit_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        bet_id,
+    )
+    .unwrap_err();
+    match res {
+        ContractError::GameWasAlreadyLiquidated {} => {}
+        _ => panic!("unexpected error"),
+    }
+}
+
+#[test]
+fn test_resolve_bet_by_relayer() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let coins = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1000000u128),
+    }];
+
+    match place_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins),
+        PlaceBetParams {
+            relayer_tip: Uint128::new(1000000u128),
+            ..PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200))
+        },
+        None,
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(cosmwasm_std::StdError::GenericErr { msg, .. }) => {
+            assert_eq!(
+                msg,
+                "relayer_tip must be less than creator winnings".to_string()
+            )
+        }
+        _ => panic!("no error"),
+    }
+
+    let send = |to: &str, amount: u128| {
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(amount),
+            }],
+        }))
+    };
+
+    // a tip without a named relayer could be taken by anyone copying the passphrase
+    match place_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins),
+        PlaceBetParams {
+            relayer_tip: Uint128::new(10000u128),
+            ..PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200))
+        },
+        None,
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(cosmwasm_std::StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "relayer_tip requires a relayer".to_string())
+        }
+        _ => panic!("no error"),
+    }
+
+    // creator wins, named relayer gets the tip from creator winnings,
+    // anybody else revealing the passphrase gets nothing
+    for (i, (side, resolver, expected)) in vec![
+        (
+            1u8,
+            "addr0009",
+            vec![
+                send("addr0001", 1970000),
+                send("addr0009", 10000),
+                send("addr0000", 20000),
+            ],
+        ),
+        (
+            0u8,
+            "addr0009",
+            vec![send("addr0002", 1980000), send("addr0000", 20000)],
+        ),
+        (
+            1u8,
+            "addr0008",
+            vec![send("addr0001", 1980000), send("addr0000", 20000)],
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut env = mock_env();
+        env.block.height += i as u64;
+        let pb = place_bet(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0001", &coins),
+            PlaceBetParams {
+                relayer_tip: Uint128::new(10000u128),
+                relayer: Some(Addr::unchecked("addr0009")),
+                ..PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200))
+            },
+            None,
+        )
+        .unwrap();
+        let bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+        let _ = respond_bet(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0002", &coins),
+            Addr::unchecked("addr0001"),
+            bet_id.clone(),
+            side,
+            None,
+        )
+        .unwrap();
+
+        let response = resolve_bet(
+            deps.as_mut(),
+            env,
+            mock_info(resolver, &[]),
+            bet_id,
+            MOCK_PASSPHRASE.to_string(),
+        )
+        .unwrap();
+        assert_eq!(response.messages, expected);
+    }
+}
+
+#[test]
+fn test_creator_bond() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let funds = |amount: u128| {
+        [Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(amount),
+        }]
+    };
+    let send = |to: &str, amount: u128| {
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(amount),
+            }],
+        }))
+    };
+
+    match place_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &funds(1050000)),
+        PlaceBetParams {
+            creator_bond: Uint128::new(50000u128),
+            ..PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200))
+        },
+        None,
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(cosmwasm_std::StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "creator_bond must not be less than creator_bond_percent of bet amount".to_string()
+        ),
+        _ => panic!("no error"),
+    }
+
+    // bond is returned on a timely resolve, half of it is slashed on a late resolve
+    // in favour of the responder and on liquidation
+    for (i, (side, action, expected)) in vec![
+        (
+            1u8,
+            "resolve",
+            vec![
+                send("addr0001", 1980000),
+                send("addr0000", 20000),
+                send("addr0001", 100000),
+            ],
+        ),
+        (
+            0u8,
+            "liquidate",
+            vec![
+                send("addr0002", 1846500),
+                send("addr0003", 143500),
+                send("addr0000", 60000),
+                send("addr0001", 50000),
+            ],
+        ),
+        (
+            1u8,
+            "late_resolve",
+            vec![
+                send("addr0001", 1980000),
+                send("addr0000", 20000),
+                send("addr0002", 50000),
+                send("addr0001", 50000),
+            ],
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut env = mock_env();
+        env.block.height += i as u64;
+        let pb = place_bet(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0001", &funds(1100000)),
+            PlaceBetParams {
+                creator_bond: Uint128::new(100000u128),
+                ..PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200))
+            },
+            None,
+        )
+        .unwrap();
+        let bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+        let _ = respond_bet(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0002", &funds(1000000)),
+            Addr::unchecked("addr0001"),
+            bet_id.clone(),
+            side,
+            None,
+        )
+        .unwrap();
+
+        let response = match action {
+            "liquidate" => liquidate_bet(
+                deps.as_mut(),
+                mock_env_custom(13_345),
+                mock_info("addr0003", &[]),
+                bet_id.clone(),
+            )
+            .unwrap(),
+            // past the deadline but still within the responder liquidation window
+            "late_resolve" => resolve_bet(
+                deps.as_mut(),
+                mock_env_custom(12_560),
+                mock_info("addr0001", &[]),
+                bet_id.clone(),
+                MOCK_PASSPHRASE.to_string(),
+            )
+            .unwrap(),
+            _ => resolve_bet(
+                deps.as_mut(),
+                env,
+                mock_info("addr0001", &[]),
+                bet_id.clone(),
+                MOCK_PASSPHRASE.to_string(),
+            )
+            .unwrap(),
+        };
+        assert_eq!(response.messages, expected);
+    }
+
+    // owner staked the bond too, so only the slashed part counts as loss
+    let stats = query_player_stats(deps.as_ref(), Addr::unchecked("addr0001")).unwrap();
+    assert_eq!(stats.stats[0].stats.profit, Uint128::new(1910000u128));
+    assert_eq!(stats.stats[0].stats.loss, Uint128::new(1050000u128));
+}
+
+#[test]
+fn test_time_based_liquidation() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let coins = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1000000u128),
+    }];
+
+    match place_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &coins),
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Time(30)),
+        None,
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(cosmwasm_std::StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "seconds_before_liquidation must be higher than min allowed value".to_string()
+        ),
+        _ => panic!("no error"),
+    }
+
+    let env = mock_env();
+    let pb = place_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &coins),
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Time(600)),
+        None,
+    )
+    .unwrap();
+    let bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+    let _ = respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &coins),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        0,
+        None,
+    )
+    .unwrap();
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    assert_eq!(
+        ongoing_bet.liquidation,
+        Expiration::AtTime(env.block.time.plus_seconds(601))
+    );
+    assert_eq!(
+        ongoing_bet.responder_liquidation,
+        Expiration::AtTime(env.block.time.plus_seconds(721))
+    );
+
+    // block height does not matter for time based deadlines
+    let mut later = env.clone();
+    later.block.height += 1_000_000;
+    later.block.time = env.block.time.plus_seconds(600);
+    match liquidate_bet(
+        deps.as_mut(),
+        later.clone(),
+        mock_info("addr0002", &[]),
+        bet_id.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::BetIsNotLiquidatableYet {} => {}
+        _ => panic!("no error"),
+    }
+
+    later.block.time = env.block.time.plus_seconds(601);
+    match liquidate_bet(
+        deps.as_mut(),
+        later.clone(),
+        mock_info("addr0003", &[]),
+        bet_id.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::ResponderLiquidationGapIsNotPassedYet {} => {}
+        _ => panic!("no error"),
+    }
+
+    let bets = query_public_liquidatable_bets(deps.as_ref(), &later.block, 0, None, None).unwrap();
+    assert!(bets.is_empty());
+
+    later.block.time = env.block.time.plus_seconds(721);
+    let bets = query_public_liquidatable_bets(deps.as_ref(), &later.block, 0, None, None).unwrap();
+    assert_eq!(bets.len(), 1);
+
+    let _ = liquidate_bet(deps.as_mut(), later, mock_info("addr0003", &[]), bet_id).unwrap();
+}
+
+#[test]
+fn test_dual_commit_mode() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let coins = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1000000u128),
+    }];
+
+    let env = mock_env();
+    let pb = place_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &coins),
+        PlaceBetParams {
+            mode: GameMode::DualCommit,
+            ..PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200))
+        },
+        None,
+    )
+    .unwrap();
+    let bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+    match respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &coins),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        0,
+        None,
+    )
+    .unwrap_err()
+    {
+        ContractError::GameModeMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    match commit_respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &coins),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        MOCK_SIGNATURE.to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::SignatureMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    let responder_passphrase = "1_responder".to_string();
+    let _ = commit_respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &coins),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        calculate_sha256(&responder_passphrase),
+    )
+    .unwrap();
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    assert_eq!(ongoing_bet.mode, GameMode::DualCommit);
+    assert_eq!(ongoing_bet.responder_side, None);
+
+    match resolve_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &[]),
+        bet_id.clone(),
+        "0_wrong".to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::SignatureMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    // first reveal is only stored
+    let res = resolve_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &[]),
+        bet_id.clone(),
+        responder_passphrase.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    assert_eq!(
+        ongoing_bet.responder_passphrase,
+        Some(responder_passphrase.clone())
+    );
+
+    // creator revealed heads, responder tails, so xor gives responder the win
+    let _ = resolve_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &[]),
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.mode, GameMode::DualCommit);
+    assert_eq!(historical_bet.outcome, GameOutcome::Resolved);
+    assert_eq!(historical_bet.winner, Some("addr0002".to_string()));
+    assert_eq!(historical_bet.responder_side, Some(FlipSide::Tails.u8()));
+    assert_eq!(historical_bet.revealed_side, Some(FlipSide::Heads.u8()));
+    assert_eq!(
+        historical_bet.responder_passphrase,
+        Some(responder_passphrase)
+    );
+    assert_eq!(historical_bet.payouts[0].role, PayoutRole::Winner);
+    assert_eq!(historical_bet.payouts.len(), 2);
+}
+
+#[test]
+fn test_dual_commit_liquidation() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let coins = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1000000u128),
+    }];
+
+    let mut bet_ids = vec![];
+    for i in 0..2 {
+        let env = mock_env_custom(12345 + i);
+        let pb = place_bet(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0001", &coins),
+            PlaceBetParams {
+                mode: GameMode::DualCommit,
+                ..PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200))
+            },
+            None,
+        )
+        .unwrap();
+        let bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+        let _ = commit_respond_bet(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0002", &coins),
+            Addr::unchecked("addr0001"),
+            bet_id.clone(),
+            calculate_sha256("1_responder"),
+        )
+        .unwrap();
+        bet_ids.push(bet_id);
+    }
+
+    // only creator reveals the first bet
+    let _ = resolve_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        bet_ids[0].clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    let mut env = mock_env_custom(12345 + 200 + 2);
+    match liquidate_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &[]),
+        bet_ids[0].clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::ForbiddenForBetResponderToLiquidateHimself {} => {}
+        _ => panic!("no error"),
+    }
+
+    match liquidate_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &[]),
+        bet_ids[0].clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::ResponderLiquidationGapIsNotPassedYet {} => {}
+        _ => panic!("no error"),
+    }
+
+    let _ = liquidate_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_ids[0].clone(),
+    )
+    .unwrap();
+
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.outcome, GameOutcome::Liquidated);
+    assert_eq!(historical_bet.winner, Some("addr0001".to_string()));
+    assert_eq!(historical_bet.payouts[0].recipient, "addr0001".to_string());
+    assert_eq!(historical_bet.payouts[0].role, PayoutRole::Winner);
+    assert_eq!(
+        historical_bet.payouts[0].gross_amount,
+        Uint128::new(1800000u128)
+    );
+
+    // nobody revealed the second bet, so both stakes are returned
+    match liquidate_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_ids[1].clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::ResponderLiquidationGapIsNotPassedYet {} => {}
+        _ => panic!("no error"),
+    }
+
+    env.block.height += 21;
+    let _ = liquidate_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &[]),
+        bet_ids[1].clone(),
+    )
+    .unwrap();
+
+    let historical_bet = load_historical_bet(&deps.storage, 1).unwrap();
+    assert_eq!(historical_bet.winner, None);
+    assert_eq!(
+        historical_bet
+            .payouts
+            .iter()
+            .map(|p| (p.recipient.clone(), p.gross_amount))
+            .collect::<Vec<_>>(),
+        vec![
+            ("addr0001".to_string(), Uint128::new(1000000u128)),
+            ("addr0002".to_string(), Uint128::new(1000000u128)),
+        ]
+    );
+
+    let stats = query_player_stats(deps.as_ref(), Addr::unchecked("addr0002")).unwrap();
+    assert_eq!(stats.stats[0].stats.liquidations_suffered, 1);
+    assert_eq!(stats.stats[0].stats.losses, 1);
+}
+
+#[test]
+fn test_beacon_mode() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let coins = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1000000u128),
+    }];
+
+    // drand round 72783 is the latest one at response time
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1595431050 + 72782 * 30);
+
+    let pb = place_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &coins),
+        PlaceBetParams {
+            mode: GameMode::Beacon,
+            ..PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200))
+        },
+        None,
+    )
+    .unwrap();
+    let bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+    match commit_respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &coins),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        calculate_sha256("1_responder"),
+    )
+    .unwrap_err()
+    {
+        ContractError::GameModeMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    let _ = respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &coins),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        0,
+        None,
+    )
+    .unwrap();
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    assert_eq!(ongoing_bet.beacon_round, Some(72785));
+
+    match resolve_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::GameModeMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    match settle_with_beacon(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &[]),
+        bet_id.clone(),
+        72786,
+        DRAND_PREVIOUS_SIGNATURE.to_string(),
+        DRAND_SIGNATURE.to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::BeaconRoundMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    match settle_with_beacon(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &[]),
+        bet_id.clone(),
+        72785,
+        DRAND_SIGNATURE.to_string(),
+        DRAND_SIGNATURE.to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::BeaconSignatureMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    let _ = settle_with_beacon(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &[]),
+        bet_id.clone(),
+        72785,
+        DRAND_PREVIOUS_SIGNATURE.to_string(),
+        DRAND_SIGNATURE.to_string(),
+    )
+    .unwrap();
+
+    // beacon randomness ends with an odd byte, so the coin shows tails
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.mode, GameMode::Beacon);
+    assert_eq!(historical_bet.outcome, GameOutcome::Resolved);
+    assert_eq!(historical_bet.winner, Some("addr0001".to_string()));
+    assert_eq!(historical_bet.revealed_side, Some(FlipSide::Tails.u8()));
+    assert_eq!(historical_bet.beacon_round, Some(72785));
+    assert_eq!(historical_bet.passphrase, None);
+
+    let reliability =
+        load_creator_reliability(&deps.storage, &Addr::unchecked("addr0001")).unwrap();
+    assert_eq!(reliability.resolved_games, 0);
+}
+
+#[test]
+fn test_dice_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let env = mock_env();
+    let passphrase = "4_dice".to_string();
+    let creator_coins = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(4000000u128),
+    }];
+
+    let invalid_odds = vec![
+        (
+            Odds {
+                sides: 1,
+                responder_picks: 1,
+            },
+            "sides must be between 2 and 100",
+        ),
+        (
+            Odds {
+                sides: 6,
+                responder_picks: 6,
+            },
+            "responder_picks must be higher than 0 and less than sides",
+        ),
+        // responder stake would be below min bet amount
+        (
+            Odds {
+                sides: 6,
+                responder_picks: 1,
+            },
+            "provided amount less than min limit for provided asset",
+        ),
+    ];
+    for (odds, message) in invalid_odds {
+        match place_bet(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0001", &creator_coins),
+            PlaceBetParams {
+                odds,
+                ..PlaceBetParams::new(calculate_sha256(&passphrase), Duration::Height(200))
+            },
+            None,
+        )
+        .unwrap_err()
+        {
+            ContractError::Std(cosmwasm_std::StdError::GenericErr { msg, .. }) => {
+                assert_eq!(msg, message.to_string())
+            }
+            _ => panic!("no error"),
+        }
+    }
+
+    let odds = Odds {
+        sides: 6,
+        responder_picks: 2,
+    };
+
+    // creator wins only 2000000 less 1% treasury tax of the 6000000 pot
+    match place_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &creator_coins),
+        PlaceBetParams {
+            odds: odds.clone(),
+            relayer_tip: Uint128::new(1940000u128),
+            relayer: Some(Addr::unchecked("addr0003")),
+            ..PlaceBetParams::new(calculate_sha256(&passphrase), Duration::Height(200))
+        },
+        None,
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(cosmwasm_std::StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "relayer_tip must be less than creator winnings".to_string()
+        ),
+        _ => panic!("no error"),
+    }
+
+    match place_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &creator_coins),
+        PlaceBetParams {
+            mode: GameMode::DualCommit,
+            odds: odds.clone(),
+            ..PlaceBetParams::new(calculate_sha256(&passphrase), Duration::Height(200))
+        },
+        None,
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(cosmwasm_std::StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "dual commit mode supports coinflip odds only".to_string()
+        ),
+        _ => panic!("no error"),
+    }
+
+    let pb = place_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &creator_coins),
+        PlaceBetParams {
+            odds: odds.clone(),
+            ..PlaceBetParams::new(calculate_sha256(&passphrase), Duration::Height(200))
+        },
+        None,
+    )
+    .unwrap();
+    let bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+    let pending_bets = query_pending_bets(
+        deps.as_ref(),
+        PendingBetsFilter {
+            skip: 0,
+            limit: None,
+            exclude_address: None,
+            assets: None,
+            liquidation: None,
+            min_creator_reliability: None,
+            min_creator_games: None,
+            sort_by: PendingBetsSort::Creation { asc: true },
+        },
+    )
+    .unwrap();
+    assert_eq!(pending_bets[0].odds, odds);
+    assert_eq!(pending_bets[0].responder_stake, Uint128::new(2000000u128));
+
+    // responder stakes half of creator stake for 2 of 6 outcomes
+    let responder_coins = [Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(2000000u128),
+    }];
+    match respond_dice_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &creator_coins),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        vec![0, 1],
+    )
+    .unwrap_err()
+    {
+        ContractError::ResponderAssetMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    let invalid_picks = vec![
+        (vec![0], "number of picks must be equal to responder_picks"),
+        (vec![0, 6], "invalid outcome"),
+        (vec![1, 1], "picks must be unique"),
+    ];
+    for (picks, message) in invalid_picks {
+        match respond_dice_bet(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0002", &responder_coins),
+            Addr::unchecked("addr0001"),
+            bet_id.clone(),
+            picks,
+        )
+        .unwrap_err()
+        {
+            ContractError::Std(cosmwasm_std::StdError::GenericErr { msg, .. }) => {
+                assert_eq!(msg, message.to_string())
+            }
+            _ => panic!("no error"),
+        }
+    }
+
+    let _ = respond_dice_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &responder_coins),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        vec![0, 1],
+    )
+    .unwrap();
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    assert_eq!(ongoing_bet.asset.amount, Uint128::new(6000000u128));
+    assert_eq!(ongoing_bet.responder_stake, Uint128::new(2000000u128));
+    assert_eq!(ongoing_bet.responder_side, None);
+    assert_eq!(ongoing_bet.responder_picks, vec![0, 1]);
+
+    let _ = resolve_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        passphrase,
+    )
+    .unwrap();
+
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.winner, Some("addr0001".to_string()));
+    assert_eq!(historical_bet.revealed_side, Some(4));
+    assert_eq!(historical_bet.odds, odds);
+    assert_eq!(historical_bet.responder_picks, vec![0, 1]);
+    assert_eq!(
+        historical_bet.payouts[0].gross_amount,
+        Uint128::new(5940000u128)
+    );
+
+    let stats = query_player_stats(deps.as_ref(), Addr::unchecked("addr0002")).unwrap();
+    assert_eq!(stats.stats[0].stats.volume, Uint128::new(2000000u128));
+    assert_eq!(stats.stats[0].stats.loss, Uint128::new(2000000u128));
+}
+
+fn create_pool_bet(deps: DepsMut, amount: u128) -> String {
+    let pb = place_bet(
+        deps,
+        mock_env(),
+        mock_info(
+            "addr0001",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(amount),
+            }],
+        ),
+        PlaceBetParams {
+            mode: GameMode::Pool,
+            ..PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200))
+        },
+        None,
+    )
+    .unwrap();
+
+    pb.attributes.get(2).expect("no bet_id").value.clone()
+}
+
+fn uusd(amount: u128) -> Vec<Coin> {
+    vec![Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(amount),
+    }]
+}
+
+#[test]
+fn test_pool_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let env = mock_env();
+    let bet_id = create_pool_bet(deps.as_mut(), 3000000u128);
+    let owner = Addr::unchecked("addr0001");
+
+    match respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &uusd(3000000u128)),
+        owner.clone(),
+        bet_id.clone(),
+        0,
+        None,
+    )
+    .unwrap_err()
+    {
+        ContractError::GameModeMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    let _ = fill_pool_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &uusd(1000000u128)),
+        owner.clone(),
+        bet_id.clone(),
+        0,
+    )
+    .unwrap();
+
+    let pending_bets = load_pending_bets(&deps.storage, &owner).unwrap();
+    assert_eq!(pending_bets.bets[0].pool_side, Some(FlipSide::Heads));
+    assert_eq!(
+        pending_bets.bets[0].pool,
+        vec![PoolFill {
+            responder: Addr::unchecked("addr0002"),
+            amount: Uint128::new(1000000u128),
+            filled_at_block: 12345,
+        }]
+    );
+
+    match withdraw_pending_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::PoolBetHasFills {} => {}
+        _ => panic!("no error"),
+    }
+
+    match fill_pool_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &uusd(2000000u128)),
+        owner.clone(),
+        bet_id.clone(),
+        1,
+    )
+    .unwrap_err()
+    {
+        ContractError::PoolSideMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    match fill_pool_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &uusd(2500000u128)),
+        owner.clone(),
+        bet_id.clone(),
+        0,
+    )
+    .unwrap_err()
+    {
+        ContractError::PoolFillExceedsRemainingStake {} => {}
+        _ => panic!("no error"),
+    }
+
+    match fill_pool_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &uusd(500000u128)),
+        owner.clone(),
+        bet_id.clone(),
+        0,
+    )
+    .unwrap_err()
+    {
+        ContractError::Std { .. } => {}
+        _ => panic!("no error"),
+    }
+
+    // filling the rest starts the game
+    let _ = fill_pool_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &uusd(2000000u128)),
+        owner.clone(),
+        bet_id.clone(),
+        0,
+    )
+    .unwrap();
+
+    assert_eq!(
+        load_pending_bets_count(&deps.storage).unwrap(),
+        Uint64::zero()
+    );
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    assert_eq!(ongoing_bet.mode, GameMode::Pool);
+    assert_eq!(ongoing_bet.bet_responder, Addr::unchecked("addr0002"));
+    assert_eq!(ongoing_bet.asset.amount, Uint128::new(6000000u128));
+    assert_eq!(ongoing_bet.responder_stake, Uint128::new(3000000u128));
+    assert_eq!(ongoing_bet.pool.len(), 2);
+
+    // creator revealed heads, so the pool wins and shares the pot by fills
+    let res = resolve_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages.first().expect("no message"),
+        &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0002".to_string(),
+            amount: uusd(1960396u128),
+        }))
+    );
+    assert_eq!(
+        res.messages.get(1).expect("no message"),
+        &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0003".to_string(),
+            amount: uusd(3920792u128),
+        }))
+    );
+
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.mode, GameMode::Pool);
+    assert_eq!(
+        historical_bet.payouts[1].gross_amount,
+        Uint128::new(3960000u128)
+    );
+    assert!(historical_bet.is_won_by("addr0003"));
+    assert!(historical_bet.is_lost_by("addr0001"));
+    assert_eq!(
+        historical_bet.staked_by("addr0003").unwrap(),
+        Uint128::new(2000000u128)
+    );
+
+    let mut filter = history_filter();
+    filter.address = Some("addr0003".to_string());
+    filter.role = Some(HistoricalBetRole::Responder);
+    let history = query_historical_bet(deps.as_ref(), filter).unwrap();
+    assert_eq!(history.history.len(), 1);
+    assert_eq!(history.totals[0].profit, Uint128::new(1920792u128));
+
+    let stats = query_player_stats(deps.as_ref(), Addr::unchecked("addr0003")).unwrap();
+    assert_eq!(stats.stats[0].stats.games_as_responder, 1);
+    assert_eq!(stats.stats[0].stats.wins, 1);
+    assert_eq!(stats.stats[0].stats.volume, Uint128::new(2000000u128));
+}
+
+#[test]
+fn test_pool_bet_close() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let env = mock_env();
+    let owner = Addr::unchecked("addr0001");
+
+    // closing an empty pool withdraws the bet
+    let bet_id = create_pool_bet(deps.as_mut(), 3000000u128);
+    let _ = close_pool_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+    )
+    .unwrap();
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.outcome, GameOutcome::Withdrawn);
+
+    let bet_id = create_pool_bet(deps.as_mut(), 3000000u128);
+    for responder in ["addr0002", "addr0003"] {
+        let _ = fill_pool_bet(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(responder, &uusd(1000000u128)),
+            owner.clone(),
+            bet_id.clone(),
+            1,
+        )
+        .unwrap();
+    }
+
+    match close_pool_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &[]),
+        bet_id.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::GameWasAlreadyAccepted {} => {}
+        _ => panic!("no error"),
+    }
+
+    // unfilled part of creator stake is returned
+    let res = close_pool_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages.first().expect("no message"),
+        &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0001".to_string(),
+            amount: uusd(990099u128),
+        }))
+    );
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    assert_eq!(ongoing_bet.asset.amount, Uint128::new(4000000u128));
+    assert_eq!(ongoing_bet.responder_stake, Uint128::new(2000000u128));
+    assert_eq!(ongoing_bet.responder_side, Some(FlipSide::Tails));
+
+    // any pool responder may liquidate within the exclusive window
+    let res = liquidate_bet(
+        deps.as_mut(),
+        mock_env_custom(12_550),
+        mock_info("addr0003", &[]),
+        bet_id.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 4);
+
+    let historical_bet = load_historical_bet(&deps.storage, 1).unwrap();
+    assert_eq!(historical_bet.outcome, GameOutcome::Liquidated);
+    assert_eq!(
+        historical_bet.payouts[..3]
+            .iter()
+            .map(|p| (p.recipient.as_str(), p.role.clone(), p.gross_amount))
+            .collect::<Vec<_>>(),
+        vec![
+            ("addr0002", PayoutRole::Responder, Uint128::new(1800000u128)),
+            ("addr0003", PayoutRole::Responder, Uint128::new(1800000u128)),
+            ("addr0003", PayoutRole::Liquidator, Uint128::new(280000u128)),
+        ]
+    );
+    assert!(historical_bet.is_won_by("addr0003"));
+}
+
+#[test]
+fn test_withdraw_pool_fill() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let env = mock_env();
+    let bet_id = create_pool_bet(deps.as_mut(), 3000000u128);
+    let owner = Addr::unchecked("addr0001");
+
+    for responder in ["addr0002", "addr0003"] {
+        let _ = fill_pool_bet(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(responder, &uusd(1000000u128)),
+            owner.clone(),
+            bet_id.clone(),
+            1,
+        )
+        .unwrap();
+    }
+
+    match withdraw_pool_fill(
+        deps.as_mut(),
+        mock_info("addr0004", &[]),
+        owner.clone(),
+        bet_id.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::PoolFillNotFound {} => {}
+        _ => panic!("no error"),
+    }
+
+    let res = withdraw_pool_fill(
+        deps.as_mut(),
+        mock_info("addr0002", &[]),
+        owner.clone(),
+        bet_id.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages.first().expect("no message"),
+        &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0002".to_string(),
+            amount: uusd(990099u128),
+        }))
+    );
+
+    let pending_bets = load_pending_bets(&deps.storage, &owner).unwrap();
+    assert_eq!(pending_bets.bets[0].pool_side, Some(FlipSide::Tails));
+    assert_eq!(
+        pending_bets.bets[0].pool,
+        vec![PoolFill {
+            responder: Addr::unchecked("addr0003"),
+            amount: Uint128::new(1000000u128),
+            filled_at_block: 12345,
+        }]
+    );
+
+    // the last fill leaving frees the pool side and lets the creator withdraw
+    let _ = withdraw_pool_fill(
+        deps.as_mut(),
+        mock_info("addr0003", &[]),
+        owner.clone(),
+        bet_id.clone(),
+    )
+    .unwrap();
+
+    let pending_bets = load_pending_bets(&deps.storage, &owner).unwrap();
+    assert_eq!(pending_bets.bets[0].pool_side, None);
+    assert!(pending_bets.bets[0].pool.is_empty());
+
+    let _ = fill_pool_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &uusd(3000000u128)),
+        owner.clone(),
+        bet_id.clone(),
+        0,
+    )
+    .unwrap();
+
+    match withdraw_pool_fill(
+        deps.as_mut(),
+        mock_info("addr0002", &[]),
+        owner,
+        bet_id.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::BetWasCancledOrAccepted {} => {}
+        _ => panic!("no error"),
+    }
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id).unwrap();
+    assert_eq!(ongoing_bet.responder_side, Some(FlipSide::Heads));
+}
+
+fn place_uusd_bet(deps: DepsMut, owner: &str, amount: u128, blocks: u64) -> Response {
+    place_bet(
+        deps,
+        mock_env(),
+        mock_info(owner, &uusd(amount)),
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(blocks)),
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_standing_offers() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let env = mock_env();
+
+    match post_offer(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &uusd(3500000u128)),
+        0,
+        Uint128::new(500000u128),
+        Uint128::new(2000000u128),
+        Duration::Height(300),
+    )
+    .unwrap_err()
+    {
+        ContractError::Std { .. } => {}
+        _ => panic!("no error"),
+    }
+
+    let _ = post_offer(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &uusd(3500000u128)),
+        0,
+        Uint128::new(1000000u128),
+        Uint128::new(2000000u128),
+        Duration::Height(300),
+    )
+    .unwrap();
+
+    // bet fitting the offer is accepted in the same tx
+    let res = place_uusd_bet(deps.as_mut(), "addr0001", 1000000u128, 200);
+    let bet_id = res.attributes.get(2).expect("no bet_id").value.clone();
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(res.events[0].attributes[0], attr("offer_id", "0"));
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id).unwrap();
+    assert_eq!(ongoing_bet.bet_responder, Addr::unchecked("addr0002"));
+    assert_eq!(ongoing_bet.responder_side, Some(FlipSide::Heads));
+    assert_eq!(ongoing_bet.asset.amount, Uint128::new(2000000u128));
+    assert_eq!(
+        load_pending_bets_count(&deps.storage).unwrap(),
+        Uint64::zero()
+    );
+
+    let offers = query_offers_by_addr(deps.as_ref(), Addr::unchecked("addr0002")).unwrap();
+    assert_eq!(offers.offers[0].asset.amount, Uint128::new(2500000u128));
+
+    // liquidation deadline beyond the offer limit stays pending
+    let res = place_uusd_bet(deps.as_mut(), "addr0001", 1000000u128, 400);
+    assert_eq!(res.events.len(), 0);
+    assert_eq!(
+        load_pending_bets_count(&deps.storage).unwrap(),
+        Uint64::new(1)
+    );
+
+    // offer owner does not play against themselves
+    let res = place_uusd_bet(deps.as_mut(), "addr0002", 1000000u128, 200);
+    assert_eq!(res.events.len(), 0);
+
+    // escrow left below min amount is returned and the offer is closed
+    let res = place_uusd_bet(deps.as_mut(), "addr0003", 2000000u128, 200);
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(
+        res.messages.first().expect("no message"),
+        &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0002".to_string(),
+            amount: uusd(495049u128),
+        }))
+    );
+    let offers = query_offers(deps.as_ref(), Some("uusd".to_string()), 0, None).unwrap();
+    assert_eq!(offers.offers.len(), 0);
+
+    let _ = post_offer(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0004", &uusd(3000000u128)),
+        1,
+        Uint128::new(1000000u128),
+        Uint128::new(1000000u128),
+        Duration::Height(300),
+    )
+    .unwrap();
+
+    match withdraw_from_offer(
+        deps.as_mut(),
+        mock_info("addr0004", &[]),
+        1,
+        Uint128::new(2500000u128),
+    )
+    .unwrap_err()
+    {
+        ContractError::Std { .. } => {}
+        _ => panic!("no error"),
+    }
+
+    let res = withdraw_from_offer(
+        deps.as_mut(),
+        mock_info("addr0004", &[]),
+        1,
+        Uint128::new(1000000u128),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[4], attr("remaining", "2000000"));
+
+    match cancel_offer(deps.as_mut(), mock_info("addr0002", &[]), 1).unwrap_err() {
+        ContractError::Unauthorized {} => {}
+        _ => panic!("no error"),
+    }
+
+    let res = cancel_offer(deps.as_mut(), mock_info("addr0004", &[]), 1).unwrap();
+    assert_eq!(
+        res.messages.first().expect("no message"),
+        &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0004".to_string(),
+            amount: uusd(1980198u128),
+        }))
+    );
+    let offers = query_offers(deps.as_ref(), None, 0, None).unwrap();
+    assert_eq!(offers.offers.len(), 0);
+
+    // offer of a player out of their limits is skipped for the next one
+    for owner in ["addr0005", "addr0006"] {
+        let _ = post_offer(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(owner, &uusd(2000000u128)),
+            0,
+            Uint128::new(1000000u128),
+            Uint128::new(2000000u128),
+            Duration::Height(300),
+        )
+        .unwrap();
+    }
+    let now = env.block.time.seconds();
+    self_exclude(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0005", &[]),
+        now + 1000,
+    )
+    .unwrap();
+
+    let res = place_uusd_bet(deps.as_mut(), "addr0007", 1000000u128, 200);
+    let bet_id = res.attributes.get(2).expect("no bet_id").value.clone();
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(res.events[0].attributes[0], attr("offer_id", "3"));
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id).unwrap();
+    assert_eq!(ongoing_bet.bet_responder, Addr::unchecked("addr0006"));
+
+    // offer capped below the shortest allowed deadline could never be matched
+    match post_offer(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0008", &uusd(2000000u128)),
+        0,
+        Uint128::new(1000000u128),
+        Uint128::new(2000000u128),
+        Duration::Height(50),
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(cosmwasm_std::StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "max_until_liquidation must not be less than min allowed value".to_string()
+        ),
+        _ => panic!("no error"),
+    }
+
+    // a pile of smaller offers does not hide an offer fitting a bigger stake
+    for i in 0..30 {
+        let _ = post_offer(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(&format!("addr3{:03}", i), &uusd(1000000u128)),
+            0,
+            Uint128::new(1000000u128),
+            Uint128::new(1000000u128),
+            Duration::Height(300),
+        )
+        .unwrap();
+    }
+    let res = post_offer(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0008", &uusd(2000000u128)),
+        0,
+        Uint128::new(2000000u128),
+        Uint128::new(2000000u128),
+        Duration::Height(300),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("offer_id", "34"));
+
+    let res = place_uusd_bet(deps.as_mut(), "addr0009", 2000000u128, 200);
+    let bet_id = res.attributes.get(2).expect("no bet_id").value.clone();
+    assert_eq!(res.events[0].attributes[0], attr("offer_id", "34"));
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id).unwrap();
+    assert_eq!(ongoing_bet.bet_responder, Addr::unchecked("addr0008"));
+}
+
+#[test]
+fn test_respond_best_match() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let mut bet_ids = vec![];
+    for (i, (owner, amount, mode)) in [
+        ("addr0001", 2000000u128, GameMode::Classic),
+        ("addr0002", 1000000u128, GameMode::Classic),
+        ("addr0003", 1000000u128, GameMode::DualCommit),
+        ("addr0004", 1000000u128, GameMode::Classic),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(10000 + i as u64);
+        let pb = place_bet(
+            deps.as_mut(),
+            env,
+            mock_info(owner, &uusd(*amount)),
+            PlaceBetParams {
+                mode: mode.clone(),
+                ..PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200))
+            },
+            None,
+        )
+        .unwrap();
+        bet_ids.push(pb.attributes.get(2).expect("no bet_id").value.clone());
+    }
+
+    let filter = |asc: bool| PendingBetsFilter {
+        skip: 0,
+        limit: None,
+        exclude_address: None,
+        assets: None,
+        liquidation: None,
+        min_creator_reliability: None,
+        min_creator_games: None,
+        sort_by: PendingBetsSort::Creation { asc },
+    };
+
+    // own bet and bets of another stake or mode are passed over
+    let _ = respond_best_match(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &uusd(1000000u128)),
+        filter(true),
+        1,
+    )
+    .unwrap();
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_ids[3].clone()).unwrap();
+    assert_eq!(ongoing_bet.bet_creator, Addr::unchecked("addr0004"));
+    assert_eq!(ongoing_bet.bet_responder, Addr::unchecked("addr0002"));
+    assert_eq!(ongoing_bet.responder_side, Some(FlipSide::Tails));
+
+    let _ = respond_best_match(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0005", &uusd(1000000u128)),
+        filter(false),
+        0,
+    )
+    .unwrap();
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_ids[1].clone()).unwrap();
+    assert_eq!(ongoing_bet.bet_responder, Addr::unchecked("addr0005"));
+
+    match respond_best_match(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0005", &uusd(1000000u128)),
+        filter(true),
+        0,
+    )
+    .unwrap_err()
+    {
+        ContractError::NoMatchingBet {} => {}
+        _ => panic!("no error"),
+    }
+
+    match respond_best_match(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0005", &uusd(2000000u128)),
+        filter(true),
+        2,
+    )
+    .unwrap_err()
+    {
+        ContractError::NoMatchingBet {} => {}
+        _ => panic!("no error"),
+    }
+
+    // oldest bets are scanned first whatever the address of their owner
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(10004);
+    let pb = place_bet(
+        deps.as_mut(),
+        env,
+        mock_info("addr3000", &uusd(3000000u128)),
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
+    )
+    .unwrap();
+    let oldest_bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+    for i in 0..98 {
+        let _ = place_uusd_bet(deps.as_mut(), &format!("addr1{:03}", i), 2000000u128, 200);
+    }
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(1);
+    let pb = place_bet(
+        deps.as_mut(),
+        env,
+        mock_info("addr2000", &uusd(3000000u128)),
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
+    )
+    .unwrap();
+    let newest_bet_id = pb.attributes.get(2).expect("no bet_id").value.clone();
+
+    let _ = respond_best_match(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0005", &uusd(3000000u128)),
+        filter(true),
+        0,
+    )
+    .unwrap();
+    let ongoing_bet = load_ongoing_bet(&deps.storage, oldest_bet_id).unwrap();
+    assert_eq!(ongoing_bet.bet_creator, Addr::unchecked("addr3000"));
+    assert_eq!(ongoing_bet.bet_responder, Addr::unchecked("addr0005"));
+
+    // newest bet is past the 100 oldest ones, so it is only reached from the newest end
+    match respond_best_match(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0005", &uusd(3000000u128)),
+        filter(true),
+        0,
+    )
+    .unwrap_err()
+    {
+        ContractError::NoMatchingBet {} => {}
+        _ => panic!("no error"),
+    }
+
+    let _ = respond_best_match(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0005", &uusd(3000000u128)),
+        filter(false),
+        0,
+    )
+    .unwrap();
+    let ongoing_bet = load_ongoing_bet(&deps.storage, newest_bet_id).unwrap();
+    assert_eq!(ongoing_bet.bet_creator, Addr::unchecked("addr2000"));
+    assert_eq!(ongoing_bet.bet_responder, Addr::unchecked("addr0005"));
+}
+
+// responder picks heads and wins the game, rolling their winnings into a rematch
+fn play_rematch_offered_bet(mut deps: DepsMut, rematch_passphrase: &str) -> Response {
+    let bet_id = create_valid_pending_bet(deps.branch());
+    create_valid_ongoing_bet(deps.branch(), bet_id.clone());
+
+    let _ = offer_rematch(
+        deps.branch(),
+        mock_info("addr0002", &[]),
+        bet_id.clone(),
+        calculate_sha256(rematch_passphrase),
+    )
+    .unwrap();
+
+    resolve_bet(
+        deps,
+        mock_env(),
+        mock_info("addr0001", &[]),
+        bet_id,
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_rematch_out_of_limits() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone());
+
+    let _ = offer_rematch(
+        deps.as_mut(),
+        mock_info("addr0002", &[]),
+        bet_id.clone(),
+        calculate_sha256("1_rematch"),
+    )
+    .unwrap();
+
+    // winner who excluded themselves after offering the rematch is paid out instead
+    let env = mock_env();
+    let now = env.block.time.seconds();
+    self_exclude(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &[]),
+        now + 1000,
+    )
+    .unwrap();
+
+    let res = resolve_bet(
+        deps.as_mut(),
+        env,
+        mock_info("addr0001", &[]),
+        bet_id,
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages.first().expect("no message"),
+        &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0002".to_string(),
+            amount: uusd(1960396u128),
+        }))
+    );
+    assert!(!res.attributes.iter().any(|a| a.key == "rematch_bet_id"));
+
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.payouts[0].role, PayoutRole::Winner);
+
+    let pending_bets = load_pending_bets(&deps.storage, &Addr::unchecked("addr0002")).unwrap();
+    assert!(pending_bets.bets.is_empty());
+}
+
+#[test]
+fn test_rematch_over_bets_limit() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let mut config = load_config(&deps.storage).unwrap();
+    config.max_bets_by_addr = 1;
+    store_config(&mut deps.storage, &config).unwrap();
+
+    // winner already has as many pending bets as allowed, so the rematch is paid out instead
+    let _ = place_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &uusd(1000000u128)),
+        PlaceBetParams::new(calculate_sha256("1_other"), Duration::Height(200)),
+        None,
+    )
+    .unwrap();
+
+    let res = play_rematch_offered_bet(deps.as_mut(), "1_rematch");
+    assert_eq!(
+        res.messages.first().expect("no message"),
+        &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0002".to_string(),
+            amount: uusd(1960396u128),
+        }))
+    );
+    assert!(!res.attributes.iter().any(|a| a.key == "rematch_bet_id"));
+
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.payouts[0].role, PayoutRole::Winner);
+
+    let pending_bets = load_pending_bets(&deps.storage, &Addr::unchecked("addr0002")).unwrap();
+    assert_eq!(pending_bets.bets.len(), 1);
+    assert!(pending_bets.bets[0].rematch.is_none());
+}
+
+#[test]
+fn test_rematch() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone());
+
+    match offer_rematch(
+        deps.as_mut(),
+        mock_info("addr0003", &[]),
+        bet_id.clone(),
+        calculate_sha256("1_rematch"),
+    )
+    .unwrap_err()
+    {
+        ContractError::OnlyBetPlayersAllowedToOfferRematch {} => {}
+        _ => panic!("no error"),
+    }
+
+    match offer_rematch(
+        deps.as_mut(),
+        mock_info("addr0002", &[]),
+        bet_id.clone(),
+        MOCK_SIGNATURE.to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::SignatureMismatch {} => {}
+        _ => panic!("no error"),
+    }
+
+    let _ = offer_rematch(
+        deps.as_mut(),
+        mock_info("addr0002", &[]),
+        bet_id.clone(),
+        calculate_sha256("1_rematch"),
+    )
+    .unwrap();
+
+    // winnings stay in the contract, only treasury is paid
+    let res = resolve_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    let rematch_bet_id = res.attributes[0].value.clone();
+
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.winner, Some("addr0002".to_string()));
+    assert_eq!(historical_bet.payouts[0].role, PayoutRole::Rematch);
+    assert_eq!(
+        historical_bet.payouts[0].net_amount,
+        Uint128::new(1980000u128)
+    );
+
+    let pending_bets = load_pending_bets(&deps.storage, &Addr::unchecked("addr0002")).unwrap();
+    let rematch_bet = &pending_bets.bets[0];
+    assert_eq!(rematch_bet.id, rematch_bet_id);
+    assert_eq!(rematch_bet.asset.amount, Uint128::new(1980000u128));
+    assert_eq!(
+        rematch_bet.rematch.as_ref().unwrap().opponent,
+        Addr::unchecked("addr0001")
+    );
+
+    // rematches are private, so they are not listed
+    let filter = PendingBetsFilter {
+        skip: 0,
+        limit: None,
+        exclude_address: None,
+        assets: None,
+        liquidation: None,
+        min_creator_reliability: None,
+        min_creator_games: None,
+        sort_by: PendingBetsSort::Creation { asc: true },
+    };
+    assert_eq!(query_pending_bets(deps.as_ref(), filter).unwrap().len(), 0);
+
+    match respond_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0003", &uusd(1980000u128)),
+        Addr::unchecked("addr0002"),
+        rematch_bet_id.clone(),
+        1,
+        None,
+    )
+    .unwrap_err()
+    {
+        ContractError::RematchIsPrivate {} => {}
+        _ => panic!("no error"),
+    }
+
+    match decline_rematch(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0003", &[]),
+        Addr::unchecked("addr0002"),
+        rematch_bet_id.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::RematchIsNotExpiredYet {} => {}
+        _ => panic!("no error"),
+    }
+
+    let _ = accept_rematch(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &uusd(1980000u128)),
+        Addr::unchecked("addr0002"),
+        rematch_bet_id.clone(),
+        1,
+    )
+    .unwrap();
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, rematch_bet_id).unwrap();
+    assert_eq!(ongoing_bet.bet_creator, Addr::unchecked("addr0002"));
+    assert_eq!(ongoing_bet.bet_responder, Addr::unchecked("addr0001"));
+    assert_eq!(ongoing_bet.asset.amount, Uint128::new(3960000u128));
+}
+
+#[test]
+fn test_rematch_decline() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let res = play_rematch_offered_bet(deps.as_mut(), "0_rematch");
+    let rematch_bet_id = res.attributes[0].value.clone();
+
+    match accept_rematch(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &uusd(1980000u128)),
+        Addr::unchecked("addr0001"),
+        rematch_bet_id.clone(),
+        1,
+    )
+    .unwrap_err()
+    {
+        ContractError::BetWasCancledOrAccepted {} => {}
+        _ => panic!("no error"),
+    }
+
+    match accept_rematch(
+        deps.as_mut(),
+        mock_env_custom(12_546),
+        mock_info("addr0001", &uusd(1980000u128)),
+        Addr::unchecked("addr0002"),
+        rematch_bet_id.clone(),
+        1,
+    )
+    .unwrap_err()
+    {
+        ContractError::RematchExpired {} => {}
+        _ => panic!("no error"),
+    }
+
+    // anyone can decline an expired rematch, winnings are paid out as usual
+    let res = decline_rematch(
+        deps.as_mut(),
+        mock_env_custom(12_545),
+        mock_info("addr0003", &[]),
+        Addr::unchecked("addr0002"),
+        rematch_bet_id.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages.first().expect("no message"),
+        &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0002".to_string(),
+            amount: uusd(1960396u128),
+        }))
+    );
+    assert_eq!(res.attributes[0], attr("action", "decline_rematch"));
+
+    let historical_bet = load_historical_bet(&deps.storage, 1).unwrap();
+    assert_eq!(historical_bet.id, rematch_bet_id);
+    assert_eq!(historical_bet.outcome, GameOutcome::Expired);
+    match query_bet(deps.as_ref(), rematch_bet_id).unwrap() {
+        BetState::Expired(bet) => assert_eq!(bet, historical_bet),
+        _ => panic!("expired rematch must be reported as expired"),
+    }
+    assert_eq!(
+        load_pending_bets_count(&deps.storage).unwrap(),
+        Uint64::zero()
+    );
+}
+
+#[test]
+fn test_referrals() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let response = place_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &uusd(1000000)),
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        Some(Addr::unchecked("addr0005")),
+    )
+    .unwrap();
+    let bet_id = response.attributes.get(2).expect("no bet_id").value.clone();
+
+    match respond_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &uusd(1000000)),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        0,
+        Some(Addr::unchecked("addr0002")),
+    ) {
+        Err(ContractError::ForbiddenToReferYourself {}) => {}
+        _ => panic!("Must return ForbiddenToReferYourself error"),
+    }
+
+    respond_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &uusd(1000000)),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        0,
+        Some(Addr::unchecked("addr0006")),
+    )
+    .unwrap();
+
+    let response = resolve_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    // 1% treasury fee of the pot is split by stakes and each referrer takes 20% of their part
+    assert_eq!(
+        response.messages.get(1).expect("no message"),
+        &SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: uusd(15841),
+        }))
+    );
+
+    let historical_bet = load_historical_bet(&deps.storage, 0).unwrap();
+    let referral_payouts: Vec<&Payout> = historical_bet
+        .payouts
+        .iter()
+        .filter(|p| p.role == PayoutRole::Referrer)
+        .collect();
+    assert_eq!(
+        referral_payouts,
+        vec![
+            &Payout {
+                recipient: "addr0005".to_string(),
+                role: PayoutRole::Referrer,
+                gross_amount: Uint128::new(2000u128),
+                net_amount: Uint128::new(2000u128),
+            },
+            &Payout {
+                recipient: "addr0006".to_string(),
+                role: PayoutRole::Referrer,
+                gross_amount: Uint128::new(2000u128),
+                net_amount: Uint128::new(2000u128),
+            },
+        ]
+    );
+
+    // player stays bound to their first referrer
+    place_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &uusd(1000000)),
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        Some(Addr::unchecked("addr0006")),
+    )
+    .unwrap();
+
+    let referral = query_referral(deps.as_ref(), Addr::unchecked("addr0001")).unwrap();
+    assert_eq!(referral.referrer, Some("addr0005".to_string()));
+
+    let referral = query_referral(deps.as_ref(), Addr::unchecked("addr0006")).unwrap();
+    assert_eq!(referral.referred_players, 1);
+
+    let referral = query_referral(deps.as_ref(), Addr::unchecked("addr0005")).unwrap();
+    assert_eq!(referral.referrer, None);
+    assert_eq!(referral.referred_players, 1);
+    assert_eq!(
+        referral.earnings,
+        vec![Asset {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(2000u128),
+        }]
+    );
+
+    let response = claim_referral_earnings(
+        deps.as_mut(),
+        mock_info("addr0005", &[]),
+        "uusd".to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        response.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0005".to_string(),
+            amount: uusd(1980),
+        }))]
+    );
+
+    let referral = query_referral(deps.as_ref(), Addr::unchecked("addr0005")).unwrap();
+    assert_eq!(referral.earnings, vec![]);
+
+    match claim_referral_earnings(
+        deps.as_mut(),
+        mock_info("addr0005", &[]),
+        "uusd".to_string(),
+    ) {
+        Err(ContractError::NoReferralEarnings {}) => {}
+        _ => panic!("Must return NoReferralEarnings error"),
+    }
+}
+
+fn treasury_payout(storage: &dyn Storage, seq: u64) -> Uint128 {
+    load_historical_bet(storage, seq)
+        .unwrap()
+        .payouts
+        .iter()
+        .find(|p| p.role == PayoutRole::Treasury)
+        .expect("no treasury payout")
+        .gross_amount
+}
+
+#[test]
+fn test_fee_tiers() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let tier_1 = FeeTier {
+        denom: "uusd".to_string(),
+        min_volume: Uint128::new(2000000u128),
+        treasury_tax_percent: 2,
+    };
+    let tier_2 = FeeTier {
+        denom: "uusd".to_string(),
+        min_volume: Uint128::new(4000000u128),
+        treasury_tax_percent: 1,
+    };
+
+    // tiers only discount the flat rate and go up by volume
+    let mut config = load_config(&deps.storage).unwrap();
+    config.fee_tiers = vec![tier_1.clone(), tier_2.clone()];
+    match config.validate() {
+        Err(ContractError::ValidationErr { .. }) => {}
+        _ => panic!("Must return ValidationErr"),
+    }
+
+    config.treasury_tax_percent = 4;
+    config.fee_tiers = vec![tier_2.clone(), tier_1.clone()];
+    match config.validate() {
+        Err(ContractError::ValidationErr { .. }) => {}
+        _ => panic!("Must return ValidationErr"),
+    }
+
+    config.fee_tiers = vec![tier_1.clone(), tier_2.clone()];
+    config.validate().unwrap();
+    store_config(&mut deps.storage, &config).unwrap();
+
+    // winner side volume already counts the bet being resolved, the first one is still below tiers
+    play_resolved_bet(deps.as_mut(), "addr0001", "addr0002", 10000);
+    assert_eq!(treasury_payout(&deps.storage, 0), Uint128::new(80000u128));
+
+    let fee_tier = query_fee_tier(deps.as_ref(), 12345, Addr::unchecked("addr0002")).unwrap();
+    assert_eq!(fee_tier.tiers[0].volume, Uint128::new(1000000u128));
+    assert_eq!(fee_tier.tiers[0].treasury_tax_percent, 4);
+    assert_eq!(fee_tier.tiers[0].tier, None);
+    assert_eq!(fee_tier.tiers[0].next_tier, Some(tier_1.clone()));
+    assert_eq!(
+        fee_tier.tiers[0].volume_to_next_tier,
+        Some(Uint128::new(1000000u128))
+    );
+
+    play_resolved_bet(deps.as_mut(), "addr0001", "addr0002", 10001);
+    assert_eq!(treasury_payout(&deps.storage, 1), Uint128::new(40000u128));
+
+    let fee_tier = query_fee_tier(deps.as_ref(), 12345, Addr::unchecked("addr0002")).unwrap();
+    assert_eq!(fee_tier.tiers[0].treasury_tax_percent, 2);
+    assert_eq!(fee_tier.tiers[0].tier, Some(tier_1.clone()));
+    assert_eq!(fee_tier.tiers[0].next_tier, Some(tier_2.clone()));
+    assert_eq!(
+        fee_tier.tiers[0].volume_to_next_tier,
+        Some(Uint128::new(2000000u128))
+    );
+
+    play_resolved_bet(deps.as_mut(), "addr0001", "addr0002", 10002);
+    assert_eq!(treasury_payout(&deps.storage, 2), Uint128::new(40000u128));
+    play_resolved_bet(deps.as_mut(), "addr0001", "addr0002", 10003);
+    assert_eq!(treasury_payout(&deps.storage, 3), Uint128::new(20000u128));
+
+    let fee_tier = query_fee_tier(deps.as_ref(), 12345, Addr::unchecked("addr0002")).unwrap();
+    assert_eq!(fee_tier.tiers[0].volume, Uint128::new(4000000u128));
+    assert_eq!(fee_tier.tiers[0].treasury_tax_percent, 1);
+    assert_eq!(fee_tier.tiers[0].tier, Some(tier_2));
+    assert_eq!(fee_tier.tiers[0].next_tier, None);
+    assert_eq!(fee_tier.tiers[0].volume_to_next_tier, None);
+
+    // volume leaves the window together with its epoch
+    let fee_tier = query_fee_tier(deps.as_ref(), 212345, Addr::unchecked("addr0002")).unwrap();
+    assert_eq!(fee_tier.tiers[0].volume, Uint128::zero());
+    assert_eq!(fee_tier.tiers[0].treasury_tax_percent, 4);
+
+    // winning pool pays the flat rate even when its first responder has a tier
+    let bet_id = create_pool_bet(deps.as_mut(), 2000000u128);
+    for responder in ["addr0002", "addr0003"] {
+        let _ = fill_pool_bet(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(responder, &uusd(1000000u128)),
+            Addr::unchecked("addr0001"),
+            bet_id.clone(),
+            0,
+        )
+        .unwrap();
+    }
+    let _ = resolve_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        bet_id,
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+    assert_eq!(treasury_payout(&deps.storage, 4), Uint128::new(160000u128));
+}
+
+#[test]
+fn test_jackpot_roll_is_fixed_at_acceptance() {
+    // the same bet accepted in another block or by another responder rolls differently,
+    // so its creator can not know the roll when placing it
+    let mut rolls = vec![];
+    for (responder, height) in [
+        ("addr0002", 12345),
+        ("addr0002", 12346),
+        ("addr0003", 12345),
+    ] {
+        let mut deps = mock_dependencies(&[]);
+        let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+        let bet_id = create_valid_pending_bet(deps.as_mut());
+        let _ = respond_bet(
+            deps.as_mut(),
+            mock_env_custom(height),
+            mock_info(responder, &uusd(1000000)),
+            Addr::unchecked("addr0001"),
+            bet_id.clone(),
+            0,
+            None,
+        )
+        .unwrap();
+
+        let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+        rolls.push(jackpot_roll(MOCK_PASSPHRASE, &bet_id, &ongoing_bet));
+    }
+
+    assert_ne!(rolls[0], rolls[1]);
+    assert_ne!(rolls[0], rolls[2]);
+}
+
+#[test]
+fn test_jackpot() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let mut config = load_config(&deps.storage).unwrap();
+    config.jackpot_percent = 90;
+    match config.validate() {
+        Err(ContractError::ValidationErr { .. }) => {}
+        _ => panic!("Must return ValidationErr"),
+    }
+
+    // half of the treasury fee feeds the jackpot, roll practically never hits
+    config.jackpot_percent = 50;
+    config.jackpot_odds = u64::MAX;
+    store_config(&mut deps.storage, &config).unwrap();
+
+    play_resolved_bet(deps.as_mut(), "addr0001", "addr0002", 10000);
+    assert_eq!(treasury_payout(&deps.storage, 0), Uint128::new(10000u128));
+
+    let jackpot = query_jackpot(deps.as_ref()).unwrap();
+    assert_eq!(
+        jackpot.jackpots,
+        vec![Asset {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(10000u128),
+        }]
+    );
+
+    // every game hits, roll decides which player takes the whole jackpot
+    config.jackpot_odds = 1;
+    store_config(&mut deps.storage, &config).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(10001);
+    let response = place_uusd_bet(deps.as_mut(), "addr0001", 1000000, 200);
+    let bet_id = response.attributes.get(2).expect("no bet_id").value.clone();
+    respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0002", &uusd(1000000)),
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        0,
+        None,
+    )
+    .unwrap();
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    let response = resolve_bet(
+        deps.as_mut(),
+        env,
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    let winner = match jackpot_roll(MOCK_PASSPHRASE, &bet_id, &ongoing_bet) % 2 {
+        0 => "addr0001",
+        _ => "addr0002",
+    };
+    let event = response.events.first().expect("no event");
+    assert_eq!(event.ty, "jackpot");
+    assert_eq!(event.attributes[1], attr("winner", winner));
+    assert_eq!(event.attributes[3], attr("amount", "20000"));
+
+    let historical_bet = load_historical_bet(&deps.storage, 1).unwrap();
+    let jackpot_payout = historical_bet
+        .payouts
+        .iter()
+        .find(|p| p.role == PayoutRole::Jackpot)
+        .expect("no jackpot payout");
+    assert_eq!(jackpot_payout.recipient, winner);
+    assert_eq!(jackpot_payout.gross_amount, Uint128::new(20000u128));
+    assert_eq!(jackpot_payout.net_amount, Uint128::new(19801u128));
+
+    let jackpot = query_jackpot(deps.as_ref()).unwrap();
+    assert_eq!(jackpot.jackpots[0].amount, Uint128::zero());
+
+    let winners = query_jackpot_winners(deps.as_ref(), 0, None).unwrap();
+    assert_eq!(
+        winners.winners,
+        vec![JackpotWinner {
+            bet_id,
+            winner: Addr::unchecked(winner),
+            asset: Asset {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(20000u128),
+            },
+            won_at: 10001,
+        }]
+    );
+}
+
+fn try_place_uusd_bet(deps: DepsMut, env: Env, owner: &str) -> Result<Response, ContractError> {
+    place_bet(
+        deps,
+        env,
+        mock_info(owner, &uusd(1000000)),
+        PlaceBetParams::new(MOCK_SIGNATURE.to_string(), Duration::Height(200)),
+        None,
+    )
+}
+
+fn daily_limit(max_wager: Option<u128>, max_loss: Option<u128>) -> WagerLimit {
+    WagerLimit {
+        denom: "uusd".to_string(),
+        window_days: 1,
+        max_wager: max_wager.map(Uint128::new),
+        max_loss: max_loss.map(Uint128::new),
+    }
+}
+
+#[test]
+fn test_player_limits() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let env = mock_env();
+    let now = env.block.time.seconds();
+    let info = mock_info("addr0001", &[]);
+
+    // new limit is a tightening, so it applies right away
+    set_wager_limit(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        daily_limit(Some(2000000), None),
+    )
+    .unwrap();
+
+    let mut next_block = env.clone();
+    next_block.block.height += 1;
+    try_place_uusd_bet(deps.as_mut(), env.clone(), "addr0001").unwrap();
+    try_place_uusd_bet(deps.as_mut(), next_block, "addr0001").unwrap();
+    match try_place_uusd_bet(deps.as_mut(), env.clone(), "addr0001") {
+        Err(ContractError::WagerLimitExceeded {}) => {}
+        _ => panic!("Must return WagerLimitExceeded error"),
+    }
+
+    // loosening waits for the cooldown
+    let response = set_wager_limit(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        daily_limit(Some(5000000), None),
+    )
+    .unwrap();
+    assert_eq!(
+        response.attributes.last(),
+        Some(&attr("effective_at", (now + 86400).to_string()))
+    );
+    match try_place_uusd_bet(deps.as_mut(), env.clone(), "addr0001") {
+        Err(ContractError::WagerLimitExceeded {}) => {}
+        _ => panic!("Must return WagerLimitExceeded error"),
+    }
+
+    let limits =
+        query_player_limits(deps.as_ref(), env.block.time, Addr::unchecked("addr0001")).unwrap();
+    assert_eq!(limits.limits.len(), 1);
+    assert_eq!(limits.limits[0].limit, daily_limit(Some(2000000), None));
+    assert_eq!(
+        limits.limits[0].usage,
+        LimitUsage {
+            wagered: Uint128::new(2000000u128),
+            lost: Uint128::zero(),
+        }
+    );
+    assert_eq!(
+        limits.pending,
+        vec![PendingWagerLimit {
+            limit: daily_limit(Some(5000000), None),
+            effective_at: now + 86400,
+        }]
+    );
+
+    let mut next_day = env.clone();
+    next_day.block.time = env.block.time.plus_seconds(86400);
+    try_place_uusd_bet(deps.as_mut(), next_day.clone(), "addr0001").unwrap();
+
+    let limits = query_player_limits(
+        deps.as_ref(),
+        next_day.block.time,
+        Addr::unchecked("addr0001"),
+    )
+    .unwrap();
+    assert_eq!(limits.limits[0].limit, daily_limit(Some(5000000), None));
+    assert_eq!(limits.limits[0].usage.wagered, Uint128::new(1000000u128));
+    assert_eq!(limits.pending, vec![]);
+
+    // loss limit assumes the new stake is lost as well
+    play_resolved_bet(deps.as_mut(), "addr0003", "addr0004", now);
+    set_wager_limit(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0003", &[]),
+        daily_limit(None, Some(1500000)),
+    )
+    .unwrap();
+    match try_place_uusd_bet(deps.as_mut(), env.clone(), "addr0003") {
+        Err(ContractError::LossLimitExceeded {}) => {}
+        _ => panic!("Must return LossLimitExceeded error"),
+    }
+
+    let limits =
+        query_player_limits(deps.as_ref(), env.block.time, Addr::unchecked("addr0003")).unwrap();
+    assert_eq!(limits.limits[0].usage.lost, Uint128::new(1000000u128));
+
+    // self-exclusion blocks both placing and responding and can not be shortened
+    let info = mock_info("addr0005", &[]);
+    self_exclude(deps.as_mut(), env.clone(), info.clone(), now + 1000).unwrap();
+    match self_exclude(deps.as_mut(), env.clone(), info.clone(), now + 500) {
+        Err(ContractError::SelfExclusionCanNotBeShortened {}) => {}
+        _ => panic!("Must return SelfExclusionCanNotBeShortened error"),
+    }
+
+    match try_place_uusd_bet(deps.as_mut(), env.clone(), "addr0005") {
+        Err(ContractError::PlayerIsSelfExcluded {}) => {}
+        _ => panic!("Must return PlayerIsSelfExcluded error"),
+    }
+
+    let response = try_place_uusd_bet(deps.as_mut(), env.clone(), "addr0006").unwrap();
+    let bet_id = response.attributes.get(2).expect("no bet_id").value.clone();
+    match respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0005", &uusd(1000000)),
+        Addr::unchecked("addr0006"),
+        bet_id.clone(),
+        0,
+        None,
+    ) {
+        Err(ContractError::PlayerIsSelfExcluded {}) => {}
+        _ => panic!("Must return PlayerIsSelfExcluded error"),
+    }
+
+    let mut later = env.clone();
+    later.block.time = env.block.time.plus_seconds(1000);
+    respond_bet(
+        deps.as_mut(),
+        later,
+        mock_info("addr0005", &uusd(1000000)),
+        Addr::unchecked("addr0006"),
+        bet_id,
+        0,
+        None,
+    )
+    .unwrap();
 }
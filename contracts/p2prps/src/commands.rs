@@ -0,0 +1,521 @@
+use cosmwasm_std::{
+    Addr, CosmosMsg, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdResult, Storage,
+    Uint64,
+};
+
+use cw0::Duration;
+
+use crate::{
+    error::ContractError,
+    msg::UpdateConfigMsg,
+    state::{
+        load_config, load_ongoing_bet, load_pending_bets, load_pending_bets_count,
+        remove_ongoing_bet, store_config, store_ongoing_bet, store_pending_bets,
+        store_pending_bets_count, Config, GameOutcome, Hand, HistoricalBet, OngoingBet,
+    },
+};
+
+use tefiluck::{
+    asset::Asset,
+    hash::calculate_sha256,
+    history,
+    payout::{send_payout, Payout, PayoutRole},
+};
+
+pub fn place_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    signature: String,
+    until_liquidation: Duration,
+) -> Result<Response, ContractError> {
+    let config = load_config(deps.storage)?;
+    let mut pending_bets = load_pending_bets(deps.storage, &info.sender)?;
+
+    let bet_id = calculate_sha256(&format!(
+        "{}{}{}",
+        env.block.height, env.block.time, &signature
+    ));
+    let asset = Asset::from_coins(info.funds)?;
+
+    config.validate_place_bet_inputs(&until_liquidation, pending_bets.bets.len(), &asset)?;
+
+    pending_bets.store_bet(
+        deps.api.addr_canonicalize(info.sender.as_str())?,
+        bet_id.clone(),
+        signature.clone(),
+        until_liquidation,
+        asset.clone(),
+        env.block.time,
+    )?;
+
+    store_pending_bets(deps.storage, &info.sender, &pending_bets)?;
+
+    let current_bets_count = load_pending_bets_count(deps.storage)?;
+    let bets_count = current_bets_count.checked_add(Uint64::new(1u64))?;
+    store_pending_bets_count(deps.storage, bets_count)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "place_bet"),
+        ("sender", info.sender.as_str()),
+        ("bet_id", &bet_id),
+        ("signature", &signature),
+        ("until_liquidation", &until_liquidation.to_string()),
+        ("denom", &asset.denom),
+        ("amount", &asset.amount.to_string()),
+        ("created_at", &env.block.time.seconds().to_string()),
+    ]))
+}
+
+pub fn respond_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_owner: Addr,
+    bet_id: String,
+    signature: String,
+) -> Result<Response, ContractError> {
+    if info.sender == bet_owner {
+        return Err(ContractError::ForbiddenToPlayVSYourself {});
+    }
+
+    let config = load_config(deps.storage)?;
+    let mut pending_bets = load_pending_bets(deps.storage, &bet_owner)?;
+    let pending_bet = match pending_bets.find_by_id(&bet_id) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::BetWasCancledOrAccepted {}),
+    };
+
+    let asset = Asset::from_coins(info.funds)?;
+    if asset.ne(&pending_bet.asset) {
+        return Err(ContractError::ResponderAssetMismatch {});
+    }
+
+    // reusing creator commitment would let responder reveal the same hand and force a draw
+    if signature.eq(&pending_bet.signature) {
+        return Err(ContractError::SignatureMismatch {});
+    }
+
+    let ongoing_bet = OngoingBet::new(
+        &pending_bet,
+        bet_owner.clone(),
+        info.sender.clone(),
+        signature,
+        config.revealer_liquidation_gap(&pending_bet.until_liquidation),
+        &env.block,
+    )?;
+
+    pending_bets.remove_bet(&bet_id);
+    store_pending_bets(deps.storage, &bet_owner, &pending_bets)?;
+    store_ongoing_bet(deps.storage, bet_id.clone(), &ongoing_bet)?;
+
+    let current_bets_count = load_pending_bets_count(deps.storage)?;
+    let bets_count = current_bets_count.checked_sub(Uint64::new(1u64))?;
+    store_pending_bets_count(deps.storage, bets_count)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "respond_bet"),
+        ("bet_id", &bet_id),
+        ("creator_signature", &ongoing_bet.creator_signature),
+        ("responder_signature", &ongoing_bet.responder_signature),
+        ("bet_creator", ongoing_bet.bet_creator.as_str()),
+        ("bet_responder", ongoing_bet.bet_responder.as_str()),
+        ("denom", &ongoing_bet.asset.denom),
+        ("amount", &ongoing_bet.asset.amount.to_string()),
+        (
+            "started_at_block",
+            &ongoing_bet.started_at_block.to_string(),
+        ),
+        (
+            "until_liquidation",
+            &ongoing_bet.until_liquidation.to_string(),
+        ),
+        ("liquidation", &ongoing_bet.liquidation.to_string()),
+        (
+            "revealer_liquidation",
+            &ongoing_bet.revealer_liquidation.to_string(),
+        ),
+        ("created_at", &ongoing_bet.created_at.seconds().to_string()),
+    ]))
+}
+
+// each player reveals their own hand, the game settles with the second reveal
+pub fn reveal_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_id: String,
+    passphrase: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let config = load_config(deps.storage)?;
+    let mut ongoing_bet = match load_ongoing_bet(deps.storage, bet_id.clone()) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::GameWasAlreadyLiquidated {}),
+    };
+
+    ongoing_bet.reveal(&info.sender, passphrase)?;
+
+    if !ongoing_bet.is_revealed() {
+        store_ongoing_bet(deps.storage, bet_id.clone(), &ongoing_bet)?;
+
+        return Ok(Response::new().add_attributes(vec![
+            ("action", "reveal_bet"),
+            ("bet_id", &bet_id),
+            ("player", info.sender.as_str()),
+        ]));
+    }
+
+    let winner = ongoing_bet.resolve_winner();
+    let treasury = deps.api.addr_humanize(&config.treasury)?;
+    let (messages, payouts, outcome) = match &winner {
+        Some(winner) => {
+            let mut winner_amount = ongoing_bet.asset.clone();
+            let treasury_amount = winner_amount.take_percent(config.treasury_tax_percent)?;
+            winner_amount.checked_sub(&treasury_amount)?;
+
+            let mut messages = vec![];
+            let mut payouts = vec![];
+            for (recipient, role, amount) in [
+                (winner, PayoutRole::Winner, winner_amount),
+                (&treasury, PayoutRole::Treasury, treasury_amount),
+            ] {
+                if amount.amount.is_zero() {
+                    continue;
+                }
+
+                let (msg, payout) = send_payout(&deps.querier, recipient, role, &amount)?;
+                messages.push(msg);
+                payouts.push(payout);
+            }
+
+            (messages, payouts, GameOutcome::Resolved)
+        }
+        None => {
+            let (messages, payouts) =
+                refund_stakes(&deps.querier, &config, &treasury, &ongoing_bet)?;
+            (messages, payouts, GameOutcome::Draw)
+        }
+    };
+
+    remove_ongoing_bet(deps.storage, bet_id.clone());
+
+    let historical_bet = HistoricalBet::new(
+        bet_id.clone(),
+        &ongoing_bet,
+        winner,
+        None,
+        payouts,
+        outcome,
+        env.block.time.seconds(),
+    );
+    save_historical_bet(deps.storage, &config, &historical_bet)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "resolve_bet"),
+        ("bet_id", &bet_id),
+        ("owner", &historical_bet.owner),
+        ("responder", ongoing_bet.bet_responder.as_str()),
+        ("winner", &historical_bet.winner.clone().unwrap_or_default()),
+        ("creator_hand", &hand_attr(&historical_bet.creator_hand)),
+        ("responder_hand", &hand_attr(&historical_bet.responder_hand)),
+        ("denom", &historical_bet.asset.denom),
+        ("amount", &historical_bet.asset.amount.to_string()),
+        ("outcome", &historical_bet.outcome.to_string()),
+        ("created_at", &historical_bet.created_at.to_string()),
+        ("completed_at", &historical_bet.completed_at.to_string()),
+    ]))
+}
+
+pub fn liquidate_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_id: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let config = load_config(deps.storage)?;
+    let ongoing_bet = match load_ongoing_bet(deps.storage, bet_id.clone()) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::GameWasAlreadyResolved {}),
+    };
+
+    if !ongoing_bet.liquidation.is_expired(&env.block) {
+        return Err(ContractError::BetIsNotLiquidatableYet {});
+    }
+
+    let treasury = deps.api.addr_humanize(&config.treasury)?;
+    let (messages, payouts, winner, outcome) = match ongoing_bet.liquidation_parties() {
+        Some((wronged, at_fault)) => {
+            if info.sender.eq(&at_fault) {
+                return Err(ContractError::ForbiddenForUnrevealedPlayerToLiquidate {});
+            }
+
+            if info.sender.ne(&wronged) && !ongoing_bet.revealer_liquidation.is_expired(&env.block)
+            {
+                return Err(ContractError::RevealerLiquidationGapIsNotPassedYet {});
+            }
+
+            let mut bet_amount = ongoing_bet.asset.clone();
+            let wronged_amount =
+                bet_amount.take_percent(config.bet_revealer_liquidation_percent)?;
+            let liquidator_amount = bet_amount.take_percent(config.bet_liquidator_percent)?;
+            let treasury_amount = bet_amount
+                .checked_sub(&wronged_amount)?
+                .checked_sub(&liquidator_amount)?
+                .clone();
+
+            let mut messages = vec![];
+            let mut payouts = vec![];
+            for (recipient, role, amount) in [
+                (&wronged, PayoutRole::Winner, wronged_amount),
+                (&info.sender, PayoutRole::Liquidator, liquidator_amount),
+                (&treasury, PayoutRole::Treasury, treasury_amount),
+            ] {
+                if amount.amount.is_zero() {
+                    continue;
+                }
+
+                let (msg, payout) = send_payout(&deps.querier, recipient, role, &amount)?;
+                messages.push(msg);
+                payouts.push(payout);
+            }
+
+            (messages, payouts, Some(wronged), GameOutcome::Liquidated)
+        }
+        // nobody revealed, so nobody is wronged and both stakes go back
+        None => {
+            let (messages, payouts) =
+                refund_stakes(&deps.querier, &config, &treasury, &ongoing_bet)?;
+            (messages, payouts, None, GameOutcome::Refunded)
+        }
+    };
+
+    remove_ongoing_bet(deps.storage, bet_id.clone());
+
+    let historical_bet = HistoricalBet::new(
+        bet_id.clone(),
+        &ongoing_bet,
+        winner,
+        Some(info.sender.clone()),
+        payouts,
+        outcome,
+        env.block.time.seconds(),
+    );
+    save_historical_bet(deps.storage, &config, &historical_bet)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "liquidate_bet"),
+        ("bet_id", &bet_id),
+        ("owner", &historical_bet.owner),
+        ("responder", ongoing_bet.bet_responder.as_str()),
+        ("winner", &historical_bet.winner.clone().unwrap_or_default()),
+        ("liquidator", info.sender.as_str()),
+        ("creator_hand", &hand_attr(&historical_bet.creator_hand)),
+        ("responder_hand", &hand_attr(&historical_bet.responder_hand)),
+        ("denom", &historical_bet.asset.denom),
+        ("amount", &historical_bet.asset.amount.to_string()),
+        ("outcome", &historical_bet.outcome.to_string()),
+        ("created_at", &historical_bet.created_at.to_string()),
+        ("completed_at", &historical_bet.completed_at.to_string()),
+    ]))
+}
+
+pub fn withdraw_pending_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bet_id: String,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::ExecuteWithoutFunds {});
+    }
+
+    let config = load_config(deps.storage)?;
+    let mut pending_bets = load_pending_bets(deps.storage, &info.sender)?;
+    let pending_bet = match pending_bets.find_by_id(&bet_id) {
+        Ok(b) => b,
+        Err(_) => return Err(ContractError::GameWasAlreadyAccepted {}),
+    };
+
+    let (send_msg, payout) = send_payout(
+        &deps.querier,
+        &info.sender,
+        PayoutRole::Owner,
+        &pending_bet.asset,
+    )?;
+
+    pending_bets.remove_bet(&bet_id);
+    store_pending_bets(deps.storage, &info.sender, &pending_bets)?;
+
+    let current_bets_count = load_pending_bets_count(deps.storage)?;
+    let bets_count = current_bets_count.checked_sub(Uint64::new(1u64))?;
+    store_pending_bets_count(deps.storage, bets_count)?;
+
+    let historical_bet = HistoricalBet::withdrawn(
+        &info.sender,
+        &pending_bet,
+        vec![payout],
+        env.block.time.seconds(),
+    );
+    save_historical_bet(deps.storage, &config, &historical_bet)?;
+
+    Ok(Response::new().add_message(send_msg).add_attributes(vec![
+        ("action", "withdraw_pending_bet"),
+        ("bet_id", &bet_id),
+        ("denom", &historical_bet.asset.denom),
+        ("amount", &historical_bet.asset.amount.to_string()),
+        ("outcome", &historical_bet.outcome.to_string()),
+        ("created_at", &historical_bet.created_at.to_string()),
+        ("completed_at", &historical_bet.completed_at.to_string()),
+    ]))
+}
+
+// only owner allowed to change config params
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: UpdateConfigMsg,
+) -> Result<Response, ContractError> {
+    let mut config = load_config(deps.storage)?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = msg.owner {
+        deps.api.addr_validate(&owner)?;
+        config.owner = deps.api.addr_canonicalize(&owner)?;
+    }
+
+    if let Some(treasury) = msg.treasury {
+        deps.api.addr_validate(&treasury)?;
+        config.treasury = deps.api.addr_canonicalize(&treasury)?;
+    }
+
+    if let Some(treasury_tax_percent) = msg.treasury_tax_percent {
+        config.treasury_tax_percent = treasury_tax_percent;
+    }
+
+    if let Some(max_bets_by_addr) = msg.max_bets_by_addr {
+        config.max_bets_by_addr = max_bets_by_addr;
+    }
+
+    if let Some(min_bet_amounts) = msg.min_bet_amounts {
+        config.min_bet_amounts = min_bet_amounts;
+    }
+
+    if let Some(min_blocks_until_liquidation) = msg.min_blocks_until_liquidation {
+        config.min_blocks_until_liquidation = min_blocks_until_liquidation;
+    }
+
+    if let Some(max_blocks_until_liquidation) = msg.max_blocks_until_liquidation {
+        config.max_blocks_until_liquidation = max_blocks_until_liquidation;
+    }
+
+    if let Some(min_seconds_until_liquidation) = msg.min_seconds_until_liquidation {
+        config.min_seconds_until_liquidation = min_seconds_until_liquidation;
+    }
+
+    if let Some(max_seconds_until_liquidation) = msg.max_seconds_until_liquidation {
+        config.max_seconds_until_liquidation = max_seconds_until_liquidation;
+    }
+
+    if let Some(blocks_for_revealer_liquidation) = msg.blocks_for_revealer_liquidation {
+        config.blocks_for_revealer_liquidation = blocks_for_revealer_liquidation;
+    }
+
+    if let Some(seconds_for_revealer_liquidation) = msg.seconds_for_revealer_liquidation {
+        config.seconds_for_revealer_liquidation = seconds_for_revealer_liquidation;
+    }
+
+    if let Some(bet_revealer_liquidation_percent) = msg.bet_revealer_liquidation_percent {
+        config.bet_revealer_liquidation_percent = bet_revealer_liquidation_percent;
+    }
+
+    if let Some(bet_liquidator_percent) = msg.bet_liquidator_percent {
+        config.bet_liquidator_percent = bet_liquidator_percent;
+    }
+
+    if let Some(treasury_liquidation_percent) = msg.treasury_liquidation_percent {
+        config.treasury_liquidation_percent = treasury_liquidation_percent;
+    }
+
+    if let Some(historical_bets_max_storage_size) = msg.historical_bets_max_storage_size {
+        config.historical_bets_max_storage_size = historical_bets_max_storage_size;
+    }
+
+    if let Some(historical_bets_clear_batch_size) = msg.historical_bets_clear_batch_size {
+        config.historical_bets_clear_batch_size = historical_bets_clear_batch_size;
+    }
+
+    config.validate()?;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn save_historical_bet(
+    storage: &mut dyn Storage,
+    config: &Config,
+    bet: &HistoricalBet,
+) -> StdResult<()> {
+    history::save_historical_bet(
+        storage,
+        config.historical_bets_max_storage_size,
+        config.historical_bets_clear_batch_size,
+        bet,
+    )?;
+
+    Ok(())
+}
+
+// returns both stakes to the players, treasury tax is taken from each of them
+fn refund_stakes(
+    querier: &QuerierWrapper,
+    config: &Config,
+    treasury: &Addr,
+    bet: &OngoingBet,
+) -> StdResult<(Vec<CosmosMsg>, Vec<Payout>)> {
+    let stake = bet.stake();
+    let stake_tax = stake.take_percent(config.treasury_tax_percent)?;
+
+    let mut refund_amount = stake.clone();
+    refund_amount.checked_sub(&stake_tax)?;
+
+    let mut treasury_amount = bet.asset.clone();
+    treasury_amount
+        .checked_sub(&refund_amount)?
+        .checked_sub(&refund_amount)?;
+
+    let mut messages = vec![];
+    let mut payouts = vec![];
+    for (recipient, role, amount) in [
+        (&bet.bet_creator, PayoutRole::Owner, refund_amount.clone()),
+        (&bet.bet_responder, PayoutRole::Responder, refund_amount),
+        (treasury, PayoutRole::Treasury, treasury_amount),
+    ] {
+        if amount.amount.is_zero() {
+            continue;
+        }
+
+        let (msg, payout) = send_payout(querier, recipient, role, &amount)?;
+        messages.push(msg);
+        payouts.push(payout);
+    }
+
+    Ok((messages, payouts))
+}
+
+fn hand_attr(hand: &Option<Hand>) -> String {
+    match hand {
+        Some(hand) => hand.u8().to_string(),
+        None => "none".to_string(),
+    }
+}
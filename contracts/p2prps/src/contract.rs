@@ -0,0 +1,148 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint64,
+};
+use cw2::set_contract_version;
+use tefiluck::config::CoinLimit;
+
+use crate::{
+    commands,
+    error::ContractError,
+    msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
+    queries,
+    state::{store_config, store_pending_bets_count, Config},
+};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:p2prps";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let min_bet_amounts: Vec<CoinLimit> = msg
+        .min_bet_amounts
+        .into_iter()
+        .map(|coin| coin.into())
+        .collect();
+
+    let config = Config {
+        owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+        treasury: deps.api.addr_canonicalize(&msg.treasury)?,
+        treasury_tax_percent: msg.treasury_tax_percent,
+        max_bets_by_addr: msg.max_bets_by_addr,
+        min_bet_amounts,
+        min_blocks_until_liquidation: msg.min_blocks_until_liquidation,
+        max_blocks_until_liquidation: msg.max_blocks_until_liquidation,
+        min_seconds_until_liquidation: msg.min_seconds_until_liquidation,
+        max_seconds_until_liquidation: msg.max_seconds_until_liquidation,
+        blocks_for_revealer_liquidation: msg.blocks_for_revealer_liquidation,
+        seconds_for_revealer_liquidation: msg.seconds_for_revealer_liquidation,
+        bet_revealer_liquidation_percent: msg.bet_revealer_liquidation_percent,
+        bet_liquidator_percent: msg.bet_liquidator_percent,
+        treasury_liquidation_percent: msg.treasury_liquidation_percent,
+        historical_bets_max_storage_size: msg.historical_bets_max_storage_size,
+        historical_bets_clear_batch_size: msg.historical_bets_clear_batch_size,
+    };
+
+    config.validate()?;
+    store_config(deps.storage, &config)?;
+
+    store_pending_bets_count(deps.storage, Uint64::new(0u64))?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::PlaceBet {
+            signature,
+            until_liquidation,
+        } => commands::place_bet(deps, env, info, signature.to_lowercase(), until_liquidation),
+        ExecuteMsg::RespondBet {
+            bet_owner,
+            bet_id,
+            signature,
+        } => {
+            let bet_owner = deps.api.addr_validate(&bet_owner)?;
+            commands::respond_bet(
+                deps,
+                env,
+                info,
+                bet_owner,
+                bet_id.to_lowercase(),
+                signature.to_lowercase(),
+            )
+        }
+        ExecuteMsg::RevealBet { bet_id, passphrase } => {
+            commands::reveal_bet(deps, env, info, bet_id.to_lowercase(), passphrase)
+        }
+        ExecuteMsg::LiquidateBet { bet_id } => {
+            commands::liquidate_bet(deps, env, info, bet_id.to_lowercase())
+        }
+        ExecuteMsg::WithdrawPendingBet { bet_id } => {
+            commands::withdraw_pending_bet(deps, env, info, bet_id.to_lowercase())
+        }
+        ExecuteMsg::UpdateConfig(msg) => commands::update_config(deps, info, msg),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&queries::query_config(deps)?),
+        QueryMsg::PendingBetsByAddr { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(&queries::query_pending_bets_by_addr(deps, address)?)
+        }
+        QueryMsg::PendingBetById { address, bet_id } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(&queries::query_pending_bet_by_id(
+                deps,
+                address,
+                bet_id.to_lowercase(),
+            )?)
+        }
+        QueryMsg::PendingBets { filter } => to_binary(&queries::query_pending_bets(deps, filter)?),
+        QueryMsg::PendingBetsCount {} => to_binary(&queries::query_pending_bets_count(deps)?),
+        QueryMsg::OngoingBet { bet_id } => {
+            to_binary(&queries::query_ongoing_bet(deps, bet_id.to_lowercase())?)
+        }
+        QueryMsg::OngoingBetsByAddr { address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_binary(&queries::query_ongoing_bets_by_addr(deps, addr)?)
+        }
+        QueryMsg::PublicLiquidatable {
+            skip,
+            limit,
+            exclude_address,
+        } => to_binary(&queries::query_public_liquidatable_bets(
+            deps,
+            &env.block,
+            skip,
+            limit,
+            exclude_address,
+        )?),
+        QueryMsg::HistoricalBets {
+            skip,
+            limit,
+            address,
+        } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_binary(&queries::query_historical_bets(deps, skip, limit, addr)?)
+        }
+    }
+}
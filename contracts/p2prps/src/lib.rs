@@ -0,0 +1,11 @@
+pub mod commands;
+pub mod contract;
+mod error;
+pub mod msg;
+pub mod queries;
+pub mod state;
+
+pub use crate::error::ContractError;
+
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::Uint128;
+use cw0::{Duration, Expiration};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tefiluck::{
+    asset::Asset,
+    config::{CoinLimit, InstantiateCoinLimitMsg},
+};
+
+use crate::state::{HistoricalBet, OngoingBet, PendingBet};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub treasury: String,
+    pub treasury_tax_percent: u8,
+    pub max_bets_by_addr: u64,
+    pub min_bet_amounts: Vec<InstantiateCoinLimitMsg>,
+    pub min_blocks_until_liquidation: u64,
+    pub max_blocks_until_liquidation: u64,
+    pub min_seconds_until_liquidation: u64,
+    pub max_seconds_until_liquidation: u64,
+    pub blocks_for_revealer_liquidation: u64,
+    pub seconds_for_revealer_liquidation: u64,
+    pub bet_revealer_liquidation_percent: u8,
+    pub bet_liquidator_percent: u8,
+    pub treasury_liquidation_percent: u8,
+    pub historical_bets_max_storage_size: u64,
+    pub historical_bets_clear_batch_size: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    PlaceBet {
+        signature: String,
+        until_liquidation: Duration,
+    },
+    RespondBet {
+        bet_owner: String,
+        bet_id: String,
+        signature: String,
+    },
+    RevealBet {
+        bet_id: String,
+        passphrase: String,
+    },
+    LiquidateBet {
+        bet_id: String,
+    },
+    WithdrawPendingBet {
+        bet_id: String,
+    },
+    UpdateConfig(UpdateConfigMsg),
+}
+
+// every field left empty keeps its current config value
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UpdateConfigMsg {
+    pub owner: Option<String>,
+    pub treasury: Option<String>,
+    pub treasury_tax_percent: Option<u8>,
+    pub max_bets_by_addr: Option<u64>,
+    pub min_bet_amounts: Option<Vec<CoinLimit>>,
+    pub min_blocks_until_liquidation: Option<u64>,
+    pub max_blocks_until_liquidation: Option<u64>,
+    pub min_seconds_until_liquidation: Option<u64>,
+    pub max_seconds_until_liquidation: Option<u64>,
+    pub blocks_for_revealer_liquidation: Option<u64>,
+    pub seconds_for_revealer_liquidation: Option<u64>,
+    pub bet_revealer_liquidation_percent: Option<u8>,
+    pub bet_liquidator_percent: Option<u8>,
+    pub treasury_liquidation_percent: Option<u8>,
+    pub historical_bets_max_storage_size: Option<u64>,
+    pub historical_bets_clear_batch_size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    PendingBetsByAddr {
+        address: String,
+    },
+    PendingBetById {
+        address: String,
+        bet_id: String,
+    },
+    PendingBets {
+        filter: PendingBetsFilter,
+    },
+    PendingBetsCount {},
+    OngoingBet {
+        bet_id: String,
+    },
+    OngoingBetsByAddr {
+        address: String,
+    },
+    PublicLiquidatable {
+        skip: u32,
+        limit: Option<u32>,
+        exclude_address: Option<String>,
+    },
+    HistoricalBets {
+        skip: u32,
+        limit: Option<u32>,
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub treasury: String,
+    pub treasury_tax_percent: u8,
+    pub max_bets_by_addr: u64,
+    pub min_bet_amounts: Vec<CoinLimit>,
+    pub min_blocks_until_liquidation: u64,
+    pub max_blocks_until_liquidation: u64,
+    pub min_seconds_until_liquidation: u64,
+    pub max_seconds_until_liquidation: u64,
+    pub blocks_for_revealer_liquidation: u64,
+    pub seconds_for_revealer_liquidation: u64,
+    pub bet_revealer_liquidation_percent: u8,
+    pub bet_liquidator_percent: u8,
+    pub treasury_liquidation_percent: u8,
+    pub historical_bets_max_storage_size: u64,
+    pub historical_bets_clear_batch_size: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingBetsFilter {
+    pub skip: u32,
+    pub limit: Option<u32>,
+    pub exclude_address: Option<String>,
+    pub assets: Option<Vec<AssetFilter>>,
+    pub liquidation: Option<LiquidationFilter>,
+    pub sort_by: PendingBetsSort,
+}
+
+impl PendingBetsFilter {
+    pub fn to_asset_map(&self) -> HashMap<String, (Option<Uint128>, Option<Uint128>)> {
+        match &self.assets {
+            Some(assets) => assets
+                .iter()
+                .map(|asset_filter| {
+                    (
+                        asset_filter.denom.clone(),
+                        (asset_filter.bet_size_from, asset_filter.bet_size_to),
+                    )
+                })
+                .collect(),
+            None => HashMap::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetFilter {
+    pub denom: String,
+    pub bet_size_from: Option<Uint128>,
+    pub bet_size_to: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidationFilter {
+    pub blocks_until_liquidation_from: Option<u64>,
+    pub blocks_until_liquidation_to: Option<u64>,
+    pub seconds_until_liquidation_from: Option<u64>,
+    pub seconds_until_liquidation_to: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingBetsSort {
+    Creation { asc: bool },
+    Price { asc: bool },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AddrPendingBetsResponse {
+    pub bets: Vec<PendingBetResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingBetResponse {
+    pub owner: String,
+    pub id: String,
+    pub signature: String,
+    pub until_liquidation: Duration,
+    pub asset: Asset,
+    pub created_at: u64,
+}
+
+impl PendingBetResponse {
+    pub fn new(owner: String, bet: &PendingBet) -> Self {
+        Self {
+            owner,
+            id: bet.id.clone(),
+            signature: bet.signature.clone(),
+            until_liquidation: bet.until_liquidation,
+            asset: bet.asset.clone(),
+            created_at: bet.created_at.seconds(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OngoingBetResponse {
+    pub id: String,
+    pub owner: String,
+    pub responder: String,
+    pub creator_signature: String,
+    pub responder_signature: String,
+    pub creator_revealed: bool,
+    pub responder_revealed: bool,
+    pub asset: Asset,
+    pub started_at_block: u64,
+    pub until_liquidation: Duration,
+    pub liquidation: Expiration,
+    pub revealer_liquidation: Expiration,
+    pub created_at: u64,
+}
+
+impl OngoingBetResponse {
+    pub fn new(bet_id: String, bet: &OngoingBet) -> Self {
+        Self {
+            id: bet_id,
+            owner: bet.bet_creator.to_string(),
+            responder: bet.bet_responder.to_string(),
+            creator_signature: bet.creator_signature.clone(),
+            responder_signature: bet.responder_signature.clone(),
+            creator_revealed: bet.creator_passphrase.is_some(),
+            responder_revealed: bet.responder_passphrase.is_some(),
+            asset: bet.asset.clone(),
+            started_at_block: bet.started_at_block,
+            until_liquidation: bet.until_liquidation,
+            liquidation: bet.liquidation,
+            revealer_liquidation: bet.revealer_liquidation,
+            created_at: bet.created_at.seconds(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoricalBetResponse {
+    pub history: Vec<HistoricalBet>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalPendingBetsResponse {
+    pub count: u64,
+}
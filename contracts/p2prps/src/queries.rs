@@ -0,0 +1,123 @@
+use cosmwasm_std::{Addr, BlockInfo, Deps, StdResult};
+
+use crate::{
+    msg::{
+        AddrPendingBetsResponse, ConfigResponse, HistoricalBetResponse, OngoingBetResponse,
+        PendingBetResponse, PendingBetsFilter, TotalPendingBetsResponse,
+    },
+    state::{
+        load_config, load_ongoing_bet, load_pending_bets, load_pending_bets_count,
+        read_historical_bets_by_addr, read_ongoing_bets_by_addr, read_pending_bets,
+        read_public_liquidatable_bets,
+    },
+};
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = load_config(deps.storage)?;
+    let response = ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        treasury: deps.api.addr_humanize(&config.treasury)?.to_string(),
+        treasury_tax_percent: config.treasury_tax_percent,
+        max_bets_by_addr: config.max_bets_by_addr,
+        min_bet_amounts: config.min_bet_amounts,
+        min_blocks_until_liquidation: config.min_blocks_until_liquidation,
+        max_blocks_until_liquidation: config.max_blocks_until_liquidation,
+        min_seconds_until_liquidation: config.min_seconds_until_liquidation,
+        max_seconds_until_liquidation: config.max_seconds_until_liquidation,
+        blocks_for_revealer_liquidation: config.blocks_for_revealer_liquidation,
+        seconds_for_revealer_liquidation: config.seconds_for_revealer_liquidation,
+        bet_revealer_liquidation_percent: config.bet_revealer_liquidation_percent,
+        bet_liquidator_percent: config.bet_liquidator_percent,
+        treasury_liquidation_percent: config.treasury_liquidation_percent,
+        historical_bets_max_storage_size: config.historical_bets_max_storage_size,
+        historical_bets_clear_batch_size: config.historical_bets_clear_batch_size,
+    };
+
+    Ok(response)
+}
+
+pub fn query_pending_bets_by_addr(deps: Deps, addr: Addr) -> StdResult<AddrPendingBetsResponse> {
+    let bets = load_pending_bets(deps.storage, &addr)?;
+    let resp = bets
+        .bets
+        .iter()
+        .map(|bet| PendingBetResponse::new(addr.to_string(), bet))
+        .collect();
+
+    Ok(AddrPendingBetsResponse { bets: resp })
+}
+
+pub fn query_pending_bet_by_id(
+    deps: Deps,
+    addr: Addr,
+    bet_id: String,
+) -> StdResult<PendingBetResponse> {
+    let bets = load_pending_bets(deps.storage, &addr)?;
+    let bet = bets.find_by_id(&bet_id)?;
+
+    Ok(PendingBetResponse::new(addr.to_string(), &bet))
+}
+
+pub fn query_pending_bets(
+    deps: Deps,
+    filter: PendingBetsFilter,
+) -> StdResult<Vec<PendingBetResponse>> {
+    let bets = read_pending_bets(deps.storage, deps.api, &filter)?;
+    bets.iter()
+        .map(|bet| {
+            Ok(PendingBetResponse::new(
+                deps.api.addr_humanize(&bet.owner)?.to_string(),
+                bet,
+            ))
+        })
+        .collect()
+}
+
+pub fn query_pending_bets_count(deps: Deps) -> StdResult<TotalPendingBetsResponse> {
+    let bets_count = load_pending_bets_count(deps.storage)?;
+    Ok(TotalPendingBetsResponse {
+        count: bets_count.u64(),
+    })
+}
+
+pub fn query_ongoing_bet(deps: Deps, bet_id: String) -> StdResult<OngoingBetResponse> {
+    let bet = load_ongoing_bet(deps.storage, bet_id.clone())?;
+    Ok(OngoingBetResponse::new(bet_id, &bet))
+}
+
+pub fn query_ongoing_bets_by_addr(deps: Deps, addr: Addr) -> StdResult<Vec<OngoingBetResponse>> {
+    let bets = read_ongoing_bets_by_addr(deps.storage, &addr)?;
+    Ok(bets
+        .into_iter()
+        .map(|(bet_id, bet)| OngoingBetResponse::new(bet_id, &bet))
+        .collect())
+}
+
+pub fn query_public_liquidatable_bets(
+    deps: Deps,
+    block: &BlockInfo,
+    skip: u32,
+    limit: Option<u32>,
+    exclude_addr: Option<String>,
+) -> StdResult<Vec<OngoingBetResponse>> {
+    let exclude_addr = match exclude_addr {
+        Some(exclude_addr) => Some(deps.api.addr_validate(&exclude_addr)?),
+        None => None,
+    };
+
+    let bets = read_public_liquidatable_bets(deps.storage, block, skip, limit, exclude_addr)?;
+    Ok(bets
+        .into_iter()
+        .map(|(bet_id, bet)| OngoingBetResponse::new(bet_id, &bet))
+        .collect())
+}
+
+pub fn query_historical_bets(
+    deps: Deps,
+    skip: u32,
+    limit: Option<u32>,
+    addr: Addr,
+) -> StdResult<HistoricalBetResponse> {
+    let history = read_historical_bets_by_addr(deps.storage, &addr, skip, limit)?;
+    Ok(HistoricalBetResponse { history })
+}
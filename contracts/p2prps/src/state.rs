@@ -0,0 +1,688 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use cosmwasm_std::{
+    Addr, Api, BlockInfo, CanonicalAddr, Order, StdError, StdResult, Storage, Timestamp, Uint64,
+};
+use cw0::{Duration, Expiration};
+use cw_storage_plus::{Item, Map};
+
+use crate::{
+    error::ContractError,
+    msg::{PendingBetsFilter, PendingBetsSort},
+};
+
+use tefiluck::{
+    asset::Asset,
+    config::{CoinLimit, CommonConfig},
+    hash::calculate_sha256,
+    history::{self, HistoricalRecord},
+    payout::Payout,
+};
+
+static CONFIG: Item<Config> = Item::new("config");
+static PENDING_BETS: Map<&Addr, AddrPendingBets> = Map::new("pending_bets");
+static PENDING_BETS_COUNT: Item<Uint64> = Item::new("pending_bets_count");
+static ONGOING_BETS: Map<String, OngoingBet> = Map::new("ongoing_bets");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub treasury: CanonicalAddr,
+    pub treasury_tax_percent: u8,
+    pub max_bets_by_addr: u64,
+    pub min_bet_amounts: Vec<CoinLimit>,
+    pub min_blocks_until_liquidation: u64,
+    pub max_blocks_until_liquidation: u64,
+    pub min_seconds_until_liquidation: u64,
+    pub max_seconds_until_liquidation: u64,
+    pub blocks_for_revealer_liquidation: u64,
+    pub seconds_for_revealer_liquidation: u64,
+    pub bet_revealer_liquidation_percent: u8,
+    pub bet_liquidator_percent: u8,
+    pub treasury_liquidation_percent: u8,
+    pub historical_bets_max_storage_size: u64,
+    pub historical_bets_clear_batch_size: u64,
+}
+
+impl Config {
+    pub fn common(&self) -> CommonConfig<'_> {
+        CommonConfig {
+            min_bet_amounts: &self.min_bet_amounts,
+            treasury_tax_percent: self.treasury_tax_percent,
+            liquidation_percents: [
+                self.bet_revealer_liquidation_percent,
+                self.bet_liquidator_percent,
+                self.treasury_liquidation_percent,
+            ],
+            min_blocks_until_liquidation: self.min_blocks_until_liquidation,
+            max_blocks_until_liquidation: self.max_blocks_until_liquidation,
+            min_seconds_until_liquidation: self.min_seconds_until_liquidation,
+            max_seconds_until_liquidation: self.max_seconds_until_liquidation,
+            historical_bets_max_storage_size: self.historical_bets_max_storage_size,
+            historical_bets_clear_batch_size: self.historical_bets_clear_batch_size,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ContractError> {
+        self.common()
+            .validate()
+            .map_err(|message| ContractError::ValidationErr { message })
+    }
+
+    // revealed player gets exclusive liquidation right for a gap measured in the same units as the bet
+    pub fn revealer_liquidation_gap(&self, until_liquidation: &Duration) -> Duration {
+        match until_liquidation {
+            Duration::Height(_) => Duration::Height(self.blocks_for_revealer_liquidation),
+            Duration::Time(_) => Duration::Time(self.seconds_for_revealer_liquidation),
+        }
+    }
+
+    pub fn validate_place_bet_inputs(
+        &self,
+        until_liquidation: &Duration,
+        addr_bets_count: usize,
+        asset: &Asset,
+    ) -> StdResult<()> {
+        self.common()
+            .validate_until_liquidation(until_liquidation)?;
+
+        if (addr_bets_count as u64) == self.max_bets_by_addr {
+            return Err(StdError::generic_err(
+                "max bets by address limit was reached",
+            ));
+        }
+
+        let coin_limit = self.common().coin_limit(&asset.denom)?;
+        if asset.amount < coin_limit.min_amount {
+            return Err(StdError::generic_err(
+                "provided amount less than min limit for provided asset",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct AddrPendingBets {
+    pub bets: Vec<PendingBet>,
+}
+
+impl AddrPendingBets {
+    pub fn store_bet(
+        &mut self,
+        owner: CanonicalAddr,
+        bet_id: String,
+        sig: String,
+        until_liquidation: Duration,
+        asset: Asset,
+        time: Timestamp,
+    ) -> StdResult<()> {
+        if self.bets.iter().any(|el| el.id == bet_id) {
+            return Err(StdError::generic_err("bet with same id alreay exists"));
+        }
+
+        self.bets.push(PendingBet::new(
+            owner,
+            bet_id,
+            sig,
+            until_liquidation,
+            asset,
+            time,
+        ));
+        Ok(())
+    }
+
+    pub fn find_by_id(&self, bet_id: &str) -> StdResult<PendingBet> {
+        match self.bets.iter().find(|bet| bet.id.eq(bet_id)) {
+            Some(b) => Ok(b.clone()),
+            None => Err(StdError::generic_err("pending bet by id not found")),
+        }
+    }
+
+    pub fn remove_bet(&mut self, bet_id: &str) {
+        self.bets.retain(|bet| bet.id.ne(bet_id))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingBet {
+    pub owner: CanonicalAddr,
+    pub id: String,
+    pub signature: String,
+    pub until_liquidation: Duration,
+    pub asset: Asset,
+    pub created_at: Timestamp,
+}
+
+impl PendingBet {
+    pub fn new(
+        owner: CanonicalAddr,
+        id: String,
+        sig: String,
+        until_liquidation: Duration,
+        asset: Asset,
+        time: Timestamp,
+    ) -> Self {
+        PendingBet {
+            owner,
+            id,
+            signature: sig,
+            until_liquidation,
+            asset,
+            created_at: time,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Hand {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Hand {
+    pub fn from_u8(hand: u8) -> StdResult<Hand> {
+        match hand {
+            0 => Ok(Hand::Rock),
+            1 => Ok(Hand::Paper),
+            2 => Ok(Hand::Scissors),
+            _ => Err(StdError::generic_err("invalid hand")),
+        }
+    }
+
+    pub fn u8(&self) -> u8 {
+        match self {
+            Hand::Rock => 0,
+            Hand::Paper => 1,
+            Hand::Scissors => 2,
+        }
+    }
+
+    // every hand beats the one right before it: paper > rock, scissors > paper, rock > scissors
+    pub fn beats(&self, other: &Hand) -> bool {
+        (self.u8() + 3 - other.u8()) % 3 == 1
+    }
+
+    // passphrase is expected in "<hand>_<secret>" format
+    pub fn from_passphrase(passphrase: &str) -> Option<Hand> {
+        let split: Vec<&str> = passphrase.split('_').collect();
+        if split.len() != 2 {
+            return None;
+        }
+
+        split[0]
+            .parse::<u8>()
+            .ok()
+            .and_then(|hand| Hand::from_u8(hand).ok())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OngoingBet {
+    pub bet_creator: Addr,
+    pub bet_responder: Addr,
+    pub creator_signature: String,
+    pub responder_signature: String,
+    pub creator_passphrase: Option<String>,
+    pub responder_passphrase: Option<String>,
+    pub asset: Asset,
+    pub started_at_block: u64,
+    pub until_liquidation: Duration,
+    // once it expires anyone can liquidate a bet nobody revealed
+    pub liquidation: Expiration,
+    // before it expires only the revealed player can liquidate
+    pub revealer_liquidation: Expiration,
+    pub created_at: Timestamp,
+}
+
+impl OngoingBet {
+    // both players put the same stake, so the pot is twice the pending one
+    pub fn new(
+        pending_bet: &PendingBet,
+        bet_creator: Addr,
+        bet_responder: Addr,
+        responder_signature: String,
+        revealer_liquidation_gap: Duration,
+        block: &BlockInfo,
+    ) -> StdResult<Self> {
+        // bet becomes liquidatable strictly after the deadline has passed
+        let until_liquidation = pending_bet.until_liquidation;
+        let liquidation = until_liquidation.plus_one().after(block);
+        let revealer_liquidation = (liquidation + revealer_liquidation_gap)?;
+
+        let mut asset = pending_bet.asset.clone();
+        asset.checked_add(&pending_bet.asset)?;
+
+        Ok(OngoingBet {
+            bet_creator,
+            bet_responder,
+            creator_signature: pending_bet.signature.clone(),
+            responder_signature,
+            creator_passphrase: None,
+            responder_passphrase: None,
+            asset,
+            started_at_block: block.height,
+            until_liquidation,
+            liquidation,
+            revealer_liquidation,
+            created_at: block.time,
+        })
+    }
+
+    pub fn is_player(&self, addr: &Addr) -> bool {
+        self.bet_creator.eq(addr) || self.bet_responder.eq(addr)
+    }
+
+    // stores passphrase of the player once it matches their commitment
+    pub fn reveal(&mut self, player: &Addr, passphrase: String) -> Result<(), ContractError> {
+        let (signature, revealed) = if player.eq(&self.bet_creator) {
+            (&self.creator_signature, &mut self.creator_passphrase)
+        } else if player.eq(&self.bet_responder) {
+            (&self.responder_signature, &mut self.responder_passphrase)
+        } else {
+            return Err(ContractError::OnlyBetPlayersAllowedToReveal {});
+        };
+
+        if revealed.is_some() {
+            return Err(ContractError::HandWasAlreadyRevealed {});
+        }
+
+        if calculate_sha256(&passphrase).ne(signature) {
+            return Err(ContractError::SignatureMismatch {});
+        }
+
+        *revealed = Some(passphrase);
+        Ok(())
+    }
+
+    pub fn is_revealed(&self) -> bool {
+        self.creator_passphrase.is_some() && self.responder_passphrase.is_some()
+    }
+
+    pub fn creator_hand(&self) -> Option<Hand> {
+        self.creator_passphrase
+            .as_deref()
+            .and_then(Hand::from_passphrase)
+    }
+
+    pub fn responder_hand(&self) -> Option<Hand> {
+        self.responder_passphrase
+            .as_deref()
+            .and_then(Hand::from_passphrase)
+    }
+
+    // player with malformed passphrase loses, None stands for a draw
+    pub fn resolve_winner(&self) -> Option<Addr> {
+        match (self.creator_hand(), self.responder_hand()) {
+            (Some(creator), Some(responder)) => {
+                if creator.beats(&responder) {
+                    Some(self.bet_creator.clone())
+                } else if responder.beats(&creator) {
+                    Some(self.bet_responder.clone())
+                } else {
+                    None
+                }
+            }
+            (Some(_), None) => Some(self.bet_creator.clone()),
+            (None, Some(_)) => Some(self.bet_responder.clone()),
+            (None, None) => None,
+        }
+    }
+
+    // (wronged, at fault) when exactly one of the players revealed their hand
+    pub fn liquidation_parties(&self) -> Option<(Addr, Addr)> {
+        match (&self.creator_passphrase, &self.responder_passphrase) {
+            (Some(_), None) => Some((self.bet_creator.clone(), self.bet_responder.clone())),
+            (None, Some(_)) => Some((self.bet_responder.clone(), self.bet_creator.clone())),
+            _ => None,
+        }
+    }
+
+    // both players put the same stake, so it is a half of the pot
+    pub fn stake(&self) -> Asset {
+        Asset {
+            denom: self.asset.denom.clone(),
+            amount: self.asset.amount.multiply_ratio(1u128, 2u128),
+        }
+    }
+
+    pub fn is_publicly_liquidatable(&self, block: &BlockInfo) -> bool {
+        if self.liquidation_parties().is_some() {
+            self.revealer_liquidation.is_expired(block)
+        } else {
+            self.liquidation.is_expired(block)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GameOutcome {
+    Resolved,
+    Draw,
+    Liquidated,
+    Refunded,
+    Withdrawn,
+}
+
+impl fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            GameOutcome::Resolved => "resolved",
+            GameOutcome::Draw => "draw",
+            GameOutcome::Liquidated => "liquidated",
+            GameOutcome::Refunded => "refunded",
+            GameOutcome::Withdrawn => "withdrawn",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoricalBet {
+    pub id: String,
+    pub owner: String,
+    pub responder: Option<String>,
+    pub winner: Option<String>,
+    pub liquidator: Option<String>,
+    pub creator_hand: Option<Hand>,
+    pub responder_hand: Option<Hand>,
+    pub creator_passphrase: Option<String>,
+    pub responder_passphrase: Option<String>,
+    pub asset: Asset,
+    pub payouts: Vec<Payout>,
+    pub outcome: GameOutcome,
+    pub created_at: u64,
+    pub completed_at: u64,
+}
+
+impl HistoricalRecord for HistoricalBet {
+    fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    fn responder(&self) -> Option<&str> {
+        self.responder.as_deref()
+    }
+
+    fn liquidator(&self) -> Option<&str> {
+        self.liquidator.as_deref()
+    }
+
+    fn denom(&self) -> &str {
+        &self.asset.denom
+    }
+}
+
+impl HistoricalBet {
+    pub fn new(
+        id: String,
+        bet: &OngoingBet,
+        winner: Option<Addr>,
+        liquidator: Option<Addr>,
+        payouts: Vec<Payout>,
+        outcome: GameOutcome,
+        completed_at: u64,
+    ) -> Self {
+        HistoricalBet {
+            id,
+            owner: bet.bet_creator.to_string(),
+            responder: Some(bet.bet_responder.to_string()),
+            winner: winner.map(|w| w.to_string()),
+            liquidator: liquidator.map(|l| l.to_string()),
+            creator_hand: bet.creator_hand(),
+            responder_hand: bet.responder_hand(),
+            creator_passphrase: bet.creator_passphrase.clone(),
+            responder_passphrase: bet.responder_passphrase.clone(),
+            asset: bet.asset.clone(),
+            payouts,
+            outcome,
+            created_at: bet.created_at.seconds(),
+            completed_at,
+        }
+    }
+
+    pub fn withdrawn(
+        owner: &Addr,
+        bet: &PendingBet,
+        payouts: Vec<Payout>,
+        completed_at: u64,
+    ) -> Self {
+        HistoricalBet {
+            id: bet.id.clone(),
+            owner: owner.to_string(),
+            responder: None,
+            winner: None,
+            liquidator: None,
+            creator_hand: None,
+            responder_hand: None,
+            creator_passphrase: None,
+            responder_passphrase: None,
+            asset: bet.asset.clone(),
+            payouts,
+            outcome: GameOutcome::Withdrawn,
+            created_at: bet.created_at.seconds(),
+            completed_at,
+        }
+    }
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    CONFIG.save(storage, config)
+}
+
+pub fn load_config(storage: &dyn Storage) -> StdResult<Config> {
+    CONFIG.load(storage)
+}
+
+pub fn store_pending_bets(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    pending_bets: &AddrPendingBets,
+) -> StdResult<()> {
+    PENDING_BETS.save(storage, addr, pending_bets)
+}
+
+pub fn may_load_pending_bets(
+    storage: &dyn Storage,
+    addr: &Addr,
+) -> StdResult<Option<AddrPendingBets>> {
+    PENDING_BETS.may_load(storage, addr)
+}
+
+pub fn load_pending_bets(storage: &dyn Storage, addr: &Addr) -> StdResult<AddrPendingBets> {
+    may_load_pending_bets(storage, addr).map(|res| res.unwrap_or_default())
+}
+
+pub fn store_pending_bets_count(storage: &mut dyn Storage, count: Uint64) -> StdResult<()> {
+    PENDING_BETS_COUNT.save(storage, &count)
+}
+
+pub fn load_pending_bets_count(storage: &dyn Storage) -> StdResult<Uint64> {
+    PENDING_BETS_COUNT.load(storage)
+}
+
+pub fn store_ongoing_bet(
+    storage: &mut dyn Storage,
+    bet_id: String,
+    ongoing_bet: &OngoingBet,
+) -> StdResult<()> {
+    ONGOING_BETS.save(storage, bet_id, ongoing_bet)
+}
+
+pub fn load_ongoing_bet(storage: &dyn Storage, bet_id: String) -> StdResult<OngoingBet> {
+    ONGOING_BETS.load(storage, bet_id)
+}
+
+pub fn remove_ongoing_bet(storage: &mut dyn Storage, bet_id: String) {
+    ONGOING_BETS.remove(storage, bet_id)
+}
+
+const MAX_LIMIT: u32 = 100;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn read_pending_bets(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    filter: &PendingBetsFilter,
+) -> StdResult<Vec<PendingBet>> {
+    let skip = filter.skip as usize;
+    let limit = filter.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let exclude_addr = match &filter.exclude_address {
+        Some(v) => Some(api.addr_canonicalize(v)?),
+        None => None,
+    };
+    let asset_filters = filter.to_asset_map();
+
+    let mut pending_bets: Vec<PendingBet> = PENDING_BETS
+        .range(storage, None, None, Order::Ascending)
+        .flat_map(|item| {
+            let (_, addr_bets) = item.unwrap_or_default();
+            addr_bets.bets
+        })
+        .filter(|bet| {
+            if let Some(exclude_address) = &exclude_addr {
+                if bet.owner.eq(exclude_address) {
+                    return false;
+                }
+            }
+
+            if !asset_filters.is_empty() {
+                if let Some((from, to)) = asset_filters.get(&bet.asset.denom) {
+                    if let Some(from) = from {
+                        if bet.asset.amount.lt(from) {
+                            return false;
+                        }
+                    }
+
+                    if let Some(to) = to {
+                        if bet.asset.amount.gt(to) {
+                            return false;
+                        }
+                    }
+                } else {
+                    return false;
+                }
+            }
+
+            if let Some(liquidation) = &filter.liquidation {
+                // bounds apply to bets with deadlines in the same units
+                let (value, from, to) = match bet.until_liquidation {
+                    Duration::Height(blocks) => (
+                        blocks,
+                        liquidation.blocks_until_liquidation_from,
+                        liquidation.blocks_until_liquidation_to,
+                    ),
+                    Duration::Time(seconds) => (
+                        seconds,
+                        liquidation.seconds_until_liquidation_from,
+                        liquidation.seconds_until_liquidation_to,
+                    ),
+                };
+
+                if let Some(from) = from {
+                    if value < from {
+                        return false;
+                    }
+                }
+
+                if let Some(to) = to {
+                    if value > to {
+                        return false;
+                    }
+                }
+            }
+
+            true
+        })
+        .collect();
+
+    let sort = match filter.sort_by {
+        PendingBetsSort::Creation { asc } => {
+            if asc {
+                |a: &PendingBet, b: &PendingBet| a.created_at.cmp(&b.created_at)
+            } else {
+                |a: &PendingBet, b: &PendingBet| b.created_at.cmp(&a.created_at)
+            }
+        }
+        PendingBetsSort::Price { asc } => {
+            if asc {
+                |a: &PendingBet, b: &PendingBet| a.asset.amount.cmp(&b.asset.amount)
+            } else {
+                |a: &PendingBet, b: &PendingBet| b.asset.amount.cmp(&a.asset.amount)
+            }
+        }
+    };
+
+    pending_bets.sort_by(sort);
+    Ok(pending_bets.into_iter().skip(skip).take(limit).collect())
+}
+
+pub fn read_ongoing_bets_by_addr(
+    storage: &dyn Storage,
+    addr: &Addr,
+) -> StdResult<Vec<(String, OngoingBet)>> {
+    ONGOING_BETS
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, bet)) => bet.is_player(addr),
+            Err(_) => false,
+        })
+        .map(|item| {
+            let (k, v) = item?;
+            let bet_id = std::str::from_utf8(&k)?.to_string();
+            Ok((bet_id, v))
+        })
+        .collect()
+}
+
+pub fn read_public_liquidatable_bets(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    skip: u32,
+    limit: Option<u32>,
+    exclude_addr: Option<Addr>,
+) -> StdResult<Vec<(String, OngoingBet)>> {
+    let skip = skip as usize;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    ONGOING_BETS
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, bet)) => {
+                if let Some(exclude_addr) = &exclude_addr {
+                    if bet.is_player(exclude_addr) {
+                        return false;
+                    }
+                }
+
+                bet.is_publicly_liquidatable(block)
+            }
+            Err(_) => false,
+        })
+        .map(|item| {
+            let (k, v) = item?;
+            let bet_id = std::str::from_utf8(&k)?.to_string();
+            Ok((bet_id, v))
+        })
+        .skip(skip)
+        .take(limit)
+        .collect()
+}
+
+// returns bets where address took part in any role, newest first
+pub fn read_historical_bets_by_addr(
+    storage: &dyn Storage,
+    addr: &Addr,
+    skip: u32,
+    limit: Option<u32>,
+) -> StdResult<Vec<HistoricalBet>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    history::read_historical_bets_by_addr(storage, addr.as_str(), skip as usize, limit)
+}
@@ -0,0 +1,1269 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{
+    attr, Addr, Api, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Response, SubMsg, Timestamp,
+    Uint128, Uint64,
+};
+
+use crate::commands::{
+    liquidate_bet, place_bet, respond_bet, reveal_bet, update_config, withdraw_pending_bet,
+};
+use crate::contract::instantiate;
+use crate::msg::{InstantiateMsg, PendingBetsFilter, PendingBetsSort, UpdateConfigMsg};
+use crate::queries::{query_historical_bets, query_pending_bets, query_public_liquidatable_bets};
+use crate::state::{
+    load_config, load_ongoing_bet, load_pending_bets, load_pending_bets_count, GameOutcome, Hand,
+    HistoricalBet, OngoingBet, PendingBet,
+};
+use crate::ContractError;
+use cw0::{Duration, Expiration};
+use tefiluck::{
+    asset::Asset,
+    config::InstantiateCoinLimitMsg,
+    hash::calculate_sha256,
+    history::{load_historical_bet, load_historical_bets_state},
+    mock_querier::{mock_dependencies, mock_env_custom},
+    payout::{Payout, PayoutRole},
+};
+
+const MOCK_SIGNATURE: &str = "84dbedafb3b595f6d2d1c9719520f0e0a19fabd953afd4e5befc22a8fd0d7510";
+// creator always plays rock
+const MOCK_PASSPHRASE: &str = "0_tefiluck";
+const PAPER_PASSPHRASE: &str = "1_tefiluck";
+const SCISSORS_PASSPHRASE: &str = "2_tefiluck";
+const ROCK_PASSPHRASE: &str = "0_rps";
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        treasury: "addr0000".to_string(),
+        treasury_tax_percent: 1,
+        max_bets_by_addr: 50,
+        min_bet_amounts: vec![InstantiateCoinLimitMsg {
+            denom: "uusd".to_string(),
+            min_amount: 1000000u64,
+        }],
+        min_blocks_until_liquidation: 100,
+        max_blocks_until_liquidation: 500,
+        min_seconds_until_liquidation: 600,
+        max_seconds_until_liquidation: 3000,
+        blocks_for_revealer_liquidation: 20,
+        seconds_for_revealer_liquidation: 120,
+        bet_revealer_liquidation_percent: 90,
+        bet_liquidator_percent: 7,
+        treasury_liquidation_percent: 3,
+        historical_bets_max_storage_size: 100,
+        historical_bets_clear_batch_size: 10,
+    }
+}
+
+fn empty_update_config_msg() -> UpdateConfigMsg {
+    UpdateConfigMsg {
+        owner: None,
+        treasury: None,
+        treasury_tax_percent: None,
+        max_bets_by_addr: None,
+        min_bet_amounts: None,
+        min_blocks_until_liquidation: None,
+        max_blocks_until_liquidation: None,
+        min_seconds_until_liquidation: None,
+        max_seconds_until_liquidation: None,
+        blocks_for_revealer_liquidation: None,
+        seconds_for_revealer_liquidation: None,
+        bet_revealer_liquidation_percent: None,
+        bet_liquidator_percent: None,
+        treasury_liquidation_percent: None,
+        historical_bets_max_storage_size: None,
+        historical_bets_clear_batch_size: None,
+    }
+}
+
+fn proper_instantiate(deps: DepsMut) -> Result<Response, ContractError> {
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+
+    instantiate(deps, env, info, instantiate_msg())
+}
+
+fn create_valid_pending_bet(deps: DepsMut) -> String {
+    let env = mock_env();
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+
+    let pb = place_bet(
+        deps,
+        env,
+        info,
+        MOCK_SIGNATURE.to_string(),
+        Duration::Height(200),
+    )
+    .unwrap();
+
+    pb.attributes.get(2).expect("no bet_id").value.clone()
+}
+
+fn create_valid_ongoing_bet(deps: DepsMut, bet_id: String, responder_passphrase: &str) {
+    let env = mock_env();
+    let info = mock_info(
+        "addr0002",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+
+    let _ = respond_bet(
+        deps,
+        env,
+        info,
+        Addr::unchecked("addr0001"),
+        bet_id,
+        calculate_sha256(responder_passphrase),
+    )
+    .unwrap();
+}
+
+fn send_msg(to_address: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: to_address.to_string(),
+        amount: vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(amount),
+        }],
+    }))
+}
+
+fn payout(recipient: &str, role: PayoutRole, gross_amount: u128, net_amount: u128) -> Payout {
+    Payout {
+        recipient: recipient.to_string(),
+        role,
+        gross_amount: Uint128::new(gross_amount),
+        net_amount: Uint128::new(net_amount),
+    }
+}
+
+#[test]
+fn test_hand_rules() {
+    assert!(Hand::Paper.beats(&Hand::Rock));
+    assert!(Hand::Scissors.beats(&Hand::Paper));
+    assert!(Hand::Rock.beats(&Hand::Scissors));
+    assert!(!Hand::Rock.beats(&Hand::Paper));
+    assert!(!Hand::Rock.beats(&Hand::Rock));
+
+    assert_eq!(Hand::from_passphrase(MOCK_PASSPHRASE), Some(Hand::Rock));
+    assert_eq!(
+        Hand::from_passphrase(SCISSORS_PASSPHRASE),
+        Some(Hand::Scissors)
+    );
+    assert_eq!(Hand::from_passphrase("3_tefiluck"), None);
+    assert_eq!(Hand::from_passphrase("tefiluck"), None);
+}
+
+#[test]
+fn test_proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+
+    assert!(proper_instantiate(deps.as_mut()).is_ok());
+}
+
+#[test]
+fn test_invalid_initialization() {
+    let mut deps = mock_dependencies(&[]);
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+
+    let mut msg = instantiate_msg();
+    msg.min_bet_amounts = vec![];
+    match instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err() {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: min_bet_amounts must be a non-empty list".to_string()
+        ),
+        _ => panic!("Must return validation err"),
+    };
+
+    let mut msg = instantiate_msg();
+    msg.treasury_tax_percent = 11;
+    match instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err() {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: treasury percent must be less than 10".to_string()
+        ),
+        _ => panic!("Must return validation err"),
+    };
+
+    let mut msg = instantiate_msg();
+    msg.bet_liquidator_percent = 8;
+    match instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err() {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: liquidation percent must be equal to 100".to_string()
+        ),
+        _ => panic!("Must return validation err"),
+    };
+
+    let mut msg = instantiate_msg();
+    msg.min_blocks_until_liquidation = 501;
+    match instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err() {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: min_blocks_until_liquidation must be less than max_blocks_until_liquidation".to_string()
+        ),
+        _ => panic!("Must return validation err"),
+    };
+
+    let mut msg = instantiate_msg();
+    msg.historical_bets_clear_batch_size = 0;
+    match instantiate(deps.as_mut(), env, info, msg).unwrap_err() {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: historical_bets_clear_batch_size must be between 1 and historical_bets_max_storage_size".to_string()
+        ),
+        _ => panic!("Must return validation err"),
+    };
+}
+
+#[test]
+fn test_place_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_nanos(100000);
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+
+    let place_bet_response = place_bet(
+        deps.as_mut(),
+        env,
+        info,
+        MOCK_SIGNATURE.to_string(),
+        Duration::Height(200),
+    )
+    .unwrap();
+
+    let log_action = place_bet_response.attributes.first().expect("no log");
+    assert_eq!(log_action, &attr("action", "place_bet"));
+
+    let log_sender = place_bet_response.attributes.get(1).expect("no log");
+    assert_eq!(log_sender, &attr("sender", "addr0001".to_string()),);
+
+    let log_bet_id = place_bet_response.attributes.get(2).expect("no log");
+    let bet_id = log_bet_id.value.clone();
+
+    let pending_bets = load_pending_bets(&deps.storage, &Addr::unchecked("addr0001")).unwrap();
+    let bet = pending_bets.find_by_id(&bet_id).unwrap();
+    assert_eq!(
+        bet,
+        PendingBet {
+            owner: deps.api.addr_canonicalize("addr0001").unwrap(),
+            id: bet_id,
+            signature: MOCK_SIGNATURE.to_string(),
+            until_liquidation: Duration::Height(200),
+            asset: Asset {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(1000000u128),
+            },
+            created_at: Timestamp::from_nanos(100000),
+        }
+    );
+
+    let bet_count = load_pending_bets_count(&deps.storage).unwrap();
+    assert_eq!(Uint64::from(1u64), bet_count,);
+
+    let bets = query_pending_bets(
+        deps.as_ref(),
+        PendingBetsFilter {
+            skip: 0,
+            limit: None,
+            exclude_address: None,
+            assets: None,
+            liquidation: None,
+            sort_by: PendingBetsSort::Creation { asc: true },
+        },
+    )
+    .unwrap();
+    assert_eq!(bets.len(), 1);
+}
+
+#[test]
+fn test_place_bet_validation_error() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let env = mock_env();
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+
+    match place_bet(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        MOCK_SIGNATURE.to_string(),
+        Duration::Height(99),
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(e) => assert_eq!(
+            e.to_string(),
+            "Generic error: blocks_before_liquidation must be higher than min allowed value"
+        ),
+        _ => panic!("no error"),
+    }
+
+    match place_bet(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        MOCK_SIGNATURE.to_string(),
+        Duration::Height(501),
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(e) => assert_eq!(
+            e.to_string(),
+            "Generic error: blocks_before_liquidation must be less than max allowed value"
+        ),
+        _ => panic!("no error"),
+    }
+
+    match place_bet(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        MOCK_SIGNATURE.to_string(),
+        Duration::Time(599),
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(e) => assert_eq!(
+            e.to_string(),
+            "Generic error: seconds_before_liquidation must be higher than min allowed value"
+        ),
+        _ => panic!("no error"),
+    }
+
+    match place_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        MOCK_SIGNATURE.to_string(),
+        Duration::Time(3001),
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(e) => assert_eq!(
+            e.to_string(),
+            "Generic error: seconds_before_liquidation must be less than max allowed value"
+        ),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(999999u128),
+        }],
+    );
+    match place_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        MOCK_SIGNATURE.to_string(),
+        Duration::Height(200),
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(e) => assert_eq!(
+            e.to_string(),
+            "Generic error: provided amount less than min limit for provided asset"
+        ),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "ukrw".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+    match place_bet(
+        deps.as_mut(),
+        env,
+        info,
+        MOCK_SIGNATURE.to_string(),
+        Duration::Height(200),
+    )
+    .unwrap_err()
+    {
+        ContractError::Std(e) => assert_eq!(
+            e.to_string(),
+            "Generic error: coin limits for provided asset not found"
+        ),
+        _ => panic!("no error"),
+    }
+}
+
+#[test]
+fn test_respond_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), PAPER_PASSPHRASE);
+
+    let env = mock_env();
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    assert_eq!(
+        ongoing_bet,
+        OngoingBet {
+            bet_creator: Addr::unchecked("addr0001"),
+            bet_responder: Addr::unchecked("addr0002"),
+            creator_signature: MOCK_SIGNATURE.to_string(),
+            responder_signature: calculate_sha256(PAPER_PASSPHRASE),
+            creator_passphrase: None,
+            responder_passphrase: None,
+            asset: Asset {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(2000000u128),
+            },
+            started_at_block: env.block.height,
+            until_liquidation: Duration::Height(200),
+            liquidation: Expiration::AtHeight(env.block.height + 201),
+            revealer_liquidation: Expiration::AtHeight(env.block.height + 221),
+            created_at: env.block.time,
+        }
+    );
+
+    let pending_bets = load_pending_bets(&deps.storage, &Addr::unchecked("addr0001")).unwrap();
+    assert!(pending_bets.bets.is_empty());
+
+    let bet_count = load_pending_bets_count(&deps.storage).unwrap();
+    assert_eq!(Uint64::from(0u64), bet_count,);
+}
+
+#[test]
+fn test_respond_bet_validation_error() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+
+    let env = mock_env();
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+    match respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        calculate_sha256(PAPER_PASSPHRASE),
+    )
+    .unwrap_err()
+    {
+        ContractError::ForbiddenToPlayVSYourself {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info(
+        "addr0002",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000001u128),
+        }],
+    );
+    match respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        calculate_sha256(PAPER_PASSPHRASE),
+    )
+    .unwrap_err()
+    {
+        ContractError::ResponderAssetMismatch {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info(
+        "addr0002",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+    match respond_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        Addr::unchecked("addr0001"),
+        bet_id.clone(),
+        MOCK_SIGNATURE.to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::SignatureMismatch {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info(
+        "addr0002",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+    match respond_bet(
+        deps.as_mut(),
+        env,
+        info,
+        Addr::unchecked("addr0001"),
+        "unknown".to_string(),
+        calculate_sha256(PAPER_PASSPHRASE),
+    )
+    .unwrap_err()
+    {
+        ContractError::BetWasCancledOrAccepted {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+}
+
+#[test]
+fn test_reveal_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), PAPER_PASSPHRASE);
+
+    let env = mock_env();
+
+    // first reveal only stores the hand
+    let info = mock_info("addr0002", &[]);
+    let response = reveal_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        bet_id.clone(),
+        PAPER_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+    assert!(response.messages.is_empty());
+    assert_eq!(
+        response.attributes.first().expect("no log"),
+        &attr("action", "reveal_bet")
+    );
+
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    assert_eq!(
+        ongoing_bet.responder_passphrase,
+        Some(PAPER_PASSPHRASE.to_string())
+    );
+    assert_eq!(ongoing_bet.creator_passphrase, None);
+
+    // second reveal settles the game, paper beats rock
+    let info = mock_info("addr0001", &[]);
+    let response = reveal_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        response.messages,
+        vec![send_msg("addr0002", 1960396), send_msg("addr0000", 19801)]
+    );
+
+    assert!(load_ongoing_bet(&deps.storage, bet_id.clone()).is_err(),);
+
+    let historical_bet = load_historical_bet::<HistoricalBet>(&deps.storage, 0).unwrap();
+    assert_eq!(
+        historical_bet,
+        HistoricalBet {
+            id: bet_id,
+            owner: "addr0001".to_string(),
+            responder: Some("addr0002".to_string()),
+            winner: Some("addr0002".to_string()),
+            liquidator: None,
+            creator_hand: Some(Hand::Rock),
+            responder_hand: Some(Hand::Paper),
+            creator_passphrase: Some(MOCK_PASSPHRASE.to_string()),
+            responder_passphrase: Some(PAPER_PASSPHRASE.to_string()),
+            asset: Asset {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(2000000u128),
+            },
+            payouts: vec![
+                payout("addr0002", PayoutRole::Winner, 1980000, 1960396),
+                payout("addr0000", PayoutRole::Treasury, 20000, 19801),
+            ],
+            outcome: GameOutcome::Resolved,
+            created_at: env.block.time.seconds(),
+            completed_at: env.block.time.seconds(),
+        }
+    );
+}
+
+#[test]
+fn test_reveal_bet_creator_wins() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), SCISSORS_PASSPHRASE);
+
+    let env = mock_env();
+    let info = mock_info("addr0001", &[]);
+    let _ = reveal_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    let info = mock_info("addr0002", &[]);
+    let response = reveal_bet(
+        deps.as_mut(),
+        env,
+        info,
+        bet_id,
+        SCISSORS_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    // rock beats scissors
+    assert_eq!(
+        response.messages,
+        vec![send_msg("addr0001", 1980000), send_msg("addr0000", 20000)]
+    );
+}
+
+#[test]
+fn test_reveal_bet_draw() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), ROCK_PASSPHRASE);
+
+    let env = mock_env();
+    let info = mock_info("addr0001", &[]);
+    let _ = reveal_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    let info = mock_info("addr0002", &[]);
+    let response = reveal_bet(
+        deps.as_mut(),
+        env,
+        info,
+        bet_id,
+        ROCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    // both stakes are refunded minus treasury fee
+    assert_eq!(
+        response.messages,
+        vec![
+            send_msg("addr0001", 980198),
+            send_msg("addr0002", 980198),
+            send_msg("addr0000", 19801),
+        ]
+    );
+
+    let historical_bet = load_historical_bet::<HistoricalBet>(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.outcome, GameOutcome::Draw);
+    assert_eq!(historical_bet.winner, None);
+    assert_eq!(
+        historical_bet.payouts,
+        vec![
+            payout("addr0001", PayoutRole::Owner, 990000, 980198),
+            payout("addr0002", PayoutRole::Responder, 990000, 980198),
+            payout("addr0000", PayoutRole::Treasury, 20000, 19801),
+        ]
+    );
+}
+
+#[test]
+fn test_reveal_bet_malformed_passphrase() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), "7_tefiluck");
+
+    let env = mock_env();
+    let info = mock_info("addr0002", &[]);
+    let _ = reveal_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        bet_id.clone(),
+        "7_tefiluck".to_string(),
+    )
+    .unwrap();
+
+    let info = mock_info("addr0001", &[]);
+    let _ = reveal_bet(
+        deps.as_mut(),
+        env,
+        info,
+        bet_id,
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    // responder committed to an invalid hand and loses
+    let historical_bet = load_historical_bet::<HistoricalBet>(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.winner, Some("addr0001".to_string()));
+    assert_eq!(historical_bet.responder_hand, None);
+}
+
+#[test]
+fn test_reveal_bet_validation_error() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), PAPER_PASSPHRASE);
+
+    let env = mock_env();
+
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1u128),
+        }],
+    );
+    match reveal_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::ExecuteWithoutFunds {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info("addr0003", &[]);
+    match reveal_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::OnlyBetPlayersAllowedToReveal {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    // responder can not reveal with the creator's passphrase
+    let info = mock_info("addr0002", &[]);
+    match reveal_bet(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::SignatureMismatch {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info("addr0001", &[]);
+    let _ = reveal_bet(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    match reveal_bet(
+        deps.as_mut(),
+        env,
+        info,
+        bet_id,
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap_err()
+    {
+        ContractError::HandWasAlreadyRevealed {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+}
+
+#[test]
+fn test_liquidate_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), PAPER_PASSPHRASE);
+
+    // only creator revealed, responder is at fault
+    let info = mock_info("addr0001", &[]);
+    let _ = reveal_bet(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    let env = mock_env_custom(13_345);
+    let liquidatable =
+        query_public_liquidatable_bets(deps.as_ref(), &env.block, 0, None, None).unwrap();
+    assert_eq!(liquidatable.len(), 1);
+    assert!(liquidatable[0].creator_revealed);
+    assert!(!liquidatable[0].responder_revealed);
+
+    let info = mock_info("addr0003", &[]);
+    let response = liquidate_bet(deps.as_mut(), env.clone(), info, bet_id.clone()).unwrap();
+
+    assert_eq!(
+        response.messages,
+        vec![
+            send_msg("addr0001", 1782178),
+            send_msg("addr0003", 138613),
+            send_msg("addr0000", 59405),
+        ]
+    );
+
+    assert!(load_ongoing_bet(&deps.storage, bet_id.clone()).is_err(),);
+
+    let historical_bet = load_historical_bet::<HistoricalBet>(&deps.storage, 0).unwrap();
+    assert_eq!(
+        historical_bet,
+        HistoricalBet {
+            id: bet_id,
+            owner: "addr0001".to_string(),
+            responder: Some("addr0002".to_string()),
+            winner: Some("addr0001".to_string()),
+            liquidator: Some("addr0003".to_string()),
+            creator_hand: Some(Hand::Rock),
+            responder_hand: None,
+            creator_passphrase: Some(MOCK_PASSPHRASE.to_string()),
+            responder_passphrase: None,
+            asset: Asset {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(2000000u128),
+            },
+            payouts: vec![
+                payout("addr0001", PayoutRole::Winner, 1800000, 1782178),
+                payout("addr0003", PayoutRole::Liquidator, 140000, 138613),
+                payout("addr0000", PayoutRole::Treasury, 60000, 59405),
+            ],
+            outcome: GameOutcome::Liquidated,
+            created_at: mock_env().block.time.seconds(),
+            completed_at: env.block.time.seconds(),
+        }
+    );
+}
+
+#[test]
+fn test_liquidate_bet_by_revealer() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), PAPER_PASSPHRASE);
+
+    let info = mock_info("addr0002", &[]);
+    let _ = reveal_bet(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        bet_id.clone(),
+        PAPER_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    // revealed player does not need to wait for the public gap
+    let env = mock_env_custom(12_546);
+    let response = liquidate_bet(deps.as_mut(), env, info, bet_id.clone()).unwrap();
+
+    assert_eq!(
+        response.messages,
+        vec![
+            send_msg("addr0002", 1800000),
+            send_msg("addr0002", 140000),
+            send_msg("addr0000", 60000),
+        ]
+    );
+
+    let history = query_historical_bets(deps.as_ref(), 0, None, Addr::unchecked("addr0002"))
+        .unwrap()
+        .history;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].winner, Some("addr0002".to_string()));
+}
+
+#[test]
+fn test_liquidate_unrevealed_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), PAPER_PASSPHRASE);
+
+    // nobody revealed, so anyone can refund right after liquidation block
+    let env = mock_env_custom(12_546);
+    let liquidatable =
+        query_public_liquidatable_bets(deps.as_ref(), &env.block, 0, None, None).unwrap();
+    assert_eq!(liquidatable.len(), 1);
+
+    let info = mock_info("addr0001", &[]);
+    let response = liquidate_bet(deps.as_mut(), env, info, bet_id).unwrap();
+
+    assert_eq!(
+        response.messages,
+        vec![
+            send_msg("addr0001", 990000),
+            send_msg("addr0002", 990000),
+            send_msg("addr0000", 20000),
+        ]
+    );
+
+    let historical_bet = load_historical_bet::<HistoricalBet>(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.outcome, GameOutcome::Refunded);
+    assert_eq!(historical_bet.winner, None);
+    assert_eq!(historical_bet.liquidator, Some("addr0001".to_string()));
+}
+
+#[test]
+fn test_liquidate_bet_validation_error() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), PAPER_PASSPHRASE);
+
+    let info = mock_info("addr0001", &[]);
+    let _ = reveal_bet(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    let info = mock_info(
+        "addr0003",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1u128),
+        }],
+    );
+    match liquidate_bet(deps.as_mut(), mock_env_custom(13_345), info, bet_id.clone()).unwrap_err() {
+        ContractError::ExecuteWithoutFunds {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info("addr0003", &[]);
+    match liquidate_bet(deps.as_mut(), mock_env_custom(12_545), info, bet_id.clone()).unwrap_err() {
+        ContractError::BetIsNotLiquidatableYet {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info("addr0002", &[]);
+    match liquidate_bet(deps.as_mut(), mock_env_custom(13_345), info, bet_id.clone()).unwrap_err() {
+        ContractError::ForbiddenForUnrevealedPlayerToLiquidate {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info("addr0003", &[]);
+    match liquidate_bet(deps.as_mut(), mock_env_custom(12_565), info, bet_id.clone()).unwrap_err() {
+        ContractError::RevealerLiquidationGapIsNotPassedYet {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    let info = mock_info("addr0003", &[]);
+    let _ = liquidate_bet(
+        deps.as_mut(),
+        mock_env_custom(12_566),
+        info.clone(),
+        bet_id.clone(),
+    )
+    .unwrap();
+
+    match liquidate_bet(deps.as_mut(), mock_env_custom(12_567), info, bet_id).unwrap_err() {
+        ContractError::GameWasAlreadyResolved {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+}
+
+#[test]
+fn test_liquidate_bet_with_time_deadline() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000000u128),
+        }],
+    );
+    let response = place_bet(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        MOCK_SIGNATURE.to_string(),
+        Duration::Time(1200),
+    )
+    .unwrap();
+    let bet_id = response.attributes.get(2).expect("no bet_id").value.clone();
+    create_valid_ongoing_bet(deps.as_mut(), bet_id.clone(), PAPER_PASSPHRASE);
+
+    let started_at = mock_env().block.time;
+    let ongoing_bet = load_ongoing_bet(&deps.storage, bet_id.clone()).unwrap();
+    assert_eq!(
+        ongoing_bet.liquidation,
+        Expiration::AtTime(started_at.plus_seconds(1201))
+    );
+    assert_eq!(
+        ongoing_bet.revealer_liquidation,
+        Expiration::AtTime(started_at.plus_seconds(1321))
+    );
+
+    let _ = reveal_bet(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        bet_id.clone(),
+        MOCK_PASSPHRASE.to_string(),
+    )
+    .unwrap();
+
+    // blocks do not count for a deadline in seconds
+    let mut env = mock_env_custom(13_345);
+    env.block.time = started_at.plus_seconds(1200);
+    let info = mock_info("addr0003", &[]);
+    match liquidate_bet(deps.as_mut(), env.clone(), info.clone(), bet_id.clone()).unwrap_err() {
+        ContractError::BetIsNotLiquidatableYet {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    env.block.time = started_at.plus_seconds(1201);
+    match liquidate_bet(deps.as_mut(), env.clone(), info.clone(), bet_id.clone()).unwrap_err() {
+        ContractError::RevealerLiquidationGapIsNotPassedYet {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    env.block.time = started_at.plus_seconds(1321);
+    let liquidatable =
+        query_public_liquidatable_bets(deps.as_ref(), &env.block, 0, None, None).unwrap();
+    assert_eq!(liquidatable.len(), 1);
+
+    let _ = liquidate_bet(deps.as_mut(), env, info, bet_id).unwrap();
+
+    let historical_bet = load_historical_bet::<HistoricalBet>(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.outcome, GameOutcome::Liquidated);
+    assert_eq!(historical_bet.winner, Some("addr0001".to_string()));
+}
+
+#[test]
+fn test_withdraw_pending_bet() {
+    let mut deps = mock_dependencies(&[]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    let bet_id = create_valid_pending_bet(deps.as_mut());
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(20000);
+    let info = mock_info("addr0001", &[]);
+    let response = withdraw_pending_bet(deps.as_mut(), env, info.clone(), bet_id.clone()).unwrap();
+
+    assert_eq!(response.messages, vec![send_msg("addr0001", 990099)]);
+
+    let bet_count = load_pending_bets_count(&deps.storage).unwrap();
+    assert_eq!(Uint64::from(0u64), bet_count,);
+
+    let historical_bet = load_historical_bet::<HistoricalBet>(&deps.storage, 0).unwrap();
+    assert_eq!(historical_bet.outcome, GameOutcome::Withdrawn);
+    assert_eq!(historical_bet.responder, None);
+    assert_eq!(historical_bet.completed_at, 20000);
+
+    match withdraw_pending_bet(deps.as_mut(), mock_env(), info, bet_id).unwrap_err() {
+        ContractError::GameWasAlreadyAccepted {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+}
+
+#[test]
+fn test_historical_bets_pruning() {
+    let mut deps = mock_dependencies(&[]);
+
+    let mut msg = instantiate_msg();
+    msg.historical_bets_max_storage_size = 3;
+    msg.historical_bets_clear_batch_size = 2;
+    let _ = instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    for height in 0..4u64 {
+        let info = mock_info(
+            "addr0001",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(1000000u128),
+            }],
+        );
+        let response = place_bet(
+            deps.as_mut(),
+            mock_env_custom(height),
+            info.clone(),
+            MOCK_SIGNATURE.to_string(),
+            Duration::Height(200),
+        )
+        .unwrap();
+        let bet_id = response.attributes.get(2).expect("no bet_id").value.clone();
+        let _ = withdraw_pending_bet(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0001", &[]),
+            bet_id,
+        )
+        .unwrap();
+    }
+
+    let state = load_historical_bets_state(&deps.storage).unwrap();
+    assert_eq!(state.first_seq, 2);
+    assert_eq!(state.next_seq, 4);
+
+    let history = query_historical_bets(deps.as_ref(), 0, None, Addr::unchecked("addr0001"))
+        .unwrap()
+        .history;
+    assert_eq!(history.len(), 2);
+}
+
+#[test]
+fn test_update_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = proper_instantiate(deps.as_mut()).unwrap();
+
+    match update_config(
+        deps.as_mut(),
+        mock_info("addr0001", &[]),
+        UpdateConfigMsg {
+            treasury_tax_percent: Some(2),
+            ..empty_update_config_msg()
+        },
+    )
+    .unwrap_err()
+    {
+        ContractError::Unauthorized {} => assert_eq!(true, true,),
+        _ => panic!("no error"),
+    }
+
+    match update_config(
+        deps.as_mut(),
+        mock_info("addr0000", &[]),
+        UpdateConfigMsg {
+            bet_revealer_liquidation_percent: Some(91),
+            ..empty_update_config_msg()
+        },
+    )
+    .unwrap_err()
+    {
+        ContractError::ValidationErr { message } => assert_eq!(
+            message,
+            "Config validation: liquidation percent must be equal to 100".to_string()
+        ),
+        _ => panic!("no error"),
+    }
+
+    let _ = update_config(
+        deps.as_mut(),
+        mock_info("addr0000", &[]),
+        UpdateConfigMsg {
+            treasury_tax_percent: Some(2),
+            blocks_for_revealer_liquidation: Some(30),
+            ..empty_update_config_msg()
+        },
+    )
+    .unwrap();
+
+    let config = load_config(&deps.storage).unwrap();
+    assert_eq!(config.treasury_tax_percent, 2);
+    assert_eq!(config.blocks_for_revealer_liquidation, 30);
+}
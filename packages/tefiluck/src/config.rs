@@ -0,0 +1,116 @@
+use cosmwasm_std::{StdError, StdResult, Uint128};
+use cw0::Duration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateCoinLimitMsg {
+    pub denom: String,
+    pub min_amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CoinLimit {
+    pub denom: String,
+    pub min_amount: Uint128,
+}
+
+impl From<InstantiateCoinLimitMsg> for CoinLimit {
+    fn from(coin: InstantiateCoinLimitMsg) -> Self {
+        CoinLimit {
+            denom: coin.denom,
+            min_amount: Uint128::new(coin.min_amount.into()),
+        }
+    }
+}
+
+// settings every game keeps in its config and checks the same way
+pub struct CommonConfig<'a> {
+    pub min_bet_amounts: &'a [CoinLimit],
+    pub treasury_tax_percent: u8,
+    // shares of a liquidated pot going to the wronged player, the liquidator and the treasury
+    pub liquidation_percents: [u8; 3],
+    pub min_blocks_until_liquidation: u64,
+    pub max_blocks_until_liquidation: u64,
+    pub min_seconds_until_liquidation: u64,
+    pub max_seconds_until_liquidation: u64,
+    pub historical_bets_max_storage_size: u64,
+    pub historical_bets_clear_batch_size: u64,
+}
+
+impl<'a> CommonConfig<'a> {
+    // returns the message of the first failed check
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_bet_amounts.is_empty() {
+            return Err("Config validation: min_bet_amounts must be a non-empty list".to_string());
+        }
+
+        if self.treasury_tax_percent > 10 {
+            return Err("Config validation: treasury percent must be less than 10".to_string());
+        }
+
+        let liquidation_percent: u16 = self.liquidation_percents.iter().map(|p| *p as u16).sum();
+        if liquidation_percent != 100 {
+            return Err("Config validation: liquidation percent must be equal to 100".to_string());
+        }
+
+        if self.min_blocks_until_liquidation > self.max_blocks_until_liquidation {
+            return Err("Config validation: min_blocks_until_liquidation must be less than max_blocks_until_liquidation".to_string());
+        }
+
+        if self.min_seconds_until_liquidation > self.max_seconds_until_liquidation {
+            return Err("Config validation: min_seconds_until_liquidation must be less than max_seconds_until_liquidation".to_string());
+        }
+
+        if self.historical_bets_clear_batch_size == 0
+            || self.historical_bets_clear_batch_size > self.historical_bets_max_storage_size
+        {
+            return Err("Config validation: historical_bets_clear_batch_size must be between 1 and historical_bets_max_storage_size".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_until_liquidation(&self, until_liquidation: &Duration) -> StdResult<()> {
+        match *until_liquidation {
+            Duration::Height(blocks) => {
+                if self.min_blocks_until_liquidation > blocks {
+                    return Err(StdError::generic_err(
+                        "blocks_before_liquidation must be higher than min allowed value",
+                    ));
+                }
+
+                if self.max_blocks_until_liquidation < blocks {
+                    return Err(StdError::generic_err(
+                        "blocks_before_liquidation must be less than max allowed value",
+                    ));
+                }
+            }
+            Duration::Time(seconds) => {
+                if self.min_seconds_until_liquidation > seconds {
+                    return Err(StdError::generic_err(
+                        "seconds_before_liquidation must be higher than min allowed value",
+                    ));
+                }
+
+                if self.max_seconds_until_liquidation < seconds {
+                    return Err(StdError::generic_err(
+                        "seconds_before_liquidation must be less than max allowed value",
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // bets are accepted only in denoms with a min amount
+    pub fn coin_limit(&self, denom: &str) -> StdResult<&'a CoinLimit> {
+        match self.min_bet_amounts.iter().find(|l| l.denom == denom) {
+            Some(l) => Ok(l),
+            None => Err(StdError::generic_err(
+                "coin limits for provided asset not found",
+            )),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use bls12_381::{
+    hash_to_curve::{ExpandMsgXmd, HashToCurve},
+    G1Affine, G2Affine, G2Projective, Gt,
+};
+use cosmwasm_std::{StdError, StdResult};
+use pairing::MultiMillerLoop;
+use sha2::Digest;
+use std::convert::TryInto;
+
+const DOMAIN: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+// chained drand beacons sign sha256(previous_signature || round)
+fn round_message(round: u64, previous_signature: &[u8]) -> Vec<u8> {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(previous_signature);
+    hasher.update(round.to_be_bytes());
+    hasher.finalize().to_vec()
+}
+
+fn decode_hex(name: &str, input: &str) -> StdResult<Vec<u8>> {
+    hex::decode(input).map_err(|_| StdError::generic_err(format!("{} must be a hex string", name)))
+}
+
+fn decode_g1(input: &[u8]) -> StdResult<G1Affine> {
+    let bytes: [u8; 48] = input
+        .try_into()
+        .map_err(|_| StdError::generic_err("drand public key must be 48 bytes long"))?;
+    Option::from(G1Affine::from_compressed(&bytes))
+        .ok_or_else(|| StdError::generic_err("drand public key is not a valid G1 point"))
+}
+
+fn decode_g2(input: &[u8]) -> StdResult<G2Affine> {
+    let bytes: [u8; 96] = input
+        .try_into()
+        .map_err(|_| StdError::generic_err("beacon signature must be 96 bytes long"))?;
+    Option::from(G2Affine::from_compressed(&bytes))
+        .ok_or_else(|| StdError::generic_err("beacon signature is not a valid G2 point"))
+}
+
+pub fn is_valid_public_key(public_key: &str) -> bool {
+    decode_hex("public_key", public_key)
+        .and_then(|key| decode_g1(&key))
+        .is_ok()
+}
+
+// checks a hex encoded beacon of the drand chained scheme against group public key
+pub fn verify_beacon(
+    public_key: &str,
+    round: u64,
+    previous_signature: &str,
+    signature: &str,
+) -> StdResult<bool> {
+    let public_key = decode_g1(&decode_hex("public_key", public_key)?)?;
+    let signature = decode_g2(&decode_hex("signature", signature)?)?;
+    let previous_signature = decode_hex("previous_signature", previous_signature)?;
+
+    let message = round_message(round, &previous_signature);
+    let hashed: G2Affine =
+        <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(&message, DOMAIN)
+            .into();
+
+    // e(pk, H(m)) == e(g1, sig) <=> e(-pk, H(m)) * e(g1, sig) == 1
+    let result = bls12_381::Bls12::multi_miller_loop(&[
+        (&-public_key, &hashed.into()),
+        (&G1Affine::generator(), &signature.into()),
+    ])
+    .final_exponentiation();
+
+    Ok(result == Gt::identity())
+}
+
+// beacon randomness is the sha256 of its signature
+pub fn beacon_randomness(signature: &str) -> StdResult<[u8; 32]> {
+    let signature = decode_hex("signature", signature)?;
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(signature);
+    Ok(hasher.finalize().into())
+}
@@ -0,0 +1,197 @@
+use cosmwasm_std::{Order, StdError, StdResult, Storage};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex, U64Key};
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+static HISTORICAL_BETS_STATE: Item<HistoricalBetsState> = Item::new("historical_bets_state");
+
+// completed bet of any game, the fields are the keys of the history indexes
+pub trait HistoricalRecord: Serialize + DeserializeOwned + Clone {
+    fn owner(&self) -> &str;
+    fn responder(&self) -> Option<&str>;
+    fn liquidator(&self) -> Option<&str>;
+    fn denom(&self) -> &str;
+}
+
+pub struct HistoricalBetIndexes<'a, T>
+where
+    T: HistoricalRecord,
+{
+    pub owner: MultiIndex<'a, (Vec<u8>, Vec<u8>), T>,
+    pub responder: MultiIndex<'a, (Vec<u8>, Vec<u8>), T>,
+    pub liquidator: MultiIndex<'a, (Vec<u8>, Vec<u8>), T>,
+    pub denom: MultiIndex<'a, (Vec<u8>, Vec<u8>), T>,
+}
+
+impl<'a, T> IndexList<T> for HistoricalBetIndexes<'a, T>
+where
+    T: HistoricalRecord,
+{
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<T>> + '_> {
+        let v: Vec<&dyn Index<T>> =
+            vec![&self.owner, &self.responder, &self.liquidator, &self.denom];
+        Box::new(v.into_iter())
+    }
+}
+
+// historical bets are keyed by an ever-increasing sequence number,
+// so the oldest entries are always at the beginning of the map
+pub fn historical_bets<'a, T>() -> IndexedMap<'a, U64Key, T, HistoricalBetIndexes<'a, T>>
+where
+    T: HistoricalRecord,
+{
+    let indexes = HistoricalBetIndexes {
+        owner: MultiIndex::new(
+            |bet: &T, pk| (bet.owner().as_bytes().to_vec(), pk),
+            "historical_bets",
+            "historical_bets__owner",
+        ),
+        responder: MultiIndex::new(
+            |bet: &T, pk| (bet.responder().unwrap_or_default().as_bytes().to_vec(), pk),
+            "historical_bets",
+            "historical_bets__responder",
+        ),
+        liquidator: MultiIndex::new(
+            |bet: &T, pk| (bet.liquidator().unwrap_or_default().as_bytes().to_vec(), pk),
+            "historical_bets",
+            "historical_bets__liquidator",
+        ),
+        denom: MultiIndex::new(
+            |bet: &T, pk| (bet.denom().as_bytes().to_vec(), pk),
+            "historical_bets",
+            "historical_bets__denom",
+        ),
+    };
+
+    IndexedMap::new("historical_bets", indexes)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct HistoricalBetsState {
+    pub first_seq: u64,
+    pub next_seq: u64,
+}
+
+impl HistoricalBetsState {
+    pub fn len(&self) -> u64 {
+        self.next_seq - self.first_seq
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub fn load_historical_bets_state(storage: &dyn Storage) -> StdResult<HistoricalBetsState> {
+    HISTORICAL_BETS_STATE
+        .may_load(storage)
+        .map(|res| res.unwrap_or_default())
+}
+
+pub fn append_historical_bet<T>(storage: &mut dyn Storage, bet: &T) -> StdResult<u64>
+where
+    T: HistoricalRecord,
+{
+    let mut state = load_historical_bets_state(storage)?;
+    let seq = state.next_seq;
+
+    historical_bets().save(storage, U64Key::new(seq), bet)?;
+
+    state.next_seq = match seq.checked_add(1) {
+        Some(s) => s,
+        None => return Err(StdError::generic_err("historical bets sequence overflow")),
+    };
+    HISTORICAL_BETS_STATE.save(storage, &state)?;
+
+    Ok(seq)
+}
+
+pub fn load_historical_bet<T>(storage: &dyn Storage, seq: u64) -> StdResult<T>
+where
+    T: HistoricalRecord,
+{
+    historical_bets().load(storage, U64Key::new(seq))
+}
+
+// removes at most batch_size of the oldest historical bets and returns them with their sequence
+// numbers, so the game can drop whatever else it keeps about them
+pub fn remove_oldest_historical_bets<T>(
+    storage: &mut dyn Storage,
+    batch_size: u64,
+) -> StdResult<Vec<(u64, T)>>
+where
+    T: HistoricalRecord,
+{
+    let mut state = load_historical_bets_state(storage)?;
+    let last_seq = state.first_seq + batch_size.min(state.len());
+
+    let mut removed = vec![];
+    for seq in state.first_seq..last_seq {
+        let bet: T = load_historical_bet(storage, seq)?;
+        historical_bets::<T>().remove(storage, U64Key::new(seq))?;
+        removed.push((seq, bet));
+    }
+
+    state.first_seq = last_seq;
+    HISTORICAL_BETS_STATE.save(storage, &state)?;
+
+    Ok(removed)
+}
+
+// keeps the history within max_storage_size by clearing the oldest bets in batches,
+// returns the sequence number of the stored bet and the removed ones
+pub fn save_historical_bet<T>(
+    storage: &mut dyn Storage,
+    max_storage_size: u64,
+    clear_batch_size: u64,
+    bet: &T,
+) -> StdResult<(u64, Vec<(u64, T)>)>
+where
+    T: HistoricalRecord,
+{
+    let removed = if load_historical_bets_state(storage)?.len() >= max_storage_size {
+        remove_oldest_historical_bets(storage, clear_batch_size)?
+    } else {
+        vec![]
+    };
+
+    let seq = append_historical_bet(storage, bet)?;
+
+    Ok((seq, removed))
+}
+
+// bets where the address took part as owner, responder or liquidator, newest first
+pub fn read_historical_bets_by_addr<T>(
+    storage: &dyn Storage,
+    addr: &str,
+    skip: usize,
+    limit: usize,
+) -> StdResult<Vec<T>>
+where
+    T: HistoricalRecord,
+{
+    let bets = historical_bets::<T>();
+    let prefix = addr.as_bytes().to_vec();
+
+    let mut keys: Vec<Vec<u8>> = vec![&bets.idx.owner, &bets.idx.responder, &bets.idx.liquidator]
+        .into_iter()
+        .flat_map(|index| {
+            index
+                .prefix(prefix.clone())
+                .keys(storage, None, None, Order::Ascending)
+                .collect::<Vec<Vec<u8>>>()
+        })
+        .collect();
+
+    // big endian keys sort the same way as sequence numbers,
+    // the same bet may be in several indexes so duplicates are dropped
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .rev()
+        .skip(skip)
+        .take(limit)
+        .map(|k| bets.load(storage, U64Key::from(k)))
+        .collect()
+}
@@ -1,9 +1,13 @@
 pub mod asset;
+pub mod config;
+pub mod drand;
 pub mod hash;
+pub mod history;
+pub mod payout;
 pub mod querier;
 
 #[cfg(test)]
 mod testing;
 
-#[cfg(test)]
-mod mock_querier;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod mock_querier;
@@ -1,13 +1,27 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, from_slice, to_binary, Coin, ContractResult, Decimal, OwnedDeps, Querier,
-    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+    from_binary, from_slice, to_binary, Addr, BlockInfo, Coin, ContractInfo, ContractResult,
+    Decimal, Env, OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult,
+    Timestamp, Uint128, WasmQuery,
 };
 use std::collections::HashMap;
 
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
 
+pub fn mock_env_custom(height: u64) -> Env {
+    Env {
+        block: BlockInfo {
+            height,
+            time: Timestamp::from_nanos(1_571_797_419_879_305_533),
+            chain_id: "cosmos-testnet-14002".to_string(),
+        },
+        contract: ContractInfo {
+            address: Addr::unchecked(MOCK_CONTRACT_ADDR),
+        },
+    }
+}
+
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
 pub fn mock_dependencies(
@@ -0,0 +1,51 @@
+use cosmwasm_std::{Addr, CosmosMsg, QuerierWrapper, StdResult, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::Asset;
+
+// roles of all games, each game pays only to the ones it has
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutRole {
+    Winner,
+    Treasury,
+    Responder,
+    Liquidator,
+    Owner,
+    Relayer,
+    // winnings kept in the contract as stake of a rematch
+    Rematch,
+    // part of treasury fee kept in the contract until the referrer claims it
+    Referrer,
+    Jackpot,
+}
+
+// gross_amount is what the contract sent, net_amount is what recipient got after terra tax
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Payout {
+    pub recipient: String,
+    pub role: PayoutRole,
+    pub gross_amount: Uint128,
+    pub net_amount: Uint128,
+}
+
+pub fn send_payout(
+    querier: &QuerierWrapper,
+    recipient: &Addr,
+    role: PayoutRole,
+    asset: &Asset,
+) -> StdResult<(CosmosMsg, Payout)> {
+    let mut net_amount = asset.clone();
+    let msg = net_amount.into_bank_msg(querier, recipient)?;
+
+    Ok((
+        msg,
+        Payout {
+            recipient: recipient.to_string(),
+            role,
+            gross_amount: asset.amount,
+            net_amount: net_amount.amount,
+        },
+    ))
+}
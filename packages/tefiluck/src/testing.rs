@@ -1,4 +1,5 @@
 use crate::asset::Asset;
+use crate::drand::{beacon_randomness, verify_beacon};
 use crate::hash::calculate_sha256;
 use crate::mock_querier::mock_dependencies;
 use crate::querier::query_native_token_balance;
@@ -16,6 +17,38 @@ fn test_hash() {
     );
 }
 
+// drand mainnet round 72785
+const DRAND_PUBLIC_KEY: &str = "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31";
+const DRAND_PREVIOUS_SIGNATURE: &str = "a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747";
+const DRAND_SIGNATURE: &str = "82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42";
+
+#[test]
+fn test_verify_beacon() {
+    assert!(verify_beacon(
+        DRAND_PUBLIC_KEY,
+        72785,
+        DRAND_PREVIOUS_SIGNATURE,
+        DRAND_SIGNATURE
+    )
+    .unwrap());
+
+    // same signature does not match another round
+    assert!(!verify_beacon(
+        DRAND_PUBLIC_KEY,
+        72786,
+        DRAND_PREVIOUS_SIGNATURE,
+        DRAND_SIGNATURE
+    )
+    .unwrap());
+
+    assert!(verify_beacon(DRAND_PUBLIC_KEY, 72785, DRAND_PREVIOUS_SIGNATURE, "beef").is_err());
+
+    assert_eq!(
+        hex::encode(beacon_randomness(DRAND_SIGNATURE).unwrap()),
+        "8b676484b5fb1f37f9ec5c413d7d29883504e5b669f604a1ce68b3388e9ae3d9".to_string(),
+    );
+}
+
 #[test]
 fn test_balance_querier() {
     let deps = mock_dependencies(&[Coin {